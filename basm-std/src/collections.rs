@@ -12,3 +12,15 @@ pub use fenwick::*;
 
 pub mod segment_tree;
 pub use segment_tree::*;
+
+pub mod implicit_treap;
+pub use implicit_treap::*;
+
+pub mod persistent_segment_tree;
+pub use persistent_segment_tree::*;
+
+pub mod sparse_table;
+pub use sparse_table::*;
+
+pub mod li_chao;
+pub use li_chao::*;
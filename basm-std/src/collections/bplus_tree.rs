@@ -1,12 +1,12 @@
 #![allow(clippy::needless_range_loop)]
 
-use alloc::{boxed::Box, collections::VecDeque};
+use alloc::{boxed::Box, collections::VecDeque, vec::Vec};
 use core::clone::CloneToUninit;
 use core::cmp::Ordering::{self, Equal, Greater, Less};
 use core::marker::PhantomData;
 use core::mem::{ManuallyDrop, MaybeUninit};
 use core::ops::Bound::*;
-use core::ops::RangeBounds;
+use core::ops::{Bound, Deref, DerefMut, RangeBounds};
 
 // The degree of BPTree.
 // (degree: the minimum number of children in internal node)
@@ -97,10 +97,27 @@ where
     // The lazy op u sits above all children of the present node.
     // It is not present in LeafNode.
     lazies: [MaybeUninit<U>; 2 * T],
+    // sizes[i] stores the number of keys in the subtree children[i]
+    sizes: [usize; 2 * T],
     _v: PhantomData<V>,
     _f: PhantomData<F>,
 }
 
+// An entry detached from an InternalNode, carrying everything needed to reinsert it elsewhere.
+struct InternalEntry<K, V, U, F>
+where
+    K: Ord + Clone,
+    V: Clone,
+    U: Clone,
+    F: LazyOp<V, U>,
+{
+    child: ChildPtr<K, V, U, F>,
+    key: K,
+    value: V,
+    lazy: Option<U>,
+    size: usize,
+}
+
 struct LeafNode<K, V, U, F>
 where
     K: Ord + Clone,
@@ -127,13 +144,146 @@ where
     leaf_node: ManuallyDropOptionBox<LeafNode<K, V, U, F>>,
 }
 
-pub struct PeekMutPoint<K, V, U>
+/// A mutable reference to the value of a single key, returned by `BPTreeMapLazy::get_mut`.
+/// The aggregate values along the path to the key are recomputed when it is dropped.
+pub struct PeekMutPoint<'a, K, V, U, F>
 where
     K: Ord + Clone,
+    V: Clone,
+    U: Clone,
+    F: LazyOp<V, U>,
 {
-    _k: PhantomData<K>,
-    _v: PhantomData<V>,
-    _u: PhantomData<U>,
+    tree: &'a mut BPTreeMapLazy<K, V, U, F>,
+    value: *mut V,
+    // [node_ptr, child_index] for each InternalNode on the path from the root
+    stack: [MaybeUninit<[usize; 2]>; MAX_STACK_DEPTH],
+}
+
+impl<K, V, U, F> Deref for PeekMutPoint<'_, K, V, U, F>
+where
+    K: Ord + Clone,
+    V: Clone,
+    U: Clone,
+    F: LazyOp<V, U>,
+{
+    type Target = V;
+    fn deref(&self) -> &V {
+        unsafe { &*self.value }
+    }
+}
+
+impl<K, V, U, F> DerefMut for PeekMutPoint<'_, K, V, U, F>
+where
+    K: Ord + Clone,
+    V: Clone,
+    U: Clone,
+    F: LazyOp<V, U>,
+{
+    fn deref_mut(&mut self) -> &mut V {
+        unsafe { &mut *self.value }
+    }
+}
+
+impl<K, V, U, F> Drop for PeekMutPoint<'_, K, V, U, F>
+where
+    K: Ord + Clone,
+    V: Clone,
+    U: Clone,
+    F: LazyOp<V, U>,
+{
+    fn drop(&mut self) {
+        let depth = self.tree.depth;
+        unsafe {
+            for d in (0..depth - 1).rev() {
+                let [ptr, i] = self.stack[d].assume_init();
+                (*(ptr as *mut InternalNode<K, V, U, F>)).pull_at(i, depth - 1 - d);
+            }
+            self.tree.value = Some(self.tree.root.aggregate(depth - 1));
+        }
+    }
+}
+
+/// An iterator over the entries of a `BPTreeMapLazy` in ascending order of keys,
+/// returned by `BPTreeMapLazy::iter` and `BPTreeMapLazy::range`.
+///
+/// Since the pending lazy ops are not pushed down while iterating,
+/// each value is yielded by value with the lazy ops above it applied.
+pub struct BPTreeIter<'a, K, V, U, F>
+where
+    K: Ord + Clone,
+    V: Clone,
+    U: Clone,
+    F: LazyOp<V, U>,
+{
+    depth: usize,
+    // (node, index of the current child, composed lazy op above the node) on the path from the root
+    #[allow(clippy::type_complexity)]
+    stack: Vec<(&'a InternalNode<K, V, U, F>, usize, Option<U>)>,
+    // (leaf, index of the next entry, composed lazy op above the leaf)
+    #[allow(clippy::type_complexity)]
+    leaf: Option<(&'a LeafNode<K, V, U, F>, usize, Option<U>)>,
+    end: Bound<K>,
+}
+
+impl<'a, K, V, U, F> Iterator for BPTreeIter<'a, K, V, U, F>
+where
+    K: Ord + Clone,
+    V: Clone,
+    U: Clone,
+    F: LazyOp<V, U>,
+{
+    type Item = (&'a K, V);
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (leaf, j, u) = self.leaf.as_mut()?;
+            if *j < leaf.count {
+                let (leaf, key) = (*leaf, unsafe { leaf.keys[*j].assume_init_ref() });
+                let in_range = match &self.end {
+                    Included(e) => key <= e,
+                    Excluded(e) => key < e,
+                    Unbounded => true,
+                };
+                if !in_range {
+                    self.leaf = None;
+                    return None;
+                }
+                let v = unsafe { leaf.values[*j].assume_init_ref() };
+                let v = match u {
+                    Some(u) => F::apply(u, v),
+                    None => v.clone(),
+                };
+                *j += 1;
+                return Some((key, v));
+            }
+            // Go up until there is a next sibling, then go down to its leftmost leaf
+            loop {
+                match self.stack.last_mut() {
+                    None => {
+                        self.leaf = None;
+                        return None;
+                    }
+                    Some((x, i, _)) if *i + 1 < x.count => {
+                        *i += 1;
+                        break;
+                    }
+                    _ => {
+                        self.stack.pop();
+                    }
+                }
+            }
+            let (x, i, u) = self.stack.last().unwrap();
+            let mut u = F::compose_option(u, &x.read_lazy(*i));
+            let mut cur = &x.children[*i];
+            while self.stack.len() + 1 < self.depth {
+                let y = unsafe { cur.as_internal_node_ref() };
+                let u_next = F::compose_option(&u, &y.read_lazy(0));
+                self.stack.push((y, 0, u));
+                u = u_next;
+                cur = &y.children[0];
+            }
+            self.leaf = Some((unsafe { cur.as_leaf_node_ref() }, 0, u));
+        }
+    }
 }
 
 pub struct PeekMutRange<'a, K, V, U, F>
@@ -316,6 +466,7 @@ where
             keys: [const { MaybeUninit::uninit() }; 2 * T],
             values: [const { MaybeUninit::uninit() }; 2 * T],
             lazies: [const { MaybeUninit::uninit() }; 2 * T],
+            sizes: [0; 2 * T],
             _v: PhantomData,
             _f: PhantomData,
         }
@@ -374,7 +525,9 @@ where
                     x.children[i].drop_by_depth(depth - 1);
                     x.keys[i].assume_init_drop();
                     x.values[i].assume_init_drop();
-                    x.lazies[i].assume_init_drop();
+                    if x.lazy_mask & (1 << i) != 0 {
+                        x.lazies[i].assume_init_drop();
+                    }
                 }
                 self.internal_node = ManuallyDrop::new(None);
             }
@@ -427,6 +580,93 @@ where
             }
         }
     }
+    /// Returns the number of keys in the subtree.
+    fn len(&self, level: usize) -> usize {
+        unsafe {
+            if level > 0 {
+                let x = self.as_internal_node_ref();
+                x.sizes[..x.count].iter().sum()
+            } else {
+                self.as_leaf_node_ref().count
+            }
+        }
+    }
+    /// Returns the number of entries in the node itself.
+    fn count(&self, level: usize) -> usize {
+        unsafe {
+            if level > 0 {
+                self.as_internal_node_ref().count
+            } else {
+                self.as_leaf_node_ref().count
+            }
+        }
+    }
+    fn push(&mut self, level: usize, u: &Option<U>) {
+        unsafe {
+            if level > 0 {
+                self.as_internal_node_mut().push(u);
+            } else {
+                self.as_leaf_node_mut().push(u);
+            }
+        }
+    }
+    /// Moves the last `k` entries of `self` to the front of `right`.
+    /// Both nodes must be at `level`, with no lazy op pending above them.
+    fn move_to_right(&mut self, right: &mut Self, level: usize, k: usize) {
+        unsafe {
+            if level > 0 {
+                let (x, y) = (self.as_internal_node_mut(), right.as_internal_node_mut());
+                for _ in 0..k {
+                    y.put_at(0, x.take_at(x.count - 1));
+                }
+            } else {
+                let (x, y) = (self.as_leaf_node_mut(), right.as_leaf_node_mut());
+                for _ in 0..k {
+                    let (key, value) = x.take_at(x.count - 1);
+                    y.put_at(0, key, value);
+                }
+            }
+        }
+    }
+    /// Moves the first `k` entries of `right` to the back of `self`.
+    /// Both nodes must be at `level`, with no lazy op pending above them.
+    fn move_from_right(&mut self, right: &mut Self, level: usize, k: usize) {
+        unsafe {
+            if level > 0 {
+                let (x, y) = (self.as_internal_node_mut(), right.as_internal_node_mut());
+                for _ in 0..k {
+                    x.put_at(x.count, y.take_at(0));
+                }
+            } else {
+                let (x, y) = (self.as_leaf_node_mut(), right.as_leaf_node_mut());
+                for _ in 0..k {
+                    let (key, value) = y.take_at(0);
+                    x.put_at(x.count, key, value);
+                }
+            }
+        }
+    }
+    /// Restores the occupancy of two adjacent nodes at `level`, with no lazy op pending above them.
+    /// If they fit in a single node, every entry is moved into `self` (if `into_left`) or `right`,
+    /// and `true` is returned. Otherwise, entries are redistributed so that both have at least `T` entries.
+    fn rebalance(&mut self, right: &mut Self, level: usize, into_left: bool) -> bool {
+        let (cl, cr) = (self.count(level), right.count(level));
+        if cl + cr <= 2 * T {
+            if into_left {
+                self.move_from_right(right, level, cr);
+            } else {
+                self.move_to_right(right, level, cl);
+            }
+            true
+        } else {
+            if cl < T {
+                self.move_from_right(right, level, T - cl);
+            } else if cr < T {
+                self.move_to_right(right, level, T - cr);
+            }
+            false
+        }
+    }
 }
 
 impl<K, V, U, F> InternalNode<K, V, U, F>
@@ -448,10 +688,12 @@ where
                 keys: [const { MaybeUninit::uninit() }; 2 * T],
                 values: [const { MaybeUninit::uninit() }; 2 * T],
                 lazies: [const { MaybeUninit::uninit() }; 2 * T],
+                sizes: [0; 2 * T],
                 _v: PhantomData,
                 _f: PhantomData,
             });
             self.count = T;
+            right_node.sizes[..T].copy_from_slice(&self.sizes[T..]);
             self.lazy_mask &= (1 << T) - 1;
 
             unsafe {
@@ -485,9 +727,11 @@ where
             self.keys.swap(j, j + 1);
             self.values.swap(j, j + 1);
             self.lazies.swap(j, j + 1);
+            self.sizes.swap(j, j + 1);
         }
         self.keys[i] = MaybeUninit::new(child_ptr.least_key(level - 1));
         self.values[i] = MaybeUninit::new(child_ptr.aggregate(level - 1));
+        self.sizes[i] = child_ptr.len(level - 1);
         self.children[i] = child_ptr;
         self.count += 1;
         self.lazy_mask =
@@ -515,6 +759,7 @@ where
         unsafe {
             debug_assert!(level >= 1);
             let v = self.children[i].aggregate(level - 1);
+            self.sizes[i] = self.children[i].len(level - 1);
             self.keys[i].assume_init_drop();
             self.keys[i] = MaybeUninit::new(self.children[i].least_key(level - 1));
             self.values[i].assume_init_drop();
@@ -525,6 +770,68 @@ where
             }
         }
     }
+    /// Pulls keys and values from all children.
+    fn pull_all(&mut self, level: usize) {
+        for i in 0..self.count {
+            self.pull_at(i, level);
+        }
+    }
+    /// Removes the entry at position `i`, and left-shifts the elements after it by one.
+    fn take_at(&mut self, i: usize) -> InternalEntry<K, V, U, F> {
+        debug_assert!(i < self.count);
+        let lazy = self.pop_lazy(i);
+        let out = unsafe {
+            InternalEntry {
+                child: core::mem::take(&mut self.children[i]),
+                key: self.keys[i].assume_init_read(),
+                value: self.values[i].assume_init_read(),
+                lazy,
+                size: self.sizes[i],
+            }
+        };
+        for j in i + 1..self.count {
+            self.children.swap(j - 1, j);
+            self.keys.swap(j - 1, j);
+            self.values.swap(j - 1, j);
+            self.lazies.swap(j - 1, j);
+            self.sizes.swap(j - 1, j);
+        }
+        self.count -= 1;
+        self.lazy_mask =
+            (self.lazy_mask & ((1 << i) - 1)) | ((self.lazy_mask >> 1) & !((1 << i) - 1));
+        out
+    }
+    /// Inserts a detached entry at position `i`, and right-shift existing elements in i and afterwards by one.
+    /// If the node is already full, this function will panic.
+    fn put_at(&mut self, i: usize, entry: InternalEntry<K, V, U, F>) {
+        assert!(self.count < 2 * T);
+        for j in (i..self.count).rev() {
+            self.children.swap(j, j + 1);
+            self.keys.swap(j, j + 1);
+            self.values.swap(j, j + 1);
+            self.lazies.swap(j, j + 1);
+            self.sizes.swap(j, j + 1);
+        }
+        self.lazy_mask =
+            (self.lazy_mask & ((1 << i) - 1)) | ((self.lazy_mask & !((1 << i) - 1)) << 1);
+        self.children[i] = entry.child;
+        self.keys[i] = MaybeUninit::new(entry.key);
+        self.values[i] = MaybeUninit::new(entry.value);
+        self.sizes[i] = entry.size;
+        if let Some(u) = entry.lazy {
+            self.lazies[i] = MaybeUninit::new(u);
+            self.lazy_mask |= 1 << i;
+        }
+        self.count += 1;
+    }
+    /// Returns the index of the child whose key range contains `key`.
+    fn find_child(&self, key: &K) -> usize {
+        let mut i = 0;
+        while i + 1 < self.count && unsafe { self.keys[i + 1].assume_init_ref() } <= key {
+            i += 1;
+        }
+        i
+    }
     /// Returns the aggregate value of the current node.
     fn aggregate(&self) -> V {
         let values = unsafe { self.values.assume_init_ref() };
@@ -619,6 +926,7 @@ where
             let ptr = iter.next().unwrap();
             out.keys[i] = MaybeUninit::new(ptr.least_key(level - 1));
             out.values[i] = MaybeUninit::new(ptr.aggregate(level - 1));
+            out.sizes[i] = ptr.len(level - 1);
             out.children[i] = ptr;
         }
         out.count = n;
@@ -690,6 +998,34 @@ where
         self.count += 1;
         None
     }
+    /// Removes the entry at position `i`, and left-shifts the elements after it by one.
+    fn take_at(&mut self, i: usize) -> (K, V) {
+        debug_assert!(i < self.count);
+        let out = unsafe {
+            (
+                self.keys[i].assume_init_read(),
+                self.values[i].assume_init_read(),
+            )
+        };
+        for j in i + 1..self.count {
+            self.keys.swap(j - 1, j);
+            self.values.swap(j - 1, j);
+        }
+        self.count -= 1;
+        out
+    }
+    /// Inserts an entry at position `i`, and right-shift existing elements in i and afterwards by one.
+    /// If the node is already full, this function will panic.
+    fn put_at(&mut self, i: usize, key: K, value: V) {
+        assert!(self.count < 2 * T);
+        for j in (i..self.count).rev() {
+            self.keys.swap(j, j + 1);
+            self.values.swap(j, j + 1);
+        }
+        self.keys[i] = MaybeUninit::new(key);
+        self.values[i] = MaybeUninit::new(value);
+        self.count += 1;
+    }
     fn push(&mut self, u: &Option<U>) {
         if let Some(u) = u {
             for i in 0..self.count {
@@ -1008,14 +1344,403 @@ where
     pub fn remove(&mut self, key: &K) -> Option<V> {
         self.remove_range(key..=key)
     }
-    pub fn remove_range<R: RangeBounds<K>>(&mut self, _range: R) -> Option<V> {
-        todo!()
+    /// Removes every entry whose key falls in `range`, and returns the aggregate value of the removed entries.
+    /// If nothing is removed, `None` is returned.
+    ///
+    /// This takes `O(lg n)` time plus the time to drop the removed entries.
+    pub fn remove_range<R: RangeBounds<K>>(&mut self, range: R) -> Option<V> {
+        let mut mid = match range.start_bound() {
+            Included(k) => self.split_by(|x| x >= k),
+            Excluded(k) => self.split_by(|x| x > k),
+            Unbounded => core::mem::take(self),
+        };
+        let right = match range.end_bound() {
+            Included(k) => mid.split_by(|x| x > k),
+            Excluded(k) => mid.split_by(|x| x >= k),
+            Unbounded => Self::new(),
+        };
+        self.join(right);
+        mid.value.take()
     }
     pub fn get(&self, key: &K) -> Option<V> {
         self.get_range(key..=key)
     }
-    pub fn get_mut(&mut self, _key: &K) -> Option<PeekMutPoint<K, V, U>> {
-        None
+    /// Returns a mutable reference to the value of `key`, wrapped in `PeekMutPoint`.
+    /// The aggregate values are updated when the returned `PeekMutPoint` is dropped.
+    pub fn get_mut(&mut self, key: &K) -> Option<PeekMutPoint<'_, K, V, U, F>> {
+        if self.depth == 0 {
+            return None;
+        }
+        let mut stack = [const { MaybeUninit::uninit() }; MAX_STACK_DEPTH];
+        let mut u = self.lazy.take();
+        let mut cur = &mut self.root;
+        unsafe {
+            for d in 0..self.depth - 1 {
+                let x = cur.as_internal_node_mut();
+                x.push(&u);
+                let i = x.find_child(key);
+                u = x.pop_lazy(i);
+                stack[d] = MaybeUninit::new([x as *mut _ as usize, i]);
+                cur = &mut x.children[i];
+            }
+            let leaf = cur.as_leaf_node_mut();
+            leaf.push(&u);
+            let j = (0..leaf.count).find(|&j| leaf.keys[j].assume_init_ref() == key)?;
+            let value = leaf.values[j].as_mut_ptr();
+            Some(PeekMutPoint {
+                tree: self,
+                value,
+                stack,
+            })
+        }
+    }
+    /// Splits the tree into two at `key`.
+    /// Returns a tree containing the entries whose keys are greater than or equal to `key`,
+    /// leaving the rest in `self`.
+    ///
+    /// This takes `O(lg n)` time. Pending lazy ops and aggregate values are preserved on both sides.
+    pub fn split_off(&mut self, key: &K) -> Self {
+        self.split_by(|x| x >= key)
+    }
+    /// Moves every entry of `other` into `self`, leaving `other` empty.
+    /// Every key in `other` must be greater than every key in `self`; otherwise, this function will panic.
+    ///
+    /// This takes `O(lg n)` time. Pending lazy ops and aggregate values are preserved.
+    pub fn append(&mut self, other: &mut Self) {
+        if let (Some((a, _)), Some((b, _))) = (self.last(), other.first()) {
+            assert!(
+                a < b,
+                "keys of `other` must be greater than those of `self`"
+            );
+        }
+        self.join(core::mem::take(other));
+    }
+    /// Returns the number of entries.
+    pub fn len(&self) -> usize {
+        if self.depth == 0 {
+            0
+        } else {
+            self.root.len(self.depth - 1)
+        }
+    }
+    /// Returns `true` if the map contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.depth == 0
+    }
+    /// Returns an iterator over all entries in ascending order of keys.
+    pub fn iter(&self) -> BPTreeIter<'_, K, V, U, F> {
+        self.range(..)
+    }
+    /// Returns an iterator over the entries whose keys fall in `range`, in ascending order of keys.
+    pub fn range<R: RangeBounds<K>>(&self, range: R) -> BPTreeIter<'_, K, V, U, F> {
+        let mut out = BPTreeIter {
+            depth: self.depth,
+            stack: Vec::new(),
+            leaf: None,
+            end: range.end_bound().cloned(),
+        };
+        if self.depth == 0 {
+            return out;
+        }
+        let before_start = |k: &K| match range.start_bound() {
+            Included(s) => k < s,
+            Excluded(s) => k <= s,
+            Unbounded => false,
+        };
+        let mut u = self.lazy.clone();
+        let mut cur = &self.root;
+        unsafe {
+            for _ in 0..self.depth - 1 {
+                let x = cur.as_internal_node_ref();
+                let mut i = 0;
+                while i + 1 < x.count && before_start(x.keys[i + 1].assume_init_ref()) {
+                    i += 1;
+                }
+                let u_next = F::compose_option(&u, &x.read_lazy(i));
+                out.stack.push((x, i, u));
+                u = u_next;
+                cur = &x.children[i];
+            }
+            let leaf = cur.as_leaf_node_ref();
+            let j = (0..leaf.count)
+                .find(|&j| !before_start(leaf.keys[j].assume_init_ref()))
+                .unwrap_or(leaf.count);
+            out.leaf = Some((leaf, j, u));
+        }
+        out
+    }
+    /// Returns the entry with the least key.
+    pub fn first(&self) -> Option<(&K, V)> {
+        self.iter().next()
+    }
+    /// Returns the entry with the greatest key.
+    pub fn last(&self) -> Option<(&K, V)> {
+        self.kth(self.len().checked_sub(1)?)
+    }
+    /// Returns the entry with the least key greater than or equal to `key`.
+    pub fn lower_bound(&self, key: &K) -> Option<(&K, V)> {
+        self.range(key..).next()
+    }
+    /// Returns the entry with the least key strictly greater than `key`.
+    pub fn upper_bound(&self, key: &K) -> Option<(&K, V)> {
+        self.range((Excluded(key), Unbounded)).next()
+    }
+    /// Returns the `k`-th (0-indexed) entry in ascending order of keys, in `O(lg n)` time.
+    pub fn kth(&self, mut k: usize) -> Option<(&K, V)> {
+        if k >= self.len() {
+            return None;
+        }
+        let mut u = self.lazy.clone();
+        let mut cur = &self.root;
+        unsafe {
+            for _ in 0..self.depth - 1 {
+                let x = cur.as_internal_node_ref();
+                let mut i = 0;
+                while k >= x.sizes[i] {
+                    k -= x.sizes[i];
+                    i += 1;
+                }
+                u = F::compose_option(&u, &x.read_lazy(i));
+                cur = &x.children[i];
+            }
+            let leaf = cur.as_leaf_node_ref();
+            let v = leaf.values[k].assume_init_ref();
+            let v = match &u {
+                Some(u) => F::apply(u, v),
+                None => v.clone(),
+            };
+            Some((leaf.keys[k].assume_init_ref(), v))
+        }
+    }
+    /// Returns the number of keys strictly less than `key`, in `O(lg n)` time.
+    pub fn rank(&self, key: &K) -> usize {
+        if self.depth == 0 {
+            return 0;
+        }
+        let mut out = 0;
+        let mut cur = &self.root;
+        unsafe {
+            for _ in 0..self.depth - 1 {
+                let x = cur.as_internal_node_ref();
+                let mut i = 0;
+                while i + 1 < x.count && x.keys[i + 1].assume_init_ref() < key {
+                    out += x.sizes[i];
+                    i += 1;
+                }
+                cur = &x.children[i];
+            }
+            let leaf = cur.as_leaf_node_ref();
+            out + (0..leaf.count)
+                .take_while(|&j| leaf.keys[j].assume_init_ref() < key)
+                .count()
+        }
+    }
+    /// Creates a tree from a subtree `root` of the given depth, with the lazy op `lazy` pending above it.
+    fn from_root(root: ChildPtr<K, V, U, F>, depth: usize, lazy: Option<U>) -> Self {
+        let value = F::apply_option(&lazy, &Some(root.aggregate(depth - 1)));
+        Self {
+            root,
+            depth,
+            value,
+            lazy,
+            _f: PhantomData,
+        }
+    }
+    /// Creates a tree from the InternalNode `ptr` at `level`, which may have fewer than two children.
+    fn from_node(mut ptr: ChildPtr<K, V, U, F>, level: usize) -> Self {
+        match ptr.count(level) {
+            0 => {
+                unsafe { ptr.drop_by_depth(level) };
+                Self::new()
+            }
+            1 => {
+                let entry = unsafe { ptr.as_internal_node_mut() }.take_at(0);
+                unsafe { ptr.drop_by_depth(level) };
+                Self::from_root(entry.child, level, entry.lazy)
+            }
+            _ => Self::from_root(ptr, level + 1, None),
+        }
+    }
+    /// Detaches the root, leaving the tree empty.
+    fn take_root(&mut self) -> ChildPtr<K, V, U, F> {
+        self.depth = 0;
+        self.value = None;
+        self.lazy = None;
+        core::mem::take(&mut self.root)
+    }
+    /// Pushes the lazy op of the whole tree down into the root node.
+    fn push_root(&mut self) {
+        if self.depth > 0 {
+            let u = self.lazy.take();
+            self.root.push(self.depth - 1, &u);
+        }
+    }
+    /// Splits the subtree `ptr` at `level`, which has no lazy op pending above it,
+    /// into the trees of keys for which `goes_right` is false and true, respectively.
+    /// `goes_right` must be monotone in the key.
+    fn split_node<P: Fn(&K) -> bool>(
+        mut ptr: ChildPtr<K, V, U, F>,
+        level: usize,
+        goes_right: &P,
+    ) -> (Self, Self) {
+        unsafe {
+            if level == 0 {
+                let x = ptr.as_leaf_node_mut();
+                let j = (0..x.count)
+                    .find(|&j| goes_right(x.keys[j].assume_init_ref()))
+                    .unwrap_or(x.count);
+                if j == 0 {
+                    return (Self::new(), Self::from_root(ptr, 1, None));
+                }
+                if j == x.count {
+                    return (Self::from_root(ptr, 1, None), Self::new());
+                }
+                let mut right: Box<LeafNode<K, V, U, F>> = Box::default();
+                while j < x.count {
+                    let (key, value) = x.take_at(j);
+                    right.put_at(right.count, key, value);
+                }
+                let right = ChildPtr {
+                    leaf_node: ManuallyDrop::new(Some(right)),
+                };
+                return (
+                    Self::from_root(ptr, 1, None),
+                    Self::from_root(right, 1, None),
+                );
+            }
+            let x = ptr.as_internal_node_mut();
+            let i = (1..x.count)
+                .find(|&i| goes_right(x.keys[i].assume_init_ref()))
+                .unwrap_or(x.count);
+            if i == 1 && goes_right(x.keys[0].assume_init_ref()) {
+                return (Self::new(), Self::from_root(ptr, level + 1, None));
+            }
+            // children[..i - 1] go left, children[i..] go right, and children[i - 1] is split
+            let mut right: Box<InternalNode<K, V, U, F>> = Box::default();
+            while i < x.count {
+                right.put_at(right.count, x.take_at(i));
+            }
+            let mut mid = x.take_at(i - 1);
+            mid.child.push(level - 1, &mid.lazy);
+            let (mid_left, mid_right) = Self::split_node(mid.child, level - 1, goes_right);
+            let right = ChildPtr {
+                internal_node: ManuallyDrop::new(Some(right)),
+            };
+            let mut left = Self::from_node(ptr, level);
+            left.join(mid_left);
+            let mut right_tree = mid_right;
+            right_tree.join(Self::from_node(right, level));
+            (left, right_tree)
+        }
+    }
+    /// Splits the tree into two, keeping the keys for which `goes_right` is false and
+    /// returning the tree of the other keys. `goes_right` must be monotone in the key.
+    fn split_by<P: Fn(&K) -> bool>(&mut self, goes_right: P) -> Self {
+        if self.depth == 0 {
+            return Self::new();
+        }
+        self.push_root();
+        let depth = self.depth;
+        let (left, right) = Self::split_node(self.take_root(), depth - 1, &goes_right);
+        *self = left;
+        right
+    }
+    /// Concatenates `other` to the right of `self`.
+    /// Every key in `self` must be less than every key in `other`.
+    ///
+    /// This takes `O(|h1 - h2| + 1)` time, where `h1` and `h2` are the depths of the trees.
+    fn join(&mut self, mut other: Self) {
+        if other.depth == 0 {
+            return;
+        }
+        if self.depth == 0 {
+            core::mem::swap(self, &mut other);
+            return;
+        }
+        self.push_root();
+        other.push_root();
+        let left_taller = self.depth >= other.depth;
+        let (mut tall, mut short) = if left_taller {
+            (core::mem::take(self), other)
+        } else {
+            (other, core::mem::take(self))
+        };
+        let level = short.depth - 1;
+        let mut short_root = short.take_root();
+        unsafe {
+            // Go down along the spine of the taller tree to the node at the same level as the other root,
+            // along which we propagate the lazy op.
+            #[allow(clippy::type_complexity)]
+            let mut stack: Vec<(*mut InternalNode<K, V, U, F>, usize)> = Vec::new();
+            let mut cur = &mut tall.root as *mut ChildPtr<K, V, U, F>;
+            for l in (level + 1..tall.depth).rev() {
+                let x = (*cur).as_internal_node_mut();
+                let i = if left_taller { x.count - 1 } else { 0 };
+                let u = x.pop_lazy(i);
+                x.children[i].push(l - 1, &u);
+                stack.push((x as *mut _, i));
+                cur = &mut x.children[i];
+            }
+
+            // Merge the two nodes, or make both of them at least half full
+            let merged = if left_taller {
+                (*cur).rebalance(&mut short_root, level, true)
+            } else {
+                short_root.rebalance(&mut *cur, level, false)
+            };
+            let mut up_ptr = if merged {
+                short_root.drop_by_depth(level);
+                None
+            } else {
+                Some(short_root)
+            };
+
+            // Re-compute the aggregate values up to the root, inserting up_ptr as a sibling
+            // of the node on the spine and splitting nodes as needed.
+            // Only the root of the shorter tree may go to the left; split-off halves always go to the right.
+            let mut up_left = !left_taller;
+            let mut l = level + 1;
+            while let Some((n, i)) = stack.pop() {
+                let n = &mut *n;
+                if let Some(up_ptr_inner) = up_ptr.take() {
+                    let pos = if up_left { i } else { i + 1 };
+                    if let Some(mut right) = n.split_if_full(l) {
+                        let r = right.as_internal_node_mut();
+                        if pos > T {
+                            r.insert_at(pos - T, up_ptr_inner, l);
+                        } else {
+                            n.insert_at(pos, up_ptr_inner, l);
+                        }
+                        r.pull_all(l);
+                        up_ptr = Some(right);
+                        up_left = false;
+                    } else {
+                        n.insert_at(pos, up_ptr_inner, l);
+                    }
+                }
+                n.pull_all(l);
+                l += 1;
+            }
+
+            // Create a new root if needed
+            if let Some(up_ptr_inner) = up_ptr {
+                let mut root_node: Box<InternalNode<K, V, U, F>> = Box::default();
+                let old_root = core::mem::take(&mut tall.root);
+                let (a, b) = if up_left {
+                    (up_ptr_inner, old_root)
+                } else {
+                    (old_root, up_ptr_inner)
+                };
+                root_node.insert_at(0, a, tall.depth);
+                root_node.insert_at(1, b, tall.depth);
+                tall.root = ChildPtr {
+                    internal_node: ManuallyDrop::new(Some(root_node)),
+                };
+                tall.depth += 1;
+            }
+            tall.value = Some(tall.root.aggregate(tall.depth - 1));
+        }
+        *self = tall;
     }
     pub fn get_range<R: RangeBounds<K>>(&self, range: R) -> Option<V> {
         if self.depth == 0 {
@@ -1262,6 +1987,45 @@ where
     pub fn get_range<R: RangeBounds<K>>(&self, range: R) -> Option<V> {
         self.0.get_range(range)
     }
+    pub fn split_off(&mut self, key: &K) -> Self {
+        Self(self.0.split_off(key))
+    }
+    pub fn append(&mut self, other: &mut Self) {
+        self.0.append(&mut other.0);
+    }
+    pub fn get_mut(&mut self, key: &K) -> Option<impl DerefMut<Target = V> + '_> {
+        self.0.get_mut(key)
+    }
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+    pub fn iter(&self) -> impl Iterator<Item = (&K, V)> {
+        self.0.iter()
+    }
+    pub fn range<R: RangeBounds<K>>(&self, range: R) -> impl Iterator<Item = (&K, V)> {
+        self.0.range(range)
+    }
+    pub fn first(&self) -> Option<(&K, V)> {
+        self.0.first()
+    }
+    pub fn last(&self) -> Option<(&K, V)> {
+        self.0.last()
+    }
+    pub fn lower_bound(&self, key: &K) -> Option<(&K, V)> {
+        self.0.lower_bound(key)
+    }
+    pub fn upper_bound(&self, key: &K) -> Option<(&K, V)> {
+        self.0.upper_bound(key)
+    }
+    pub fn kth(&self, k: usize) -> Option<(&K, V)> {
+        self.0.kth(k)
+    }
+    pub fn rank(&self, key: &K) -> usize {
+        self.0.rank(key)
+    }
 }
 
 #[cfg(test)]
@@ -1394,7 +2158,6 @@ mod test {
         }
     }
     #[test]
-    #[ignore]
     fn check_btree_remove() {
         struct F;
         impl LazyOp<(i64, usize), i64> for F {
@@ -1422,6 +2185,188 @@ mod test {
         assert_eq!(Some((10, 2)), bptm.get_range(3..=6));
     }
     #[test]
+    fn check_btree_ordered_map() {
+        struct F;
+        impl LazyOp<(i64, usize), i64> for F {
+            fn binary_op(t1: &(i64, usize), t2: &(i64, usize)) -> (i64, usize) {
+                (t1.0 + t2.0, t1.1 + t2.1)
+            }
+            fn apply(u: &i64, t: &(i64, usize)) -> (i64, usize) {
+                (t.0 + u * t.1 as i64, t.1)
+            }
+            fn compose(u1: &i64, u2: &i64) -> i64 {
+                u1 + u2
+            }
+            fn id_op() -> i64 {
+                0
+            }
+        }
+        use alloc::collections::BTreeMap;
+        use rand::{Rng, SeedableRng};
+        let mut rng = rand::rngs::SmallRng::seed_from_u64(42);
+        let mut bptm = BPTreeMapLazy::<u32, (i64, usize), i64, F>::new();
+        let mut naive = BTreeMap::<u32, i64>::new();
+        for it in 0..20000 {
+            let (mut l, mut r) = (rng.random_range(0..400), rng.random_range(0..400));
+            if l > r {
+                (l, r) = (r, l);
+            }
+            match rng.random_range(0..8) {
+                0..=2 => {
+                    let v = rng.random_range(-100..100);
+                    let prev = naive.insert(l, v).map(|x| (x, 1));
+                    assert_eq!(prev, bptm.insert(l, (v, 1)));
+                }
+                3 => {
+                    let removed: Vec<u32> = naive.range(l..r).map(|(&k, _)| k).collect();
+                    let expected = removed.iter().fold(None, |acc: Option<(i64, usize)>, k| {
+                        let v = naive.remove(k).unwrap();
+                        Some(acc.map_or((v, 1), |(s, c)| (s + v, c + 1)))
+                    });
+                    assert_eq!(expected, bptm.remove_range(l..r));
+                }
+                4 => {
+                    let expected = naive.remove(&l).map(|v| (v, 1));
+                    assert_eq!(expected, bptm.remove(&l));
+                }
+                5 => {
+                    if let Some(mut v) = bptm.get_mut(&l) {
+                        v.0 *= 2;
+                        *naive.get_mut(&l).unwrap() *= 2;
+                    } else {
+                        assert!(!naive.contains_key(&l));
+                    }
+                }
+                6 => {
+                    let delta = rng.random_range(-10..10);
+                    if let Some(mut x) = bptm.get_range_mut(l..=r) {
+                        x.apply(&delta);
+                    }
+                    for (_, v) in naive.range_mut(l..=r) {
+                        *v += delta;
+                    }
+                }
+                _ => {
+                    let expected =
+                        naive
+                            .range(l..=r)
+                            .fold(None, |acc: Option<(i64, usize)>, (_, &v)| {
+                                Some(acc.map_or((v, 1), |(s, c)| (s + v, c + 1)))
+                            });
+                    assert_eq!(expected, bptm.get_range(l..=r));
+                }
+            }
+            let as_pair = |x: Option<(&u32, &i64)>| x.map(|(&k, &v)| (k, (v, 1)));
+            let from_tree = |x: Option<(&u32, (i64, usize))>| x.map(|(&k, v)| (k, v));
+            assert_eq!(naive.len(), bptm.len());
+            assert_eq!(naive.is_empty(), bptm.is_empty());
+            assert_eq!(as_pair(naive.first_key_value()), from_tree(bptm.first()));
+            assert_eq!(as_pair(naive.last_key_value()), from_tree(bptm.last()));
+            assert_eq!(
+                as_pair(naive.range(l..).next()),
+                from_tree(bptm.lower_bound(&l))
+            );
+            assert_eq!(
+                as_pair(naive.range(l + 1..).next()),
+                from_tree(bptm.upper_bound(&l))
+            );
+            assert_eq!(naive.range(..l).count(), bptm.rank(&l));
+            let k = rng.random_range(0..=naive.len());
+            assert_eq!(as_pair(naive.iter().nth(k)), from_tree(bptm.kth(k)));
+            let expected: Vec<(u32, i64)> = naive.range(l..r).map(|(&k, &v)| (k, v)).collect();
+            let actual: Vec<(u32, i64)> = bptm.range(l..r).map(|(&k, v)| (k, v.0)).collect();
+            assert_eq!(expected, actual);
+            if it % 100 == 0 {
+                let expected: Vec<(u32, i64)> = naive.iter().map(|(&k, &v)| (k, v)).collect();
+                let actual: Vec<(u32, i64)> = bptm.iter().map(|(&k, v)| (k, v.0)).collect();
+                assert_eq!(expected, actual);
+            }
+        }
+    }
+    #[test]
+    fn check_btree_split_off_append() {
+        struct F;
+        impl LazyOp<(i64, usize), i64> for F {
+            fn binary_op(t1: &(i64, usize), t2: &(i64, usize)) -> (i64, usize) {
+                (t1.0 + t2.0, t1.1 + t2.1)
+            }
+            fn apply(u: &i64, t: &(i64, usize)) -> (i64, usize) {
+                (t.0 + u * t.1 as i64, t.1)
+            }
+            fn compose(u1: &i64, u2: &i64) -> i64 {
+                u1 + u2
+            }
+            fn id_op() -> i64 {
+                0
+            }
+        }
+        use rand::{Rng, SeedableRng};
+        let mut rng = rand::rngs::SmallRng::seed_from_u64(2024);
+        for _ in 0..200 {
+            let n = rng.random_range(0..600);
+            let mut v: Vec<i64> = (0..n).map(|i| i as i64).collect();
+            let mut bptm = BPTreeMapLazy::<usize, (i64, usize), i64, F>::from_iter(
+                n,
+                (0..n).map(|i| (i, (i as i64, 1))),
+            );
+            // leave lazy ops pending in both halves
+            for _ in 0..3 {
+                let (mut l, mut r) = (rng.random_range(0..=n), rng.random_range(0..=n));
+                if l > r {
+                    (l, r) = (r, l);
+                }
+                if let Some(mut x) = bptm.get_range_mut(l..r) {
+                    x.apply(&5);
+                }
+                v[l..r].iter_mut().for_each(|x| *x += 5);
+            }
+            let k = rng.random_range(0..=n);
+            let mut right = bptm.split_off(&k);
+            assert_eq!((k, n - k), (bptm.len(), right.len()));
+            let sum = |s: &[i64]| (!s.is_empty()).then(|| (s.iter().sum::<i64>(), s.len()));
+            assert_eq!(sum(&v[..k]), bptm.get_range(..));
+            assert_eq!(sum(&v[k..]), right.get_range(..));
+            assert_eq!((k < n).then_some(k), right.first().map(|(&k, _)| k));
+            let (mut l, mut r) = (rng.random_range(k..=n), rng.random_range(k..=n));
+            if l > r {
+                (l, r) = (r, l);
+            }
+            if let Some(mut x) = right.get_range_mut(l..r) {
+                x.apply(&-3);
+            }
+            v[l..r].iter_mut().for_each(|x| *x -= 3);
+            bptm.append(&mut right);
+            assert!(right.is_empty());
+            assert_eq!(n, bptm.len());
+            for _ in 0..20 {
+                let (mut l, mut r) = (rng.random_range(0..=n), rng.random_range(0..=n));
+                if l > r {
+                    (l, r) = (r, l);
+                }
+                assert_eq!(sum(&v[l..r]), bptm.get_range(l..r));
+            }
+            let actual: Vec<i64> = bptm.iter().map(|(_, x)| x.0).collect();
+            assert_eq!(v, actual);
+        }
+
+        // appending a taller tree to a shorter one, and vice versa
+        struct Sum;
+        impl Op<u32> for Sum {
+            fn binary_op(t1: &u32, t2: &u32) -> u32 {
+                t1 + t2
+            }
+        }
+        let mut a = BPTreeMap::<u32, u32, Sum>::from_iter(3, (0..3).map(|i| (i, i)));
+        let mut b = BPTreeMap::<u32, u32, Sum>::from_iter(1000, (3..1003).map(|i| (i, i)));
+        a.append(&mut b);
+        assert_eq!(Some(1002 * 1003 / 2), a.get_range(..));
+        let mut c = a.split_off(&1000);
+        assert_eq!(Some(1000 + 1001 + 1002), c.get_range(..));
+        c.append(&mut BPTreeMap::new());
+        a.append(&mut c);
+        assert_eq!((1003, Some(1002 * 1003 / 2)), (a.len(), a.get_range(..)));
+    }
+    #[test]
     fn check_btree_from_iter() {
         struct F;
         impl LazyOp<(i64, usize), i64> for F {
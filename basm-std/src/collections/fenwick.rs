@@ -1,4 +1,6 @@
 use alloc::{vec, vec::Vec};
+use core::marker::PhantomData;
+use core::ops::{Add, Mul, Sub};
 
 pub trait FenwickOp {
     type T: Clone;
@@ -38,6 +40,40 @@ impl<Op: FenwickOp> FenwickTree<Op> {
         }
         result
     }
+
+    /// Returns the largest `count` such that `pred(&self.query(count))` holds, in `O(lg n)` time by binary lifting.
+    /// `pred` must be monotone: true for prefixes up to some length, and false for all longer ones.
+    /// `pred(&Op::e())` is assumed to hold, so the result is at least 0.
+    pub fn partition_point<P>(&self, pred: P) -> usize
+    where
+        P: Fn(&Op::T) -> bool,
+    {
+        let n = self.v.len();
+        let mut pos = 0;
+        let mut acc = Op::e();
+        let mut step = if n == 0 { 0 } else { 1 << n.ilog2() };
+        while step > 0 {
+            if pos + step <= n {
+                let test = Op::combine(&acc, &self.v[pos + step - 1]);
+                if pred(&test) {
+                    pos += step;
+                    acc = test;
+                }
+            }
+            step >>= 1;
+        }
+        pos
+    }
+
+    /// Returns the smallest index `i` such that `self.query(i + 1) >= *target`,
+    /// or the number of elements if there is none.
+    /// The prefix aggregates must be nondecreasing (e.g., prefix sums of nonnegative values).
+    pub fn lower_bound(&self, target: &Op::T) -> usize
+    where
+        Op::T: Ord,
+    {
+        self.partition_point(|x| x < target)
+    }
 }
 
 impl<Op: FenwickOp> FromIterator<Op::T> for FenwickTree<Op> {
@@ -52,3 +88,257 @@ impl<Op: FenwickOp> FromIterator<Op::T> for FenwickTree<Op> {
         Self { v }
     }
 }
+
+struct SumOp<T>(PhantomData<T>);
+
+impl<T> FenwickOp for SumOp<T>
+where
+    T: Copy + Default + Add<Output = T>,
+{
+    type T = T;
+    type U = T;
+    fn e() -> T {
+        T::default()
+    }
+    fn combine(l: &T, r: &T) -> T {
+        *l + *r
+    }
+    fn apply(v: &mut T, u: &T) {
+        *v = *v + *u;
+    }
+}
+
+/// A Fenwick tree supporting range add and range sum, both in `O(lg n)` time.
+///
+/// `T` is expected to be a signed integer type (or any type forming a ring with `T::default()` as zero),
+/// since adding to a range internally subtracts at its end.
+///
+/// Example:
+/// ```
+/// use basm_std::collections::RangeFenwickTree;
+/// let mut tree = RangeFenwickTree::<i64>::new(5);
+/// tree.add(1..4, 10);
+/// tree.add(..2, -3);
+/// assert_eq!(-3 + 7 + 10 + 10, tree.sum(..4));
+/// assert_eq!(10, tree.sum(3..));
+/// ```
+pub struct RangeFenwickTree<T>
+where
+    T: Copy + Default + Add<Output = T> + Sub<Output = T> + Mul<Output = T> + TryFrom<usize>,
+{
+    // prefix_sum(c) = c * b1.query(c) - b2.query(c)
+    b1: FenwickTree<SumOp<T>>,
+    b2: FenwickTree<SumOp<T>>,
+    n: usize,
+}
+
+impl<T> RangeFenwickTree<T>
+where
+    T: Copy + Default + Add<Output = T> + Sub<Output = T> + Mul<Output = T> + TryFrom<usize>,
+{
+    /// Creates a tree of `n` elements, all initialized to zero.
+    pub fn new(n: usize) -> Self {
+        Self {
+            b1: FenwickTree::new(n),
+            b2: FenwickTree::new(n),
+            n,
+        }
+    }
+
+    /// Returns the number of elements.
+    pub fn len(&self) -> usize {
+        self.n
+    }
+
+    /// Returns `true` if the tree has no elements.
+    pub fn is_empty(&self) -> bool {
+        self.n == 0
+    }
+
+    fn to_t(i: usize) -> T {
+        T::try_from(i).ok().unwrap()
+    }
+
+    /// Adds `x` to every element in `range`.
+    pub fn add<B>(&mut self, range: B, x: T)
+    where
+        B: core::ops::RangeBounds<usize>,
+    {
+        let (l, r) = super::segment_tree::to_pair(range, self.n);
+        let zero = T::default();
+        self.b1.update(l, x);
+        self.b1.update(r, zero - x);
+        self.b2.update(l, x * Self::to_t(l));
+        self.b2.update(r, zero - x * Self::to_t(r));
+    }
+
+    /// Returns the sum of the first `count` elements.
+    pub fn prefix_sum(&self, count: usize) -> T {
+        self.b1.query(count) * Self::to_t(count) - self.b2.query(count)
+    }
+
+    /// Returns the sum of the elements in `range`.
+    pub fn sum<B>(&self, range: B) -> T
+    where
+        B: core::ops::RangeBounds<usize>,
+    {
+        let (l, r) = super::segment_tree::to_pair(range, self.n);
+        self.prefix_sum(r) - self.prefix_sum(l)
+    }
+}
+
+/// A two-dimensional Fenwick tree over an `h` by `w` grid,
+/// supporting point update and prefix-rectangle query, both in `O(lg h lg w)` time.
+///
+/// Example:
+/// ```
+/// use basm_std::collections::{FenwickOp, FenwickTree2D};
+/// struct Count;
+/// impl FenwickOp for Count {
+///     type T = u32;
+///     type U = u32;
+///     fn e() -> u32 { 0 }
+///     fn combine(l: &u32, r: &u32) -> u32 { l + r }
+///     fn apply(v: &mut u32, u: &u32) { *v += u }
+/// }
+/// let mut grid = FenwickTree2D::<Count>::new(4, 5);
+/// for (i, j) in [(0, 0), (1, 3), (3, 4), (2, 2)] {
+///     grid.update(i, j, 1);
+/// }
+/// assert_eq!(3, grid.query(3, 5)); // rows [0, 3), columns [0, 5)
+/// assert_eq!(2, grid.query(3, 3));
+/// ```
+pub struct FenwickTree2D<Op: FenwickOp> {
+    v: Vec<Op::T>,
+    h: usize,
+    w: usize,
+}
+
+impl<Op: FenwickOp> FenwickTree2D<Op> {
+    /// Creates an `h` by `w` grid, all initialized to `Op::e()`.
+    pub fn new(h: usize, w: usize) -> Self {
+        Self {
+            v: vec![Op::e(); h * w],
+            h,
+            w,
+        }
+    }
+
+    /// Returns `(h, w)`.
+    pub fn dims(&self) -> (usize, usize) {
+        (self.h, self.w)
+    }
+
+    /// Applies `u` to the cell in row `i` and column `j`.
+    pub fn update<U>(&mut self, mut i: usize, j: usize, u: U)
+    where
+        U: core::borrow::Borrow<Op::U>,
+    {
+        while i < self.h {
+            let mut k = j;
+            while k < self.w {
+                Op::apply(&mut self.v[i * self.w + k], u.borrow());
+                k |= k + 1;
+            }
+            i |= i + 1;
+        }
+    }
+
+    /// Combines the cells in the first `rows` rows and the first `cols` columns.
+    pub fn query(&self, rows: usize, cols: usize) -> Op::T {
+        let mut result = Op::e();
+        let mut i = rows;
+        while i > 0 {
+            let mut k = cols;
+            while k > 0 {
+                result = Op::combine(&result, &self.v[(i - 1) * self.w + k - 1]);
+                k &= k - 1;
+            }
+            i &= i - 1;
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+
+    struct Sum;
+    impl FenwickOp for Sum {
+        type T = i64;
+        type U = i64;
+        fn e() -> i64 {
+            0
+        }
+        fn combine(l: &i64, r: &i64) -> i64 {
+            l + r
+        }
+        fn apply(v: &mut i64, u: &i64) {
+            *v += u;
+        }
+    }
+
+    #[test]
+    fn check_fenwick_lower_bound() {
+        let mut rng = rand::rngs::SmallRng::seed_from_u64(17);
+        for n in [0, 1, 2, 5, 16, 100] {
+            let a: Vec<i64> = (0..n).map(|_| rng.random_range(0..5)).collect();
+            let tree: FenwickTree<Sum> = a.iter().copied().collect();
+            let total: i64 = a.iter().sum();
+            for target in 0..=total + 1 {
+                let mut s = 0;
+                let expected = a
+                    .iter()
+                    .position(|&x| {
+                        s += x;
+                        s >= target
+                    })
+                    .unwrap_or(n);
+                assert_eq!(expected, tree.lower_bound(&target));
+            }
+        }
+    }
+
+    #[test]
+    fn check_range_fenwick() {
+        let mut rng = rand::rngs::SmallRng::seed_from_u64(18);
+        let n = 50;
+        let mut tree = RangeFenwickTree::<i64>::new(n);
+        let mut naive = vec![0i64; n];
+        for _ in 0..1000 {
+            let (mut l, mut r) = (rng.random_range(0..=n), rng.random_range(0..=n));
+            if l > r {
+                (l, r) = (r, l);
+            }
+            if rng.random_bool(0.5) {
+                let x = rng.random_range(-100..100);
+                tree.add(l..r, x);
+                naive[l..r].iter_mut().for_each(|v| *v += x);
+            } else {
+                assert_eq!(naive[l..r].iter().sum::<i64>(), tree.sum(l..r));
+            }
+        }
+    }
+
+    #[test]
+    fn check_fenwick_2d() {
+        let mut rng = rand::rngs::SmallRng::seed_from_u64(19);
+        let (h, w) = (13, 7);
+        let mut tree = FenwickTree2D::<Sum>::new(h, w);
+        let mut naive = vec![vec![0i64; w]; h];
+        for _ in 0..1000 {
+            let (i, j) = (rng.random_range(0..h), rng.random_range(0..w));
+            let x = rng.random_range(-100..100);
+            tree.update(i, j, x);
+            naive[i][j] += x;
+            let (rows, cols) = (rng.random_range(0..=h), rng.random_range(0..=w));
+            let expected: i64 = naive[..rows]
+                .iter()
+                .map(|row| row[..cols].iter().sum::<i64>())
+                .sum();
+            assert_eq!(expected, tree.query(rows, cols));
+        }
+    }
+}
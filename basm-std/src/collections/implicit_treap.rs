@@ -0,0 +1,485 @@
+use super::LazyOp;
+use super::segment_tree::to_pair;
+use alloc::{boxed::Box, vec::Vec};
+use core::marker::PhantomData;
+use core::ops::RangeBounds;
+use core::sync::atomic::{AtomicU64, Ordering};
+
+/// Returns a fresh pseudo-random priority, using splitmix64 on a global counter.
+fn next_priority() -> u64 {
+    static STATE: AtomicU64 = AtomicU64::new(0x2545_f491_4f6c_dd1d);
+    let mut z = STATE.fetch_add(0x9e37_79b9_7f4a_7c15, Ordering::Relaxed);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+type Link<V, U> = Option<Box<Node<V, U>>>;
+
+struct Node<V, U> {
+    value: V,
+    // Aggregate of the subtree in order, and in reverse order.
+    agg: V,
+    rev_agg: V,
+    // Pending ops for the children; the node itself is already up to date.
+    lazy: Option<U>,
+    rev: bool,
+    size: usize,
+    priority: u64,
+    left: Link<V, U>,
+    right: Link<V, U>,
+}
+
+/// A sequence stored in a treap keyed by position (an implicit treap),
+/// supporting insertion, removal, splitting and concatenation at arbitrary positions,
+/// as well as range reversal, range aggregation and range updates by lazy propagation.
+/// Every operation takes `O(lg n)` expected time.
+///
+/// The aggregation and update rules are given by [`LazyOp`], the same trait used by `BPTreeMapLazy`.
+/// `binary_op` need not be commutative; to support reversal, the aggregate of each subtree
+/// is maintained in both directions.
+///
+/// Example:
+/// ```
+/// use basm_std::collections::{ImplicitTreap, LazyOp};
+/// // range add, range sum of (sum, count)
+/// struct AddSum;
+/// impl LazyOp<(i64, usize), i64> for AddSum {
+///     fn binary_op(t1: &(i64, usize), t2: &(i64, usize)) -> (i64, usize) { (t1.0 + t2.0, t1.1 + t2.1) }
+///     fn apply(u: &i64, t: &(i64, usize)) -> (i64, usize) { (t.0 + u * t.1 as i64, t.1) }
+///     fn compose(u1: &i64, u2: &i64) -> i64 { u1 + u2 }
+///     fn id_op() -> i64 { 0 }
+/// }
+/// let mut seq: ImplicitTreap<_, _, AddSum> = (1..=5).map(|x| (x, 1)).collect();
+/// seq.reverse(1..4);
+/// seq.range_apply(..2, &10);
+/// seq.insert_at(0, (7, 1));
+/// let v: Vec<i64> = seq.iter().map(|x| x.0).collect();
+/// assert_eq!(vec![7, 11, 14, 3, 2, 5], v);
+/// assert_eq!(Some((19, 3)), seq.range_query(2..5));
+/// ```
+pub struct ImplicitTreap<V, U, F>
+where
+    V: Clone,
+    U: Clone,
+    F: LazyOp<V, U>,
+{
+    root: Link<V, U>,
+    _f: PhantomData<F>,
+}
+
+impl<V, U, F> Default for ImplicitTreap<V, U, F>
+where
+    V: Clone,
+    U: Clone,
+    F: LazyOp<V, U>,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<V, U, F> FromIterator<V> for ImplicitTreap<V, U, F>
+where
+    V: Clone,
+    U: Clone,
+    F: LazyOp<V, U>,
+{
+    fn from_iter<I: IntoIterator<Item = V>>(iter: I) -> Self {
+        let mut out = Self::new();
+        for v in iter {
+            let node = Self::new_node(v);
+            out.root = Self::merge(out.root.take(), node);
+        }
+        out
+    }
+}
+
+impl<V, U, F> ImplicitTreap<V, U, F>
+where
+    V: Clone,
+    U: Clone,
+    F: LazyOp<V, U>,
+{
+    /// Creates an empty sequence.
+    pub fn new() -> Self {
+        Self {
+            root: None,
+            _f: PhantomData,
+        }
+    }
+
+    /// Returns the number of elements.
+    pub fn len(&self) -> usize {
+        Self::size(&self.root)
+    }
+
+    /// Returns `true` if the sequence has no elements.
+    pub fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+
+    /// Inserts `value` so that it becomes the `i`-th element.
+    /// This function will panic if `i > self.len()`.
+    pub fn insert_at(&mut self, i: usize, value: V) {
+        assert!(i <= self.len());
+        let (l, r) = Self::split(self.root.take(), i);
+        self.root = Self::merge(Self::merge(l, Self::new_node(value)), r);
+    }
+
+    /// Removes and returns the `i`-th element.
+    /// This function will panic if `i >= self.len()`.
+    pub fn remove_at(&mut self, i: usize) -> V {
+        assert!(i < self.len());
+        let (l, r) = Self::split(self.root.take(), i);
+        let (m, r) = Self::split(r, 1);
+        self.root = Self::merge(l, r);
+        m.unwrap().value
+    }
+
+    /// Returns the `i`-th element, or `None` if `i >= self.len()`.
+    pub fn get(&self, i: usize) -> Option<V> {
+        self.iter_from(i).next()
+    }
+
+    /// Splits the sequence into two at position `i`.
+    /// Returns the elements in `[i, len)`, leaving `[0, i)` in `self`.
+    /// This function will panic if `i > self.len()`.
+    pub fn split_at(&mut self, i: usize) -> Self {
+        assert!(i <= self.len());
+        let (l, r) = Self::split(self.root.take(), i);
+        self.root = l;
+        Self {
+            root: r,
+            _f: PhantomData,
+        }
+    }
+
+    /// Appends every element of `other` to the end of the sequence.
+    pub fn concat(&mut self, other: Self) {
+        self.root = Self::merge(self.root.take(), other.root);
+    }
+
+    /// Reverses the elements in `range`.
+    pub fn reverse<R: RangeBounds<usize>>(&mut self, range: R) {
+        self.with_range(range, |x| {
+            if let Some(x) = x {
+                Self::apply_rev(x);
+            }
+        });
+    }
+
+    /// Returns the aggregate of the elements in `range`, or `None` if `range` is empty.
+    pub fn range_query<R: RangeBounds<usize>>(&mut self, range: R) -> Option<V> {
+        self.with_range(range, |x| x.as_ref().map(|x| x.agg.clone()))
+    }
+
+    /// Applies `u` to every element in `range`.
+    pub fn range_apply<R: RangeBounds<usize>>(&mut self, range: R, u: &U) {
+        self.with_range(range, |x| {
+            if let Some(x) = x {
+                Self::apply_op(x, u);
+            }
+        });
+    }
+
+    /// Returns an iterator over the elements in order.
+    /// Pending lazy ops are not pushed down; each element is yielded by value with them applied.
+    pub fn iter(&self) -> ImplicitTreapIter<'_, V, U, F> {
+        self.iter_from(0)
+    }
+
+    /// Returns an iterator over the elements starting from position `i`.
+    fn iter_from(&self, mut i: usize) -> ImplicitTreapIter<'_, V, U, F> {
+        let mut out = ImplicitTreapIter {
+            stack: Vec::new(),
+            _f: PhantomData,
+        };
+        let (mut node, mut u, mut r) = (self.root.as_deref(), None, false);
+        while let Some(x) = node {
+            let (first, second) = if r {
+                (&x.right, &x.left)
+            } else {
+                (&x.left, &x.right)
+            };
+            let (cu, cr) = (F::compose_option(&u, &x.lazy), r ^ x.rev);
+            let k = Self::size(first);
+            if i < k {
+                out.stack.push((x, u, r));
+                node = first.as_deref();
+            } else if i == k {
+                out.stack.push((x, u, r));
+                break;
+            } else {
+                i -= k + 1;
+                node = second.as_deref();
+            }
+            (u, r) = (cu, cr);
+        }
+        out
+    }
+
+    /// Runs `f` on the subtree holding exactly the elements in `range`.
+    fn with_range<R, O>(&mut self, range: R, f: impl FnOnce(&mut Link<V, U>) -> O) -> O
+    where
+        R: RangeBounds<usize>,
+    {
+        let (l, r) = to_pair(range, self.len());
+        let (a, b) = Self::split(self.root.take(), l);
+        let (mut b, c) = Self::split(b, r - l);
+        let out = f(&mut b);
+        self.root = Self::merge(Self::merge(a, b), c);
+        out
+    }
+
+    fn new_node(value: V) -> Link<V, U> {
+        Some(Box::new(Node {
+            agg: value.clone(),
+            rev_agg: value.clone(),
+            value,
+            lazy: None,
+            rev: false,
+            size: 1,
+            priority: next_priority(),
+            left: None,
+            right: None,
+        }))
+    }
+
+    fn size(x: &Link<V, U>) -> usize {
+        x.as_ref().map_or(0, |x| x.size)
+    }
+
+    fn apply_op(x: &mut Node<V, U>, u: &U) {
+        x.value = F::apply(u, &x.value);
+        x.agg = F::apply(u, &x.agg);
+        x.rev_agg = F::apply(u, &x.rev_agg);
+        x.lazy = Some(match &x.lazy {
+            Some(v) => F::compose(u, v),
+            None => u.clone(),
+        });
+    }
+
+    fn apply_rev(x: &mut Node<V, U>) {
+        core::mem::swap(&mut x.left, &mut x.right);
+        core::mem::swap(&mut x.agg, &mut x.rev_agg);
+        x.rev ^= true;
+    }
+
+    fn push(x: &mut Node<V, U>) {
+        if x.rev {
+            x.rev = false;
+            for c in [&mut x.left, &mut x.right].into_iter().flatten() {
+                Self::apply_rev(c);
+            }
+        }
+        if let Some(u) = x.lazy.take() {
+            for c in [&mut x.left, &mut x.right].into_iter().flatten() {
+                Self::apply_op(c, &u);
+            }
+        }
+    }
+
+    fn pull(x: &mut Node<V, U>) {
+        x.size = 1 + Self::size(&x.left) + Self::size(&x.right);
+        let (mut agg, mut rev_agg) = (x.value.clone(), x.value.clone());
+        if let Some(l) = &x.left {
+            agg = F::binary_op(&l.agg, &agg);
+            rev_agg = F::binary_op(&rev_agg, &l.rev_agg);
+        }
+        if let Some(r) = &x.right {
+            agg = F::binary_op(&agg, &r.agg);
+            rev_agg = F::binary_op(&r.rev_agg, &rev_agg);
+        }
+        (x.agg, x.rev_agg) = (agg, rev_agg);
+    }
+
+    /// Splits `x` into the first `k` elements and the rest.
+    fn split(x: Link<V, U>, k: usize) -> (Link<V, U>, Link<V, U>) {
+        let Some(mut x) = x else {
+            return (None, None);
+        };
+        Self::push(&mut x);
+        let lsize = Self::size(&x.left);
+        if k <= lsize {
+            let (l, r) = Self::split(x.left.take(), k);
+            x.left = r;
+            Self::pull(&mut x);
+            (l, Some(x))
+        } else {
+            let (l, r) = Self::split(x.right.take(), k - lsize - 1);
+            x.right = l;
+            Self::pull(&mut x);
+            (Some(x), r)
+        }
+    }
+
+    /// Concatenates `l` and `r`.
+    fn merge(l: Link<V, U>, r: Link<V, U>) -> Link<V, U> {
+        match (l, r) {
+            (None, r) => r,
+            (l, None) => l,
+            (Some(mut l), Some(mut r)) => {
+                if l.priority > r.priority {
+                    Self::push(&mut l);
+                    l.right = Self::merge(l.right.take(), Some(r));
+                    Self::pull(&mut l);
+                    Some(l)
+                } else {
+                    Self::push(&mut r);
+                    r.left = Self::merge(Some(l), r.left.take());
+                    Self::pull(&mut r);
+                    Some(r)
+                }
+            }
+        }
+    }
+}
+
+/// An iterator over the elements of an [`ImplicitTreap`] in order, returned by `ImplicitTreap::iter`.
+pub struct ImplicitTreapIter<'a, V, U, F>
+where
+    V: Clone,
+    U: Clone,
+    F: LazyOp<V, U>,
+{
+    // (node, composed lazy op above the node, whether the node is reversed by its ancestors)
+    // for the nodes whose value is yet to be yielded, on the path from the root
+    stack: Vec<(&'a Node<V, U>, Option<U>, bool)>,
+    _f: PhantomData<F>,
+}
+
+impl<V, U, F> Iterator for ImplicitTreapIter<'_, V, U, F>
+where
+    V: Clone,
+    U: Clone,
+    F: LazyOp<V, U>,
+{
+    type Item = V;
+    fn next(&mut self) -> Option<V> {
+        let (x, u, r) = self.stack.pop()?;
+        let (mut u2, mut r2) = (F::compose_option(&u, &x.lazy), r ^ x.rev);
+        let mut node = if r { &x.left } else { &x.right }.as_deref();
+        while let Some(y) = node {
+            let (cu, cr) = (F::compose_option(&u2, &y.lazy), r2 ^ y.rev);
+            node = if r2 { &y.right } else { &y.left }.as_deref();
+            self.stack.push((y, u2, r2));
+            (u2, r2) = (cu, cr);
+        }
+        Some(match &u {
+            Some(u) => F::apply(u, &x.value),
+            None => x.value.clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+
+    // Affine maps x -> a * x + b composed left to right, modulo a prime.
+    // This is not commutative, so reversal has to track both directions.
+    const P: u64 = 998_244_353;
+    struct Affine;
+    impl LazyOp<(u64, u64), ()> for Affine {
+        fn binary_op(t1: &(u64, u64), t2: &(u64, u64)) -> (u64, u64) {
+            (t1.0 * t2.0 % P, (t1.1 * t2.0 + t2.1) % P)
+        }
+        fn apply(_u: &(), t: &(u64, u64)) -> (u64, u64) {
+            *t
+        }
+        fn compose(_u1: &(), _u2: &()) {}
+        fn id_op() {}
+    }
+
+    #[test]
+    fn check_implicit_treap() {
+        let mut rng = rand::rngs::SmallRng::seed_from_u64(15);
+        let mut treap = ImplicitTreap::<(u64, u64), (), Affine>::new();
+        let mut naive: Vec<(u64, u64)> = Vec::new();
+        let fold = |s: &[(u64, u64)]| s.iter().cloned().reduce(|a, b| Affine::binary_op(&a, &b));
+        for _ in 0..20000 {
+            let n = naive.len();
+            let (mut l, mut r) = (rng.random_range(0..=n), rng.random_range(0..=n));
+            if l > r {
+                (l, r) = (r, l);
+            }
+            match rng.random_range(0..6) {
+                0 | 1 => {
+                    let v = (rng.random_range(1..P), rng.random_range(0..P));
+                    treap.insert_at(l, v);
+                    naive.insert(l, v);
+                }
+                2 if n > 0 => {
+                    let i = rng.random_range(0..n);
+                    assert_eq!(naive.remove(i), treap.remove_at(i));
+                }
+                3 => {
+                    treap.reverse(l..r);
+                    naive[l..r].reverse();
+                }
+                4 => {
+                    let mut right = treap.split_at(l);
+                    assert_eq!((l, n - l), (treap.len(), right.len()));
+                    right.reverse(..);
+                    treap.concat(right);
+                    naive[l..].reverse();
+                }
+                _ => {
+                    assert_eq!(fold(&naive[l..r]), treap.range_query(l..r));
+                }
+            }
+            assert_eq!(naive.len(), treap.len());
+            let i = rng.random_range(0..=naive.len());
+            assert_eq!(naive.get(i).cloned(), treap.get(i));
+        }
+        assert_eq!(naive, treap.iter().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn check_implicit_treap_lazy() {
+        struct AddSum;
+        impl LazyOp<(i64, usize), i64> for AddSum {
+            fn binary_op(t1: &(i64, usize), t2: &(i64, usize)) -> (i64, usize) {
+                (t1.0 + t2.0, t1.1 + t2.1)
+            }
+            fn apply(u: &i64, t: &(i64, usize)) -> (i64, usize) {
+                (t.0 + u * t.1 as i64, t.1)
+            }
+            fn compose(u1: &i64, u2: &i64) -> i64 {
+                u1 + u2
+            }
+            fn id_op() -> i64 {
+                0
+            }
+        }
+        let mut rng = rand::rngs::SmallRng::seed_from_u64(16);
+        let n = 300;
+        let mut treap: ImplicitTreap<_, _, AddSum> = (0..n as i64).map(|x| (x, 1)).collect();
+        let mut naive: Vec<i64> = (0..n as i64).collect();
+        for _ in 0..5000 {
+            let (mut l, mut r) = (rng.random_range(0..=n), rng.random_range(0..=n));
+            if l > r {
+                (l, r) = (r, l);
+            }
+            match rng.random_range(0..3) {
+                0 => {
+                    let u = rng.random_range(-50..50);
+                    treap.range_apply(l..r, &u);
+                    naive[l..r].iter_mut().for_each(|x| *x += u);
+                }
+                1 => {
+                    treap.reverse(l..r);
+                    naive[l..r].reverse();
+                }
+                _ => {
+                    let expected = (l < r).then(|| (naive[l..r].iter().sum(), r - l));
+                    assert_eq!(expected, treap.range_query(l..r));
+                }
+            }
+            let actual: Vec<i64> = treap.iter().map(|x| x.0).collect();
+            assert_eq!(naive, actual);
+        }
+    }
+}
@@ -0,0 +1,491 @@
+use alloc::{collections::VecDeque, vec, vec::Vec};
+
+/// The line `y = a * x + b`, evaluated with `i128` intermediates.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Line {
+    a: i64,
+    b: i64,
+}
+
+impl Line {
+    fn eval(&self, x: i64) -> i128 {
+        self.a as i128 * x as i128 + self.b as i128
+    }
+}
+
+fn to_i64(v: i128) -> i64 {
+    i64::try_from(v).expect("the minimum does not fit in i64")
+}
+
+/// A Li Chao tree over a fixed set of query coordinates, which maintains a set of lines (or segments)
+/// and answers the minimum of `a * x + b` at a given `x`.
+///
+/// Inserting a line and querying take `O(lg n)` time, and inserting a segment takes `O(lg**2 n)` time,
+/// where `n` is the number of coordinates. Values are compared in `i128`,
+/// so only the answers need to fit in `i64`. For the maximum, insert `(-a, -b)` and negate the answer.
+///
+/// Example:
+/// ```
+/// use basm_std::collections::LiChaoTree;
+/// let mut tree = LiChaoTree::new(vec![-5, 0, 3, 10]);
+/// tree.add_line(2, 1);
+/// tree.add_line(-1, 4);
+/// tree.add_segment(0..=3, 0, -10); // y = -10 only for 0 <= x <= 3
+/// assert_eq!(Some(-9), tree.query(-5));
+/// assert_eq!(Some(-10), tree.query(3));
+/// assert_eq!(Some(-6), tree.query(10));
+/// ```
+pub struct LiChaoTree {
+    xs: Vec<i64>,
+    size: usize,
+    lines: Vec<Option<Line>>,
+}
+
+impl LiChaoTree {
+    /// Creates an empty tree which can be queried at the coordinates in `xs`.
+    /// `xs` need not be sorted, and may contain duplicates.
+    pub fn new(mut xs: Vec<i64>) -> Self {
+        xs.sort_unstable();
+        xs.dedup();
+        let size = xs.len().next_power_of_two();
+        Self {
+            xs,
+            size,
+            lines: vec![None; 2 * size],
+        }
+    }
+
+    /// Returns the coordinate of the `i`-th leaf, extending the last coordinate to the padding leaves.
+    fn x(&self, i: usize) -> i64 {
+        self.xs[i.min(self.xs.len() - 1)]
+    }
+
+    fn insert(&mut self, mut k: usize, mut lo: usize, mut hi: usize, mut line: Line) {
+        loop {
+            let Some(cur) = &mut self.lines[k] else {
+                self.lines[k] = Some(line);
+                return;
+            };
+            let mid = (lo + hi) / 2;
+            let xm = self.xs[mid.min(self.xs.len() - 1)];
+            if line.eval(xm) < cur.eval(xm) {
+                core::mem::swap(cur, &mut line);
+            }
+            let cur = *cur;
+            if hi - lo == 1 {
+                return;
+            }
+            let (xl, xr) = (self.x(lo), self.x(hi - 1));
+            if line.eval(xl) < cur.eval(xl) {
+                (k, hi) = (2 * k, mid);
+            } else if line.eval(xr) < cur.eval(xr) {
+                (k, lo) = (2 * k + 1, mid);
+            } else {
+                return;
+            }
+        }
+    }
+
+    /// Inserts the line `y = a * x + b`.
+    pub fn add_line(&mut self, a: i64, b: i64) {
+        if !self.xs.is_empty() {
+            self.insert(1, 0, self.size, Line { a, b });
+        }
+    }
+
+    /// Inserts the segment `y = a * x + b` restricted to the coordinates in `range`.
+    pub fn add_segment<B>(&mut self, range: B, a: i64, b: i64)
+    where
+        B: core::ops::RangeBounds<i64>,
+    {
+        use core::ops::Bound::*;
+        let l = match range.start_bound() {
+            Included(x) => self.xs.partition_point(|v| v < x),
+            Excluded(x) => self.xs.partition_point(|v| v <= x),
+            Unbounded => 0,
+        };
+        let r = match range.end_bound() {
+            Included(x) => self.xs.partition_point(|v| v <= x),
+            Excluded(x) => self.xs.partition_point(|v| v < x),
+            Unbounded => self.xs.len(),
+        };
+        if l >= r {
+            return;
+        }
+        // Decompose [l, r) into canonical nodes, as in a bottom-up segment tree
+        let line = Line { a, b };
+        let (mut lk, mut rk, mut width) = (l + self.size, r + self.size, 1);
+        while lk < rk {
+            if lk & 1 != 0 {
+                let lo = lk * width - self.size;
+                self.insert(lk, lo, lo + width, line);
+                lk += 1;
+            }
+            if rk & 1 != 0 {
+                rk -= 1;
+                let lo = rk * width - self.size;
+                self.insert(rk, lo, lo + width, line);
+            }
+            lk >>= 1;
+            rk >>= 1;
+            width <<= 1;
+        }
+    }
+
+    /// Returns the minimum value at `x` over the inserted lines and segments covering `x`,
+    /// or `None` if there is none. `x` must be one of the coordinates given on creation.
+    pub fn query(&self, x: i64) -> Option<i64> {
+        let i = self
+            .xs
+            .binary_search(&x)
+            .expect("x is not a coordinate of the tree");
+        let mut k = i + self.size;
+        let mut out: Option<i128> = None;
+        while k > 0 {
+            if let Some(line) = &self.lines[k] {
+                let v = line.eval(x);
+                out = Some(out.map_or(v, |o| o.min(v)));
+            }
+            k >>= 1;
+        }
+        out.map(to_i64)
+    }
+}
+
+struct DynamicNode {
+    line: Option<Line>,
+    left: u32,
+    right: u32,
+}
+
+/// A Li Chao tree over the integer interval `[lo, hi)`, whose nodes are created on demand.
+/// Use this when the query coordinates are not known in advance.
+///
+/// Inserting a line and querying take `O(lg (hi - lo))` time, and inserting a segment takes
+/// `O(lg**2 (hi - lo))` time. Inserting a line creates `O(lg (hi - lo))` nodes at most,
+/// and inserting a segment creates `O(lg**2 (hi - lo))` nodes at most.
+/// As in [`LiChaoTree`], the minimum is maintained and values are compared in `i128`.
+///
+/// Example:
+/// ```
+/// use basm_std::collections::DynamicLiChaoTree;
+/// let mut tree = DynamicLiChaoTree::new(-1_000_000_000, 1_000_000_001);
+/// tree.add_line(3, 0);
+/// tree.add_segment(10..20, -1, 5);
+/// assert_eq!(Some(-15), tree.query(-5));
+/// assert_eq!(Some(-14), tree.query(19));
+/// assert_eq!(Some(60), tree.query(20));
+/// ```
+pub struct DynamicLiChaoTree {
+    lo: i64,
+    hi: i64,
+    // Node 0 is the root; 0 is also used as the null child, since the root is never a child
+    nodes: Vec<DynamicNode>,
+}
+
+impl DynamicLiChaoTree {
+    /// Creates an empty tree over the integers in `[lo, hi)`.
+    pub fn new(lo: i64, hi: i64) -> Self {
+        assert!(lo < hi);
+        Self {
+            lo,
+            hi,
+            nodes: vec![DynamicNode {
+                line: None,
+                left: 0,
+                right: 0,
+            }],
+        }
+    }
+
+    fn mid(lo: i64, hi: i64) -> i64 {
+        ((lo as i128 + hi as i128) >> 1) as i64
+    }
+
+    fn child(&mut self, k: usize, right: bool) -> usize {
+        let c = if right {
+            self.nodes[k].right
+        } else {
+            self.nodes[k].left
+        };
+        if c != 0 {
+            return c as usize;
+        }
+        self.nodes.push(DynamicNode {
+            line: None,
+            left: 0,
+            right: 0,
+        });
+        let c = (self.nodes.len() - 1) as u32;
+        if right {
+            self.nodes[k].right = c;
+        } else {
+            self.nodes[k].left = c;
+        }
+        c as usize
+    }
+
+    fn insert(&mut self, mut k: usize, mut lo: i64, mut hi: i64, mut line: Line) {
+        loop {
+            let Some(cur) = &mut self.nodes[k].line else {
+                self.nodes[k].line = Some(line);
+                return;
+            };
+            let mid = Self::mid(lo, hi);
+            if line.eval(mid) < cur.eval(mid) {
+                core::mem::swap(cur, &mut line);
+            }
+            let cur = *cur;
+            if lo == hi - 1 {
+                return;
+            }
+            if line.eval(lo) < cur.eval(lo) {
+                (k, hi) = (self.child(k, false), mid);
+            } else if line.eval(hi - 1) < cur.eval(hi - 1) {
+                (k, lo) = (self.child(k, true), mid);
+            } else {
+                return;
+            }
+        }
+    }
+
+    fn insert_segment(&mut self, k: usize, lo: i64, hi: i64, l: i64, r: i64, line: Line) {
+        if r <= lo || hi <= l {
+            return;
+        }
+        if l <= lo && hi <= r {
+            self.insert(k, lo, hi, line);
+            return;
+        }
+        let mid = Self::mid(lo, hi);
+        if l < mid {
+            let left = self.child(k, false);
+            self.insert_segment(left, lo, mid, l, r, line);
+        }
+        if mid < r {
+            let right = self.child(k, true);
+            self.insert_segment(right, mid, hi, l, r, line);
+        }
+    }
+
+    /// Inserts the line `y = a * x + b`.
+    pub fn add_line(&mut self, a: i64, b: i64) {
+        self.insert(0, self.lo, self.hi, Line { a, b });
+    }
+
+    /// Inserts the segment `y = a * x + b` restricted to the integers in `range`.
+    pub fn add_segment(&mut self, range: core::ops::Range<i64>, a: i64, b: i64) {
+        let (l, r) = (range.start.max(self.lo), range.end.min(self.hi));
+        if l < r {
+            self.insert_segment(0, self.lo, self.hi, l, r, Line { a, b });
+        }
+    }
+
+    /// Returns the minimum value at `x` over the inserted lines and segments covering `x`,
+    /// or `None` if there is none. `x` must lie in `[lo, hi)`.
+    pub fn query(&self, x: i64) -> Option<i64> {
+        assert!(self.lo <= x && x < self.hi);
+        let (mut k, mut lo, mut hi) = (0, self.lo, self.hi);
+        let mut out: Option<i128> = None;
+        loop {
+            let node = &self.nodes[k];
+            if let Some(line) = &node.line {
+                let v = line.eval(x);
+                out = Some(out.map_or(v, |o| o.min(v)));
+            }
+            let mid = Self::mid(lo, hi);
+            let c = if x < mid {
+                hi = mid;
+                node.left
+            } else {
+                lo = mid;
+                node.right
+            };
+            if c == 0 {
+                return out.map(to_i64);
+            }
+            k = c as usize;
+        }
+    }
+}
+
+/// The convex hull trick for lines inserted in order of nonincreasing slope,
+/// which answers the minimum of `a * x + b` at a given `x`.
+///
+/// Adding a line takes amortized `O(1)` time, `query` takes `O(lg n)` time,
+/// and `query_monotone` takes amortized `O(1)` time when called with nondecreasing `x`.
+/// Redundancy of a line is decided exactly over the integers, using `i128` intermediates.
+/// For the maximum, insert `(-a, -b)` (in order of nondecreasing `a`) and negate the answer.
+///
+/// Example:
+/// ```
+/// use basm_std::collections::ConvexHullTrick;
+/// let mut cht = ConvexHullTrick::new();
+/// cht.add_line(2, 0);
+/// cht.add_line(0, 3);
+/// cht.add_line(-1, 10);
+/// assert_eq!(Some(-2), cht.query(-1));
+/// assert_eq!(Some(3), cht.query(5));
+/// assert_eq!(Some(0), cht.query(10));
+/// ```
+#[derive(Default)]
+pub struct ConvexHullTrick {
+    lines: VecDeque<Line>,
+}
+
+impl ConvexHullTrick {
+    /// Creates an empty hull.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of lines on the hull.
+    pub fn len(&self) -> usize {
+        self.lines.len()
+    }
+
+    /// Returns `true` if the hull has no lines.
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    /// Returns the least integer `x` at which `l2` is at most `l1`, where `l1.a > l2.a`.
+    fn start(l1: &Line, l2: &Line) -> i128 {
+        let (num, den) = (l2.b as i128 - l1.b as i128, l1.a as i128 - l2.a as i128);
+        num.div_euclid(den) + (num.rem_euclid(den) != 0) as i128
+    }
+
+    /// Inserts the line `y = a * x + b`.
+    /// `a` must be less than or equal to the slopes of all the lines inserted before;
+    /// among lines of equal slope, only the lowest one is kept.
+    pub fn add_line(&mut self, a: i64, b: i64) {
+        let line = Line { a, b };
+        if let Some(last) = self.lines.back() {
+            assert!(a <= last.a, "slopes must be nonincreasing");
+            if a == last.a {
+                if b >= last.b {
+                    return;
+                }
+                self.lines.pop_back();
+            }
+        }
+        while self.lines.len() >= 2 {
+            let n = self.lines.len();
+            let (l1, l2) = (&self.lines[n - 2], &self.lines[n - 1]);
+            // l2 is optimal on the integers in [start(l1, l2), start(l2, line))
+            if Self::start(l1, l2) >= Self::start(l2, &line) {
+                self.lines.pop_back();
+            } else {
+                break;
+            }
+        }
+        self.lines.push_back(line);
+    }
+
+    /// Returns the minimum value at `x`, or `None` if the hull is empty.
+    pub fn query(&self, x: i64) -> Option<i64> {
+        if self.lines.is_empty() {
+            return None;
+        }
+        // the values at x decrease and then increase along the hull
+        let (mut lo, mut hi) = (0, self.lines.len() - 1);
+        while lo < hi {
+            let mid = (lo + hi) / 2;
+            if self.lines[mid + 1].eval(x) <= self.lines[mid].eval(x) {
+                lo = mid + 1;
+            } else {
+                hi = mid;
+            }
+        }
+        Some(to_i64(self.lines[lo].eval(x)))
+    }
+
+    /// Returns the minimum value at `x`, or `None` if the hull is empty.
+    /// Successive calls must be made with nondecreasing `x`, since the lines that are no longer
+    /// optimal are discarded from the front.
+    pub fn query_monotone(&mut self, x: i64) -> Option<i64> {
+        while self.lines.len() >= 2 && self.lines[1].eval(x) <= self.lines[0].eval(x) {
+            self.lines.pop_front();
+        }
+        self.lines.front().map(|l| to_i64(l.eval(x)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+
+    #[test]
+    fn check_li_chao() {
+        let mut rng = rand::rngs::SmallRng::seed_from_u64(20);
+        for _ in 0..50 {
+            let xs: Vec<i64> = (0..rng.random_range(1..40))
+                .map(|_| rng.random_range(-50..50))
+                .collect();
+            let mut tree = LiChaoTree::new(xs.clone());
+            let mut dynamic = DynamicLiChaoTree::new(-50, 50);
+            let mut segs: Vec<(i64, i64, i64, i64)> = vec![];
+            for _ in 0..100 {
+                let (a, b) = (rng.random_range(-1000..1000), rng.random_range(-1000..1000));
+                if rng.random_bool(0.3) {
+                    tree.add_line(a, b);
+                    dynamic.add_line(a, b);
+                    segs.push((-50, 50, a, b));
+                } else {
+                    let (mut l, mut r) = (rng.random_range(-50..=50), rng.random_range(-50..=50));
+                    if l > r {
+                        (l, r) = (r, l);
+                    }
+                    tree.add_segment(l..r, a, b);
+                    dynamic.add_segment(l..r, a, b);
+                    segs.push((l, r, a, b));
+                }
+                let x = xs[rng.random_range(0..xs.len())];
+                let expected = segs
+                    .iter()
+                    .filter(|&&(l, r, _, _)| l <= x && x < r)
+                    .map(|&(_, _, a, b)| a * x + b)
+                    .min();
+                assert_eq!(expected, tree.query(x));
+                assert_eq!(expected, dynamic.query(x));
+            }
+        }
+        // extreme coordinates and slopes, whose intermediate values exceed i64
+        let mut dynamic = DynamicLiChaoTree::new(i64::MIN, i64::MAX);
+        dynamic.add_line(i64::MAX, 0);
+        dynamic.add_line(1, 0);
+        assert_eq!(Some(i64::MAX - 1), dynamic.query(i64::MAX - 1));
+        assert_eq!(Some(i64::MIN + 1), dynamic.query(-1));
+        // a segment only allocates the nodes on the paths to its ends
+        let mut dynamic = DynamicLiChaoTree::new(i64::MIN, i64::MAX);
+        dynamic.add_segment(5..6, 1, 0);
+        assert!(dynamic.nodes.len() <= 65);
+        assert_eq!(Some(5), dynamic.query(5));
+        assert_eq!(None, dynamic.query(6));
+    }
+
+    #[test]
+    fn check_convex_hull_trick() {
+        let mut rng = rand::rngs::SmallRng::seed_from_u64(21);
+        for _ in 0..200 {
+            let mut lines: Vec<(i64, i64)> = (0..rng.random_range(1..30))
+                .map(|_| (rng.random_range(-20..20), rng.random_range(-100..100)))
+                .collect();
+            lines.sort_by_key(|&(a, _)| core::cmp::Reverse(a));
+            let mut cht = ConvexHullTrick::new();
+            let mut mono = ConvexHullTrick::new();
+            for &(a, b) in &lines {
+                cht.add_line(a, b);
+                mono.add_line(a, b);
+            }
+            let mut xs: Vec<i64> = (0..50).map(|_| rng.random_range(-30..30)).collect();
+            xs.sort();
+            for x in xs {
+                let expected = lines.iter().map(|&(a, b)| a * x + b).min();
+                assert_eq!(expected, cht.query(x));
+                assert_eq!(expected, mono.query_monotone(x));
+            }
+        }
+    }
+}
@@ -0,0 +1,324 @@
+use super::SegmentOp;
+use super::segment_tree::to_pair;
+use alloc::{vec, vec::Vec};
+
+/// A handle to a version of a [`PersistentSegmentTree`].
+/// It is only meaningful for the tree that created it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Version(u32);
+
+struct Node<T> {
+    v: T,
+    left: u32,
+    right: u32,
+}
+
+/// A persistent segment tree over `n` elements.
+///
+/// Every point update creates a new version in `O(lg n)` time and space, leaving the old versions intact,
+/// and every version can be queried in `O(lg n)` time. Versions are identified by lightweight [`Version`] handles.
+/// Nodes are allocated from a single contiguous arena rather than one `Box` each,
+/// and are never freed until the whole tree is dropped.
+///
+/// Node 0 is a shared subtree whose elements are all `Op::e()`,
+/// so that an empty tree is created in `O(1)` time.
+///
+/// Example (the `k`-th smallest element in a subarray):
+/// ```
+/// use basm_std::collections::{PersistentSegmentTree, SegmentOp};
+/// struct Count;
+/// impl SegmentOp for Count {
+///     type T = u32;
+///     type U = u32;
+///     fn e() -> u32 { 0 }
+///     fn combine(l: &u32, r: &u32) -> u32 { l + r }
+///     fn apply(v: &mut u32, u: &u32) { *v += u }
+/// }
+/// let a = [3, 1, 4, 1, 5, 9, 2, 6];
+/// let mut tree = PersistentSegmentTree::<Count>::new(10);
+/// // versions[i] counts the values in a[..i]
+/// let mut versions = vec![tree.initial()];
+/// for &x in &a {
+///     let ver = tree.update(*versions.last().unwrap(), x, 1);
+///     versions.push(ver);
+/// }
+/// // the 2nd smallest (0-indexed) value in a[2..7] = [4, 1, 5, 9, 2]
+/// let mut k = 2;
+/// let value = tree.descend_diff(versions[7], versions[2], |hi, lo| {
+///     if k < hi - lo { true } else { k -= hi - lo; false }
+/// });
+/// assert_eq!(4, value);
+/// assert_eq!(3, tree.query(versions[7], 2..=5) - tree.query(versions[2], 2..=5));
+/// ```
+pub struct PersistentSegmentTree<Op: SegmentOp> {
+    nodes: Vec<Node<Op::T>>,
+    n: usize,
+    initial: Version,
+}
+
+impl<Op: SegmentOp> PersistentSegmentTree<Op> {
+    /// Creates a tree of `n` elements, all initialized to `Op::e()`.
+    pub fn new(n: usize) -> Self {
+        Self {
+            nodes: vec![Node {
+                v: Op::e(),
+                left: 0,
+                right: 0,
+            }],
+            n,
+            initial: Version(0),
+        }
+    }
+
+    /// Creates a tree of `n` elements taken from `iter`.
+    /// If `iter` yields fewer than `n` elements, the rest are initialized to `Op::e()`.
+    pub fn from_iter<I>(n: usize, iter: I) -> Self
+    where
+        I: IntoIterator<Item = Op::T>,
+    {
+        let mut out = Self::new(n);
+        out.nodes.reserve(2 * n);
+        let mut iter = iter.into_iter();
+        if n > 0 {
+            out.initial = Version(out.build(0, n, &mut iter));
+        }
+        out
+    }
+
+    fn build<I: Iterator<Item = Op::T>>(&mut self, lo: usize, hi: usize, iter: &mut I) -> u32 {
+        if hi - lo == 1 {
+            let v = iter.next().unwrap_or_else(Op::e);
+            return self.alloc(v, 0, 0);
+        }
+        let mid = (lo + hi) / 2;
+        let left = self.build(lo, mid, iter);
+        let right = self.build(mid, hi, iter);
+        let v = Op::combine(&self.nodes[left as usize].v, &self.nodes[right as usize].v);
+        self.alloc(v, left, right)
+    }
+
+    fn alloc(&mut self, v: Op::T, left: u32, right: u32) -> u32 {
+        self.nodes.push(Node { v, left, right });
+        (self.nodes.len() - 1) as u32
+    }
+
+    /// Returns the number of elements.
+    pub fn len(&self) -> usize {
+        self.n
+    }
+
+    /// Returns `true` if the tree has no elements.
+    pub fn is_empty(&self) -> bool {
+        self.n == 0
+    }
+
+    /// Returns the number of nodes allocated so far, which bounds the memory usage.
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns the version the tree was created with.
+    pub fn initial(&self) -> Version {
+        self.initial
+    }
+
+    /// Returns a new version in which `u` is applied to the `i`-th element of version `ver`.
+    pub fn update<U>(&mut self, ver: Version, i: usize, u: U) -> Version
+    where
+        U: core::borrow::Borrow<Op::U>,
+    {
+        assert!(i < self.n);
+        Version(self.modify(ver.0, 0, self.n, i, &mut |v| Op::apply(v, u.borrow())))
+    }
+
+    /// Returns a new version in which the `i`-th element of version `ver` is replaced with `x`.
+    pub fn set(&mut self, ver: Version, i: usize, x: Op::T) -> Version {
+        assert!(i < self.n);
+        let mut x = Some(x);
+        Version(self.modify(ver.0, 0, self.n, i, &mut |v| *v = x.take().unwrap()))
+    }
+
+    fn modify(
+        &mut self,
+        x: u32,
+        lo: usize,
+        hi: usize,
+        i: usize,
+        f: &mut impl FnMut(&mut Op::T),
+    ) -> u32 {
+        if hi - lo == 1 {
+            let mut v = self.nodes[x as usize].v.clone();
+            f(&mut v);
+            return self.alloc(v, 0, 0);
+        }
+        let mid = (lo + hi) / 2;
+        let Node { left, right, .. } = self.nodes[x as usize];
+        let (left, right) = if i < mid {
+            (self.modify(left, lo, mid, i, f), right)
+        } else {
+            (left, self.modify(right, mid, hi, i, f))
+        };
+        let v = Op::combine(&self.nodes[left as usize].v, &self.nodes[right as usize].v);
+        self.alloc(v, left, right)
+    }
+
+    /// Returns the `i`-th element of version `ver`.
+    pub fn get(&self, ver: Version, i: usize) -> Op::T {
+        assert!(i < self.n);
+        let (mut x, mut lo, mut hi) = (ver.0, 0, self.n);
+        while hi - lo > 1 {
+            let mid = (lo + hi) / 2;
+            let node = &self.nodes[x as usize];
+            (x, lo, hi) = if i < mid {
+                (node.left, lo, mid)
+            } else {
+                (node.right, mid, hi)
+            };
+        }
+        self.nodes[x as usize].v.clone()
+    }
+
+    /// Combines the elements of version `ver` in `range`.
+    pub fn query<B>(&self, ver: Version, range: B) -> Op::T
+    where
+        B: core::ops::RangeBounds<usize>,
+    {
+        let (l, r) = to_pair(range, self.n);
+        if l == r {
+            return Op::e();
+        }
+        self.query_rec(ver.0, 0, self.n, l, r)
+    }
+
+    fn query_rec(&self, x: u32, lo: usize, hi: usize, l: usize, r: usize) -> Op::T {
+        let node = &self.nodes[x as usize];
+        if l <= lo && hi <= r {
+            return node.v.clone();
+        }
+        let mid = (lo + hi) / 2;
+        if r <= mid {
+            self.query_rec(node.left, lo, mid, l, r)
+        } else if mid <= l {
+            self.query_rec(node.right, mid, hi, l, r)
+        } else {
+            Op::combine(
+                &self.query_rec(node.left, lo, mid, l, r),
+                &self.query_rec(node.right, mid, hi, l, r),
+            )
+        }
+    }
+
+    /// Walks down versions `hi` and `lo` simultaneously from the root to a leaf, and returns the index of the leaf.
+    /// At each internal node, `go_left` is called with the values of the left children in `hi` and `lo`;
+    /// the walk proceeds to the left child if it returns `true`, and to the right child otherwise.
+    ///
+    /// When `hi` is derived from `lo` by point updates, this finds the `k`-th element of the difference,
+    /// e.g., the `k`-th smallest value in a subarray (see the example on [`PersistentSegmentTree`]).
+    /// This function will panic if the tree is empty.
+    pub fn descend_diff<P>(&self, hi: Version, lo: Version, mut go_left: P) -> usize
+    where
+        P: FnMut(&Op::T, &Op::T) -> bool,
+    {
+        assert!(self.n > 0);
+        let (mut x, mut y) = (hi.0 as usize, lo.0 as usize);
+        let (mut l, mut r) = (0, self.n);
+        while r - l > 1 {
+            let mid = (l + r) / 2;
+            let (nx, ny) = (&self.nodes[x], &self.nodes[y]);
+            let (lx, ly) = (nx.left as usize, ny.left as usize);
+            if go_left(&self.nodes[lx].v, &self.nodes[ly].v) {
+                (x, y, r) = (lx, ly, mid);
+            } else {
+                (x, y, l) = (nx.right as usize, ny.right as usize, mid);
+            }
+        }
+        l
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+
+    struct Sum;
+    impl SegmentOp for Sum {
+        type T = i64;
+        type U = i64;
+        fn e() -> i64 {
+            0
+        }
+        fn combine(l: &i64, r: &i64) -> i64 {
+            l + r
+        }
+        fn apply(v: &mut i64, u: &i64) {
+            *v += u;
+        }
+    }
+
+    #[test]
+    fn check_persistent_segment_tree() {
+        let mut rng = rand::rngs::SmallRng::seed_from_u64(16);
+        for n in [1, 2, 7, 64, 100] {
+            let init: Vec<i64> = (0..n).map(|_| rng.random_range(-100..100)).collect();
+            let mut tree = PersistentSegmentTree::<Sum>::from_iter(n, init.iter().copied());
+            let mut versions = vec![(tree.initial(), init)];
+            for _ in 0..500 {
+                let (ver, arr) = &versions[rng.random_range(0..versions.len())];
+                let (ver, mut arr) = (*ver, arr.clone());
+                let i = rng.random_range(0..n);
+                let x = rng.random_range(-100..100);
+                let new_ver = if rng.random_bool(0.5) {
+                    arr[i] += x;
+                    tree.update(ver, i, x)
+                } else {
+                    arr[i] = x;
+                    tree.set(ver, i, x)
+                };
+                versions.push((new_ver, arr));
+                let (ver, arr) = &versions[rng.random_range(0..versions.len())];
+                let (mut l, mut r) = (rng.random_range(0..=n), rng.random_range(0..=n));
+                if l > r {
+                    (l, r) = (r, l);
+                }
+                assert_eq!(arr[l..r].iter().sum::<i64>(), tree.query(*ver, l..r));
+                let i = rng.random_range(0..n);
+                assert_eq!(arr[i], tree.get(*ver, i));
+            }
+        }
+    }
+
+    #[test]
+    fn check_persistent_segment_tree_kth() {
+        let mut rng = rand::rngs::SmallRng::seed_from_u64(17);
+        let (n, m) = (300, 50);
+        let a: Vec<usize> = (0..n).map(|_| rng.random_range(0..m)).collect();
+        let mut tree = PersistentSegmentTree::<Sum>::new(m);
+        let mut versions = vec![tree.initial()];
+        for &x in &a {
+            let ver = tree.update(*versions.last().unwrap(), x, 1);
+            versions.push(ver);
+        }
+        assert!(tree.node_count() <= 1 + n * (m.ilog2() as usize + 2));
+        for _ in 0..1000 {
+            let (mut l, mut r) = (rng.random_range(0..n), rng.random_range(0..n));
+            if l > r {
+                (l, r) = (r, l);
+            }
+            r += 1;
+            let mut sorted = a[l..r].to_vec();
+            sorted.sort();
+            let k = rng.random_range(0..r - l);
+            let mut rem = k as i64;
+            let found = tree.descend_diff(versions[r], versions[l], |hi, lo| {
+                if rem < hi - lo {
+                    true
+                } else {
+                    rem -= hi - lo;
+                    false
+                }
+            });
+            assert_eq!(sorted[k], found);
+        }
+    }
+}
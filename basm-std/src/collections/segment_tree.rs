@@ -105,6 +105,317 @@ impl<Op: SegmentOp> SegmentTree<Op> {
     }
 }
 
+/// Operations for [`LazySegmentTree`].
+///
+/// `T` is the value type, which forms a monoid under `combine` with the identity `e()`.
+/// `U` is the lazy tag type, which forms a monoid under `compose` with the identity `id()`.
+/// The tags must act on the values as monoid homomorphisms, i.e.,
+/// `apply(combine(l, r), u) = combine(apply(l, u), apply(r, u))` and applying `compose(u1, u2)`
+/// equals applying `u2` first and then `u1`. If a tag depends on the length of the segment
+/// (e.g., range add with range sum), keep the length inside `T`.
+pub trait LazySegmentOp {
+    type T: Clone;
+    type U: Clone;
+    fn e() -> Self::T;
+    fn combine(l: &Self::T, r: &Self::T) -> Self::T;
+    fn id() -> Self::U;
+    fn apply(v: &mut Self::T, u: &Self::U);
+    fn compose(u1: &Self::U, u2: &Self::U) -> Self::U;
+}
+
+pub(crate) fn to_pair<B>(range: B, size: usize) -> (usize, usize)
+where
+    B: core::ops::RangeBounds<usize>,
+{
+    use core::ops::Bound::*;
+    let l = match range.start_bound() {
+        Included(&x) => x,
+        Excluded(&x) => x + 1,
+        Unbounded => 0,
+    };
+    let r = match range.end_bound() {
+        Included(&x) => x + 1,
+        Excluded(&x) => x,
+        Unbounded => size,
+    };
+    assert!(l <= r && r <= size);
+    (l, r)
+}
+
+/// A segment tree supporting range updates and range queries by lazy propagation,
+/// both in `O(lg n)` time.
+///
+/// Example:
+/// ```
+/// use basm_std::collections::{LazySegmentOp, LazySegmentTree};
+/// // range add, range minimum
+/// struct AddMin;
+/// impl LazySegmentOp for AddMin {
+///     type T = i64;
+///     type U = i64;
+///     fn e() -> i64 { i64::MAX }
+///     fn combine(l: &i64, r: &i64) -> i64 { *l.min(r) }
+///     fn id() -> i64 { 0 }
+///     fn apply(v: &mut i64, u: &i64) { *v = v.saturating_add(*u) }
+///     fn compose(u1: &i64, u2: &i64) -> i64 { u1 + u2 }
+/// }
+/// let mut tree = LazySegmentTree::<AddMin>::from_iter(5, [3, 1, 4, 1, 5]);
+/// tree.apply(1..4, 10);
+/// assert_eq!(3, tree.query(..));
+/// assert_eq!(11, tree.query(1..=3));
+/// assert_eq!(4, tree.max_right(1, |&x| x >= 6)); // [11, 14, 11] is the longest run from 1
+/// ```
+pub struct LazySegmentTree<Op: LazySegmentOp> {
+    v: Vec<Op::T>,
+    lazy: Vec<Op::U>,
+    size: usize,
+    n: usize,
+    log: u32,
+}
+
+impl<Op: LazySegmentOp> LazySegmentTree<Op> {
+    /// Creates a tree of `n` elements, all initialized to `Op::e()`.
+    pub fn new(n: usize) -> Self {
+        Self::from_iter(n, core::iter::empty())
+    }
+
+    /// Creates a tree of `n` elements taken from `iter`.
+    /// If `iter` yields fewer than `n` elements, the rest are initialized to `Op::e()`.
+    pub fn from_iter<I>(n: usize, iter: I) -> Self
+    where
+        I: IntoIterator<Item = Op::T>,
+    {
+        let off = n.next_power_of_two();
+        let mut v = vec![Op::e(); off];
+        v.extend(iter.into_iter().take(n));
+        v.resize(off * 2, Op::e());
+        for i in (1..off).rev() {
+            v[i] = Op::combine(&v[i * 2], &v[i * 2 + 1]);
+        }
+        Self {
+            v,
+            lazy: vec![Op::id(); off],
+            size: n,
+            n: off,
+            log: off.trailing_zeros(),
+        }
+    }
+
+    /// Returns the number of elements.
+    pub fn len(&self) -> usize {
+        self.size
+    }
+
+    /// Returns `true` if the tree has no elements.
+    pub fn is_empty(&self) -> bool {
+        self.size == 0
+    }
+
+    fn pull(&mut self, k: usize) {
+        self.v[k] = Op::combine(&self.v[k * 2], &self.v[k * 2 + 1]);
+    }
+
+    fn apply_node(&mut self, k: usize, u: &Op::U) {
+        Op::apply(&mut self.v[k], u);
+        if k < self.n {
+            self.lazy[k] = Op::compose(u, &self.lazy[k]);
+        }
+    }
+
+    fn push(&mut self, k: usize) {
+        let u = core::mem::replace(&mut self.lazy[k], Op::id());
+        self.apply_node(k * 2, &u);
+        self.apply_node(k * 2 + 1, &u);
+    }
+
+    /// Returns the `i`-th element.
+    pub fn get(&mut self, i: usize) -> Op::T {
+        assert!(i < self.size);
+        let i = i + self.n;
+        for d in (1..=self.log).rev() {
+            self.push(i >> d);
+        }
+        self.v[i].clone()
+    }
+
+    /// Replaces the `i`-th element with `x`.
+    pub fn set(&mut self, i: usize, x: Op::T) {
+        assert!(i < self.size);
+        let i = i + self.n;
+        for d in (1..=self.log).rev() {
+            self.push(i >> d);
+        }
+        self.v[i] = x;
+        for d in 1..=self.log {
+            self.pull(i >> d);
+        }
+    }
+
+    /// Combines the elements in `range`.
+    pub fn query<B>(&mut self, range: B) -> Op::T
+    where
+        B: core::ops::RangeBounds<usize>,
+    {
+        let (l, r) = to_pair(range, self.size);
+        if l == r {
+            return Op::e();
+        }
+        let (mut l, mut r) = (l + self.n, r + self.n);
+        for d in (1..=self.log).rev() {
+            if ((l >> d) << d) != l {
+                self.push(l >> d);
+            }
+            if ((r >> d) << d) != r {
+                self.push((r - 1) >> d);
+            }
+        }
+        let mut lsum = Op::e();
+        let mut rsum = Op::e();
+        while l < r {
+            if l & 1 != 0 {
+                lsum = Op::combine(&lsum, &self.v[l]);
+                l += 1;
+            }
+            if r & 1 != 0 {
+                r -= 1;
+                rsum = Op::combine(&self.v[r], &rsum);
+            }
+            l >>= 1;
+            r >>= 1;
+        }
+        Op::combine(&lsum, &rsum)
+    }
+
+    /// Applies `u` to every element in `range`.
+    pub fn apply<B, U>(&mut self, range: B, u: U)
+    where
+        B: core::ops::RangeBounds<usize>,
+        U: core::borrow::Borrow<Op::U>,
+    {
+        let (l, r) = to_pair(range, self.size);
+        if l == r {
+            return;
+        }
+        let u = u.borrow();
+        let (l, r) = (l + self.n, r + self.n);
+        for d in (1..=self.log).rev() {
+            if ((l >> d) << d) != l {
+                self.push(l >> d);
+            }
+            if ((r >> d) << d) != r {
+                self.push((r - 1) >> d);
+            }
+        }
+        let (mut l2, mut r2) = (l, r);
+        while l2 < r2 {
+            if l2 & 1 != 0 {
+                self.apply_node(l2, u);
+                l2 += 1;
+            }
+            if r2 & 1 != 0 {
+                r2 -= 1;
+                self.apply_node(r2, u);
+            }
+            l2 >>= 1;
+            r2 >>= 1;
+        }
+        for d in 1..=self.log {
+            if ((l >> d) << d) != l {
+                self.pull(l >> d);
+            }
+            if ((r >> d) << d) != r {
+                self.pull((r - 1) >> d);
+            }
+        }
+    }
+
+    /// Returns the largest `r` such that `pred(query(l..r))` is `true`,
+    /// assuming that `pred` is monotone (once `false`, it stays `false` as `r` grows).
+    ///
+    /// `pred(&Op::e())` must be `true`.
+    pub fn max_right<P>(&mut self, l: usize, pred: P) -> usize
+    where
+        P: Fn(&Op::T) -> bool,
+    {
+        assert!(l <= self.size);
+        debug_assert!(pred(&Op::e()));
+        if l == self.size {
+            return self.size;
+        }
+        let mut l = l + self.n;
+        for d in (1..=self.log).rev() {
+            self.push(l >> d);
+        }
+        let mut sum = Op::e();
+        loop {
+            while l.is_multiple_of(2) {
+                l >>= 1;
+            }
+            let test = Op::combine(&sum, &self.v[l]);
+            if !pred(&test) {
+                while l < self.n {
+                    self.push(l);
+                    l *= 2;
+                    let test = Op::combine(&sum, &self.v[l]);
+                    if pred(&test) {
+                        sum = test;
+                        l += 1;
+                    }
+                }
+                return (l - self.n).min(self.size);
+            }
+            sum = test;
+            l += 1;
+            if l.is_power_of_two() {
+                return self.size;
+            }
+        }
+    }
+
+    /// Returns the smallest `l` such that `pred(query(l..r))` is `true`,
+    /// assuming that `pred` is monotone (once `false`, it stays `false` as `l` decreases).
+    ///
+    /// `pred(&Op::e())` must be `true`.
+    pub fn min_left<P>(&mut self, r: usize, pred: P) -> usize
+    where
+        P: Fn(&Op::T) -> bool,
+    {
+        assert!(r <= self.size);
+        debug_assert!(pred(&Op::e()));
+        if r == 0 {
+            return 0;
+        }
+        let mut r = r + self.n;
+        for d in (1..=self.log).rev() {
+            self.push((r - 1) >> d);
+        }
+        let mut sum = Op::e();
+        loop {
+            r -= 1;
+            while r > 1 && r % 2 == 1 {
+                r >>= 1;
+            }
+            let test = Op::combine(&self.v[r], &sum);
+            if !pred(&test) {
+                while r < self.n {
+                    self.push(r);
+                    r = r * 2 + 1;
+                    let test = Op::combine(&self.v[r], &sum);
+                    if pred(&test) {
+                        sum = test;
+                        r -= 1;
+                    }
+                }
+                return r + 1 - self.n;
+            }
+            sum = test;
+            if r.is_power_of_two() {
+                return 0;
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -130,4 +441,69 @@ mod test {
         assert_eq!(tree.n, 4);
         assert_eq!(&tree.v[1..], [6, 3, 3, 1, 2, 3, 0]);
     }
+
+    struct AddSum {}
+
+    impl LazySegmentOp for AddSum {
+        type T = (i64, i64);
+        type U = i64;
+        fn e() -> Self::T {
+            (0, 0)
+        }
+        fn combine(l: &Self::T, r: &Self::T) -> Self::T {
+            (l.0 + r.0, l.1 + r.1)
+        }
+        fn id() -> Self::U {
+            0
+        }
+        fn apply(v: &mut Self::T, u: &Self::U) {
+            v.0 += u * v.1;
+        }
+        fn compose(u1: &Self::U, u2: &Self::U) -> Self::U {
+            u1 + u2
+        }
+    }
+
+    #[test]
+    fn check_lazy_segment_tree() {
+        use rand::{Rng, SeedableRng};
+        let mut rng = rand::rngs::SmallRng::seed_from_u64(12);
+        for n in [0usize, 1, 2, 5, 16, 37] {
+            let mut naive: Vec<i64> = (0..n).map(|_| rng.random_range(0..100)).collect();
+            let mut tree = LazySegmentTree::<AddSum>::from_iter(n, naive.iter().map(|&x| (x, 1)));
+            assert_eq!(n, tree.len());
+            for _ in 0..500 {
+                let l = rng.random_range(0..=n);
+                let r = rng.random_range(l..=n);
+                match rng.random_range(0..5) {
+                    0 => {
+                        let u = rng.random_range(0..50);
+                        tree.apply(l..r, u);
+                        naive[l..r].iter_mut().for_each(|x| *x += u);
+                    }
+                    1 if l < n => {
+                        let x = rng.random_range(0..100);
+                        tree.set(l, (x, 1));
+                        naive[l] = x;
+                    }
+                    2 if l < n => assert_eq!(naive[l], tree.get(l).0),
+                    3 => {
+                        /* prefix sums are monotone since every element is nonnegative */
+                        let bound = rng.random_range(0..1000);
+                        let expected = (l..=n)
+                            .rev()
+                            .find(|&k| naive[l..k].iter().sum::<i64>() <= bound)
+                            .unwrap();
+                        assert_eq!(expected, tree.max_right(l, |v| v.0 <= bound));
+                        let expected = (0..=r)
+                            .find(|&k| naive[k..r].iter().sum::<i64>() <= bound)
+                            .unwrap();
+                        assert_eq!(expected, tree.min_left(r, |v| v.0 <= bound));
+                    }
+                    _ => assert_eq!(naive[l..r].iter().sum::<i64>(), tree.query(l..r).0),
+                }
+            }
+            assert_eq!(naive.iter().sum::<i64>(), tree.query(..).0);
+        }
+    }
 }
@@ -0,0 +1,205 @@
+use super::SegmentOp;
+use super::segment_tree::to_pair;
+use alloc::{vec, vec::Vec};
+
+/// A sparse table answering static range queries in `O(1)` time after `O(n lg n)` preprocessing.
+///
+/// The op types of [`SegmentTree`](super::SegmentTree) are reused; `Op::U` and `Op::apply` are not used.
+/// `Op::combine` must be idempotent (`combine(x, x) = x`), as in min, max, gcd, bitwise and/or,
+/// since a query combines two possibly overlapping blocks.
+/// For ops which are not idempotent, use [`DisjointSparseTable`].
+///
+/// Example:
+/// ```
+/// use basm_std::collections::{SegmentOp, SparseTable};
+/// use basm_std::math::gcd;
+/// struct Gcd;
+/// impl SegmentOp for Gcd {
+///     type T = u64;
+///     type U = ();
+///     fn e() -> u64 { 0 }
+///     fn combine(l: &u64, r: &u64) -> u64 { gcd(*l, *r) }
+///     fn apply(_v: &mut u64, _u: &()) {}
+/// }
+/// let table = SparseTable::<Gcd>::new(&[12, 18, 24, 9, 30]);
+/// assert_eq!(6, table.query(..3));
+/// assert_eq!(3, table.query(1..4));
+/// assert_eq!(0, table.query(2..2));
+/// ```
+pub struct SparseTable<Op: SegmentOp> {
+    // table[k][i] = combine of the elements in [i, i + 2**k)
+    table: Vec<Vec<Op::T>>,
+}
+
+impl<Op: SegmentOp> SparseTable<Op> {
+    /// Builds a table over the elements of `a`.
+    pub fn new(a: &[Op::T]) -> Self {
+        let mut table = vec![a.to_vec()];
+        let mut k = 1;
+        while 2 * k <= a.len() {
+            let prev = table.last().unwrap();
+            let next = (0..=a.len() - 2 * k)
+                .map(|i| Op::combine(&prev[i], &prev[i + k]))
+                .collect();
+            table.push(next);
+            k *= 2;
+        }
+        Self { table }
+    }
+
+    /// Returns the number of elements.
+    pub fn len(&self) -> usize {
+        self.table[0].len()
+    }
+
+    /// Returns `true` if the table has no elements.
+    pub fn is_empty(&self) -> bool {
+        self.table[0].is_empty()
+    }
+
+    /// Combines the elements in `range`. Returns `Op::e()` if `range` is empty.
+    pub fn query<B>(&self, range: B) -> Op::T
+    where
+        B: core::ops::RangeBounds<usize>,
+    {
+        let (l, r) = to_pair(range, self.len());
+        if l == r {
+            return Op::e();
+        }
+        let k = (r - l).ilog2() as usize;
+        Op::combine(&self.table[k][l], &self.table[k][r - (1 << k)])
+    }
+}
+
+/// A disjoint sparse table answering static range queries in `O(1)` time after `O(n lg n)` preprocessing.
+///
+/// Unlike [`SparseTable`], `Op::combine` only needs to be associative; each query combines exactly two
+/// disjoint blocks, so it works for sums, products, matrix products and other non-idempotent ops.
+/// The op types of [`SegmentTree`](super::SegmentTree) are reused; `Op::U` and `Op::apply` are not used.
+///
+/// Example:
+/// ```
+/// use basm_std::collections::{DisjointSparseTable, SegmentOp};
+/// struct Sum;
+/// impl SegmentOp for Sum {
+///     type T = i64;
+///     type U = ();
+///     fn e() -> i64 { 0 }
+///     fn combine(l: &i64, r: &i64) -> i64 { l + r }
+///     fn apply(_v: &mut i64, _u: &()) {}
+/// }
+/// let table = DisjointSparseTable::<Sum>::new(&[3, 1, 4, 1, 5]);
+/// assert_eq!(6, table.query(1..4));
+/// assert_eq!(14, table.query(..));
+/// ```
+pub struct DisjointSparseTable<Op: SegmentOp> {
+    // For the blocks of size 2**(k + 1), table[k][i] = combine of [i, mid) if i < mid, or [mid, i] otherwise,
+    // where mid is the middle of the block containing i.
+    table: Vec<Vec<Op::T>>,
+    n: usize,
+}
+
+impl<Op: SegmentOp> DisjointSparseTable<Op> {
+    /// Builds a table over the elements of `a`.
+    pub fn new(a: &[Op::T]) -> Self {
+        let n = a.len();
+        let levels = if n <= 1 {
+            1
+        } else {
+            (n - 1).ilog2() as usize + 1
+        };
+        let mut table = Vec::with_capacity(levels);
+        for k in 0..levels {
+            let half = 1 << k;
+            let mut row = a.to_vec();
+            for mid in (half..n).step_by(2 * half) {
+                for i in (mid - half..mid - 1).rev() {
+                    row[i] = Op::combine(&a[i], &row[i + 1]);
+                }
+                for i in mid + 1..n.min(mid + half) {
+                    row[i] = Op::combine(&row[i - 1], &a[i]);
+                }
+            }
+            table.push(row);
+        }
+        Self { table, n }
+    }
+
+    /// Returns the number of elements.
+    pub fn len(&self) -> usize {
+        self.n
+    }
+
+    /// Returns `true` if the table has no elements.
+    pub fn is_empty(&self) -> bool {
+        self.n == 0
+    }
+
+    /// Combines the elements in `range`. Returns `Op::e()` if `range` is empty.
+    pub fn query<B>(&self, range: B) -> Op::T
+    where
+        B: core::ops::RangeBounds<usize>,
+    {
+        let (l, r) = to_pair(range, self.n);
+        if l == r {
+            return Op::e();
+        }
+        let r = r - 1;
+        if l == r {
+            return self.table[0][l].clone();
+        }
+        let k = (l ^ r).ilog2() as usize;
+        Op::combine(&self.table[k][l], &self.table[k][r])
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+
+    struct Min;
+    impl SegmentOp for Min {
+        type T = i32;
+        type U = ();
+        fn e() -> i32 {
+            i32::MAX
+        }
+        fn combine(l: &i32, r: &i32) -> i32 {
+            *l.min(r)
+        }
+        fn apply(_v: &mut i32, _u: &()) {}
+    }
+
+    // Concatenation of strings, which is associative but neither commutative nor idempotent
+    struct Concat;
+    impl SegmentOp for Concat {
+        type T = Vec<u8>;
+        type U = ();
+        fn e() -> Vec<u8> {
+            vec![]
+        }
+        fn combine(l: &Vec<u8>, r: &Vec<u8>) -> Vec<u8> {
+            [l.as_slice(), r.as_slice()].concat()
+        }
+        fn apply(_v: &mut Vec<u8>, _u: &()) {}
+    }
+
+    #[test]
+    fn check_sparse_tables() {
+        let mut rng = rand::rngs::SmallRng::seed_from_u64(19);
+        for n in [0, 1, 2, 3, 8, 9, 31, 100] {
+            let a: Vec<i32> = (0..n).map(|_| rng.random_range(-1000..1000)).collect();
+            let b: Vec<Vec<u8>> = (0..n as u8).map(|i| vec![i]).collect();
+            let min_table = SparseTable::<Min>::new(&a);
+            let concat_table = DisjointSparseTable::<Concat>::new(&b);
+            for l in 0..=n {
+                for r in l..=n {
+                    let expected = a[l..r].iter().copied().min().unwrap_or(i32::MAX);
+                    assert_eq!(expected, min_table.query(l..r));
+                    assert_eq!(b[l..r].concat(), concat_table.query(l..r));
+                }
+            }
+        }
+    }
+}
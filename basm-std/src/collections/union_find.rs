@@ -187,3 +187,334 @@ impl UnionFind {
         pu != pv
     }
 }
+
+/// A group used by [`WeightedUnionFind`] to describe potential differences.
+///
+/// `combine` must be associative with the identity `e()`, and `inv(x)` must be the inverse of `x`.
+/// The group need not be commutative.
+pub trait GroupOp {
+    type T: Clone;
+    fn e() -> Self::T;
+    fn combine(l: &Self::T, r: &Self::T) -> Self::T;
+    fn inv(x: &Self::T) -> Self::T;
+}
+
+/// A union-find which additionally keeps a potential `p(u)` for each element,
+/// known up to a common offset within each component.
+/// Relations are of the form `p(v) = p(u) * w`, where `*` is `G::combine`
+/// (e.g., `p(v) - p(u) = w` for the additive group of integers).
+///
+/// Both `unite` and `diff` take amortized `O(α(n))` time.
+///
+/// Example:
+/// ```
+/// use basm_std::collections::{GroupOp, WeightedUnionFind};
+/// struct Add;
+/// impl GroupOp for Add {
+///     type T = i64;
+///     fn e() -> i64 { 0 }
+///     fn combine(l: &i64, r: &i64) -> i64 { l + r }
+///     fn inv(x: &i64) -> i64 { -x }
+/// }
+/// let mut uf = WeightedUnionFind::<Add>::new(4);
+/// assert!(uf.unite(0, 1, 5)); // p(1) - p(0) = 5
+/// assert!(uf.unite(1, 2, -2)); // p(2) - p(1) = -2
+/// assert_eq!(Some(3), uf.diff(0, 2));
+/// assert_eq!(None, uf.diff(0, 3));
+/// assert!(!uf.unite(2, 0, 0)); // contradicts p(0) - p(2) = -3
+/// ```
+pub struct WeightedUnionFind<G: GroupOp> {
+    up: Vec<u32>,
+    rank: Vec<u32>,
+    // weight[u] = p(up[u])^-1 * p(u)
+    weight: Vec<G::T>,
+    connected_component_count: usize,
+}
+
+impl<G: GroupOp> WeightedUnionFind<G> {
+    /// Creates a new instance of `WeightedUnionFind` with length `n`.
+    pub fn new(n: usize) -> Self {
+        Self {
+            up: (0..n as u32).collect(),
+            rank: vec![1; n],
+            weight: vec![G::e(); n],
+            connected_component_count: n,
+        }
+    }
+
+    /// Returns the number of elements in the current instance.
+    pub fn len(&self) -> usize {
+        self.up.len()
+    }
+
+    /// Returns `true` if the current instance contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.up.is_empty()
+    }
+
+    /// Returns the number of connected components.
+    pub fn connected_component_count(&self) -> usize {
+        self.connected_component_count
+    }
+
+    /// Finds the representative `r` of `u`, and returns `(r, p(r)^-1 * p(u))`.
+    pub fn find(&mut self, u: usize) -> (usize, G::T) {
+        let p = self.up[u] as usize;
+        if p == u {
+            return (u, G::e());
+        }
+        // Union by rank bounds the recursion depth by O(lg n)
+        let (r, wp) = self.find(p);
+        self.weight[u] = G::combine(&wp, &self.weight[u]);
+        self.up[u] = r as u32;
+        (r, self.weight[u].clone())
+    }
+
+    /// Returns `true` if `u` and `v` are in the same component.
+    pub fn same(&mut self, u: usize, v: usize) -> bool {
+        self.find(u).0 == self.find(v).0
+    }
+
+    /// Returns `p(u)^-1 * p(v)` if `u` and `v` are in the same component, or `None` otherwise.
+    pub fn diff(&mut self, u: usize, v: usize) -> Option<G::T> {
+        let ((ru, a), (rv, b)) = (self.find(u), self.find(v));
+        (ru == rv).then(|| G::combine(&G::inv(&a), &b))
+    }
+
+    /// Adds the relation `p(v) = p(u) * w`.
+    ///
+    /// Returns `false` if it contradicts the relations added so far, in which case nothing changes.
+    /// Returns `true` otherwise.
+    pub fn unite(&mut self, u: usize, v: usize, w: G::T) -> bool
+    where
+        G::T: PartialEq,
+    {
+        let ((ru, a), (rv, b)) = (self.find(u), self.find(v));
+        if ru == rv {
+            return G::combine(&a, &w) == b;
+        }
+        // p(rv) = p(ru) * (a * w * b^-1)
+        let mut t = G::combine(&G::combine(&a, &w), &G::inv(&b));
+        let (mut ru, mut rv) = (ru, rv);
+        if self.rank[ru] < self.rank[rv] {
+            (ru, rv) = (rv, ru);
+            t = G::inv(&t);
+        }
+        self.up[rv] = ru as u32;
+        self.weight[rv] = t;
+        if self.rank[ru] == self.rank[rv] {
+            self.rank[ru] += 1;
+        }
+        self.connected_component_count -= 1;
+        true
+    }
+}
+
+/// A union-find without path compression, whose unions can be undone in the reverse order.
+/// This is typically used for offline dynamic connectivity, or divide and conquer over time.
+///
+/// Union by size bounds the depth by `O(lg n)`, so `find` and `try_union` take `O(lg n)` time,
+/// and each undone union takes `O(1)` time.
+///
+/// Example:
+/// ```
+/// use basm_std::collections::RollbackUnionFind;
+/// let mut uf = RollbackUnionFind::new(4);
+/// uf.try_union(0, 1);
+/// let snapshot = uf.snapshot();
+/// uf.try_union(1, 2);
+/// uf.try_union(2, 3);
+/// assert_eq!(1, uf.connected_component_count());
+/// uf.rollback(snapshot);
+/// assert!(uf.same(0, 1) && !uf.same(1, 2));
+/// assert_eq!(3, uf.connected_component_count());
+/// ```
+#[derive(Default)]
+pub struct RollbackUnionFind {
+    up: Vec<u32>,
+    size: Vec<u32>,
+    // Roots which were attached to another root, in order
+    history: Vec<u32>,
+    connected_component_count: usize,
+}
+
+impl RollbackUnionFind {
+    /// Creates a new instance of `RollbackUnionFind` with length `n`.
+    pub fn new(n: usize) -> Self {
+        Self {
+            up: (0..n as u32).collect(),
+            size: vec![1; n],
+            history: vec![],
+            connected_component_count: n,
+        }
+    }
+
+    /// Returns the number of elements in the current instance.
+    pub fn len(&self) -> usize {
+        self.up.len()
+    }
+
+    /// Returns `true` if the current instance contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.up.is_empty()
+    }
+
+    /// Returns the number of connected components.
+    pub fn connected_component_count(&self) -> usize {
+        self.connected_component_count
+    }
+
+    /// Finds the representative of `u`.
+    pub fn find(&self, mut u: usize) -> usize {
+        while u != self.up[u] as usize {
+            u = self.up[u] as usize;
+        }
+        u
+    }
+
+    /// Returns `true` if `u` and `v` are in the same component.
+    pub fn same(&self, u: usize, v: usize) -> bool {
+        self.find(u) == self.find(v)
+    }
+
+    /// Returns the size of the component containing `u`.
+    pub fn component_size(&self, u: usize) -> usize {
+        self.size[self.find(u)] as usize
+    }
+
+    /// Tries to unite `u` and `v`.
+    ///
+    /// Returns `true` if a new union is created, `false` otherwise.
+    /// Only a new union is recorded in the history.
+    pub fn try_union(&mut self, u: usize, v: usize) -> bool {
+        let (mut pu, mut pv) = (self.find(u), self.find(v));
+        if pu == pv {
+            return false;
+        }
+        if self.size[pu] < self.size[pv] {
+            core::mem::swap(&mut pu, &mut pv);
+        }
+        self.up[pv] = pu as u32;
+        self.size[pu] += self.size[pv];
+        self.history.push(pv as u32);
+        self.connected_component_count -= 1;
+        true
+    }
+
+    /// Returns a snapshot of the current state, to be passed to `rollback`.
+    pub fn snapshot(&self) -> usize {
+        self.history.len()
+    }
+
+    /// Undoes the most recent union. Returns `false` if there is nothing to undo.
+    pub fn undo(&mut self) -> bool {
+        let Some(pv) = self.history.pop() else {
+            return false;
+        };
+        let pv = pv as usize;
+        let pu = self.up[pv] as usize;
+        self.size[pu] -= self.size[pv];
+        self.up[pv] = pv as u32;
+        self.connected_component_count += 1;
+        true
+    }
+
+    /// Undoes every union made after `snapshot` was taken.
+    pub fn rollback(&mut self, snapshot: usize) {
+        assert!(snapshot <= self.history.len());
+        while self.history.len() > snapshot {
+            self.undo();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+
+    #[test]
+    fn check_weighted_union_find() {
+        // Permutations of 3 elements under composition, a non-commutative group
+        struct Perm;
+        impl GroupOp for Perm {
+            type T = [u8; 3];
+            fn e() -> [u8; 3] {
+                [0, 1, 2]
+            }
+            fn combine(l: &[u8; 3], r: &[u8; 3]) -> [u8; 3] {
+                [l[r[0] as usize], l[r[1] as usize], l[r[2] as usize]]
+            }
+            fn inv(x: &[u8; 3]) -> [u8; 3] {
+                let mut out = [0; 3];
+                for i in 0..3 {
+                    out[x[i] as usize] = i as u8;
+                }
+                out
+            }
+        }
+        const PERMS: [[u8; 3]; 6] = [
+            [0, 1, 2],
+            [0, 2, 1],
+            [1, 0, 2],
+            [1, 2, 0],
+            [2, 0, 1],
+            [2, 1, 0],
+        ];
+        let mut rng = rand::rngs::SmallRng::seed_from_u64(18);
+        let n = 30;
+        let hidden: Vec<[u8; 3]> = (0..n).map(|_| PERMS[rng.random_range(0..6)]).collect();
+        let rel = |u: usize, v: usize| Perm::combine(&Perm::inv(&hidden[u]), &hidden[v]);
+        let mut uf = WeightedUnionFind::<Perm>::new(n);
+        let mut naive = UnionFind::new(n);
+        for _ in 0..200 {
+            let (u, v) = (rng.random_range(0..n), rng.random_range(0..n));
+            if rng.random_bool(0.2) {
+                // A random relation within a component is accepted only if it is consistent
+                let w = PERMS[rng.random_range(0..6)];
+                if naive.find(u) == naive.find(v) {
+                    assert_eq!(w == rel(u, v), uf.unite(u, v, w));
+                }
+            } else {
+                assert!(uf.unite(u, v, rel(u, v)));
+                naive.try_union(u, v);
+            }
+            let (a, b) = (rng.random_range(0..n), rng.random_range(0..n));
+            let expected = (naive.find(a) == naive.find(b)).then(|| rel(a, b));
+            assert_eq!(expected, uf.diff(a, b));
+            assert_eq!(naive.cc_count(), uf.connected_component_count());
+        }
+    }
+
+    #[test]
+    fn check_rollback_union_find() {
+        let mut rng = rand::rngs::SmallRng::seed_from_u64(19);
+        let n = 40;
+        let mut uf = RollbackUnionFind::new(n);
+        let mut edges: Vec<(usize, usize)> = vec![];
+        let mut snapshots: Vec<(usize, usize)> = vec![];
+        for _ in 0..2000 {
+            match rng.random_range(0..4) {
+                0 => snapshots.push((uf.snapshot(), edges.len())),
+                1 => {
+                    if let Some((s, len)) = snapshots.pop() {
+                        uf.rollback(s);
+                        edges.truncate(len);
+                    }
+                }
+                _ => {
+                    let (u, v) = (rng.random_range(0..n), rng.random_range(0..n));
+                    uf.try_union(u, v);
+                    edges.push((u, v));
+                }
+            }
+            let mut naive = UnionFind::new(n);
+            for &(u, v) in &edges {
+                naive.try_union(u, v);
+            }
+            assert_eq!(naive.cc_count(), uf.connected_component_count());
+            let (a, b) = (rng.random_range(0..n), rng.random_range(0..n));
+            assert_eq!(naive.find(a) == naive.find(b), uf.same(a, b));
+        }
+    }
+}
@@ -1,6 +1,10 @@
+pub mod bipartite;
+pub mod hungarian;
+pub mod matching;
 pub mod maxflow;
 pub mod mcmf;
 pub mod scc;
+pub mod shortest_path;
 use crate::collections::JaggedVec;
 
 pub trait DfsTarget<T> {
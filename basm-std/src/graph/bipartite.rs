@@ -0,0 +1,210 @@
+use alloc::collections::VecDeque;
+use alloc::vec;
+use alloc::vec::Vec;
+
+const NONE: usize = usize::MAX;
+
+/// Maximum bipartite matching with the Hopcroft–Karp algorithm, which runs in `O(m sqrt(n))` time.
+///
+/// Left vertices are numbered `0..n_left` and right vertices `0..n_right`, independently.
+/// As in [`FlowGraph`](super::maxflow::FlowGraph), the matching is kept in the graph:
+/// edges added after [`BipartiteMatching::max_matching`] are picked up by calling it again,
+/// which only augments the current matching.
+///
+/// Example:
+/// ```
+/// use basm_std::graph::bipartite::BipartiteMatching;
+/// let mut g = BipartiteMatching::new(3, 3);
+/// g.add_edge(0, 0);
+/// g.add_edge(0, 1);
+/// g.add_edge(1, 0);
+/// g.add_edge(2, 0);
+/// assert_eq!(2, g.max_matching());
+/// assert_eq!(Some(1), g.match_left(0));
+/// let (left, right) = g.min_vertex_cover();
+/// assert_eq!((vec![0], vec![0]), (left, right));
+/// ```
+#[derive(Clone)]
+pub struct BipartiteMatching {
+    adj: Vec<Vec<usize>>,
+    match_l: Vec<usize>,
+    match_r: Vec<usize>,
+    dist: Vec<usize>,
+    it: Vec<usize>,
+}
+
+impl BipartiteMatching {
+    /// Creates a graph with `n_left` left vertices, `n_right` right vertices and no edges.
+    pub fn new(n_left: usize, n_right: usize) -> Self {
+        Self {
+            adj: vec![vec![]; n_left],
+            match_l: vec![NONE; n_left],
+            match_r: vec![NONE; n_right],
+            dist: vec![0; n_left],
+            it: vec![0; n_left],
+        }
+    }
+    /// Returns the number of left vertices.
+    pub fn n_left(&self) -> usize {
+        self.match_l.len()
+    }
+    /// Returns the number of right vertices.
+    pub fn n_right(&self) -> usize {
+        self.match_r.len()
+    }
+    /// Adds an edge between the left vertex `l` and the right vertex `r`.
+    pub fn add_edge(&mut self, l: usize, r: usize) {
+        assert!(l < self.n_left() && r < self.n_right());
+        self.adj[l].push(r);
+    }
+    /// Computes a maximum matching in place, and returns its size.
+    pub fn max_matching(&mut self) -> usize {
+        while self.bfs() {
+            self.it.fill(0);
+            for u in 0..self.n_left() {
+                if self.match_l[u] == NONE {
+                    self.dfs(u);
+                }
+            }
+        }
+        self.match_l.iter().filter(|&&r| r != NONE).count()
+    }
+    // Layers the left vertices by the length of the shortest alternating path from a free left vertex,
+    // and returns whether there is an augmenting path.
+    fn bfs(&mut self) -> bool {
+        let mut queue = VecDeque::new();
+        for u in 0..self.n_left() {
+            if self.match_l[u] == NONE {
+                self.dist[u] = 0;
+                queue.push_back(u);
+            } else {
+                self.dist[u] = NONE;
+            }
+        }
+        let mut found = false;
+        while let Some(u) = queue.pop_front() {
+            for &v in &self.adj[u] {
+                let w = self.match_r[v];
+                if w == NONE {
+                    found = true;
+                } else if self.dist[w] == NONE {
+                    self.dist[w] = self.dist[u] + 1;
+                    queue.push_back(w);
+                }
+            }
+        }
+        found
+    }
+    fn dfs(&mut self, u: usize) -> bool {
+        while self.it[u] < self.adj[u].len() {
+            let v = self.adj[u][self.it[u]];
+            self.it[u] += 1;
+            let w = self.match_r[v];
+            if w == NONE || (self.dist[w] == self.dist[u] + 1 && self.dfs(w)) {
+                self.match_l[u] = v;
+                self.match_r[v] = u;
+                return true;
+            }
+        }
+        self.dist[u] = NONE;
+        false
+    }
+    /// Returns the right vertex matched with the left vertex `l`, if any.
+    pub fn match_left(&self, l: usize) -> Option<usize> {
+        Some(self.match_l[l]).filter(|&r| r != NONE)
+    }
+    /// Returns the left vertex matched with the right vertex `r`, if any.
+    pub fn match_right(&self, r: usize) -> Option<usize> {
+        Some(self.match_r[r]).filter(|&l| l != NONE)
+    }
+    /// Returns the matched pairs `(l, r)` in increasing order of `l`.
+    pub fn pairs(&self) -> Vec<(usize, usize)> {
+        (0..self.n_left())
+            .filter_map(|l| self.match_left(l).map(|r| (l, r)))
+            .collect()
+    }
+    /// Returns a minimum vertex cover as (left vertices, right vertices), each in increasing order,
+    /// for the maximum matching computed by [`BipartiteMatching::max_matching`].
+    /// By König's theorem, its size equals the size of the maximum matching.
+    ///
+    /// The complement of the cover is a maximum independent set.
+    pub fn min_vertex_cover(&self) -> (Vec<usize>, Vec<usize>) {
+        // Vertices reachable from the free left vertices by alternating paths
+        let mut visited_l = vec![false; self.n_left()];
+        let mut visited_r = vec![false; self.n_right()];
+        let mut queue = VecDeque::new();
+        for (u, &v) in self.match_l.iter().enumerate() {
+            if v == NONE {
+                visited_l[u] = true;
+                queue.push_back(u);
+            }
+        }
+        while let Some(u) = queue.pop_front() {
+            for &v in &self.adj[u] {
+                if !visited_r[v] {
+                    visited_r[v] = true;
+                    let w = self.match_r[v];
+                    if w != NONE && !visited_l[w] {
+                        visited_l[w] = true;
+                        queue.push_back(w);
+                    }
+                }
+            }
+        }
+        let left = (0..self.n_left()).filter(|&u| !visited_l[u]).collect();
+        let right = (0..self.n_right()).filter(|&v| visited_r[v]).collect();
+        (left, right)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::graph::maxflow::FlowGraph;
+    use rand::{Rng, SeedableRng};
+
+    #[test]
+    fn check_bipartite_matching() {
+        let mut rng = rand::rngs::SmallRng::seed_from_u64(22);
+        for _ in 0..300 {
+            let (n_left, n_right) = (rng.random_range(1..15), rng.random_range(1..15));
+            let m = rng.random_range(0..50);
+            let edges: Vec<(usize, usize)> = (0..m)
+                .map(|_| (rng.random_range(0..n_left), rng.random_range(0..n_right)))
+                .collect();
+            let mut flow = FlowGraph::new();
+            let (s, t) = (n_left + n_right, n_left + n_right + 1);
+            for l in 0..n_left {
+                flow.add_edge(s, l, 1, false);
+            }
+            for r in 0..n_right {
+                flow.add_edge(n_left + r, t, 1, false);
+            }
+            let mut g = BipartiteMatching::new(n_left, n_right);
+            let half = m / 2;
+            for &(l, r) in &edges[..half] {
+                g.add_edge(l, r);
+                flow.add_edge(l, n_left + r, 1, false);
+            }
+            assert_eq!(flow.max_flow(s, t) as usize, g.max_matching());
+            for &(l, r) in &edges[half..] {
+                g.add_edge(l, r);
+                flow.add_edge(l, n_left + r, 1, false);
+            }
+            let size = g.max_matching();
+            assert_eq!(flow.max_flow(s, t) as usize, size);
+
+            let pairs = g.pairs();
+            assert_eq!(size, pairs.len());
+            for &(l, r) in &pairs {
+                assert!(edges.contains(&(l, r)));
+                assert_eq!(Some(l), g.match_right(r));
+            }
+            let (left, right) = g.min_vertex_cover();
+            assert_eq!(size, left.len() + right.len());
+            for &(l, r) in &edges {
+                assert!(left.contains(&l) || right.contains(&r));
+            }
+        }
+    }
+}
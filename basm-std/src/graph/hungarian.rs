@@ -0,0 +1,137 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+/// Solves the assignment problem on an `n` by `m` cost matrix with `n <= m` using the Hungarian algorithm,
+/// in `O(n**2 m)` time.
+///
+/// Each row is assigned a distinct column so that the total cost is minimized.
+/// Returns the minimum total cost and, for each row, the column assigned to it.
+/// For the maximum, negate the costs and the total.
+///
+/// This is the method of choice for dense instances; for sparse ones,
+/// [`MinCostFlowGraph`](super::mcmf::MinCostFlowGraph) may be faster.
+///
+/// Example:
+/// ```
+/// use basm_std::graph::hungarian::hungarian;
+/// let cost = vec![
+///     vec![4, 1, 3],
+///     vec![2, 0, 5],
+///     vec![3, 2, 2],
+/// ];
+/// assert_eq!((5, vec![1, 0, 2]), hungarian(&cost));
+/// ```
+pub fn hungarian(cost: &[Vec<i64>]) -> (i64, Vec<usize>) {
+    let n = cost.len();
+    if n == 0 {
+        return (0, vec![]);
+    }
+    let m = cost[0].len();
+    assert!(n <= m, "there must be at least as many columns as rows");
+    assert!(cost.iter().all(|row| row.len() == m));
+
+    // 1-indexed; column 0 is a sentinel holding the row being inserted
+    let mut u = vec![0i64; n + 1];
+    let mut v = vec![0i64; m + 1];
+    let mut p = vec![0usize; m + 1]; // p[j] = the row assigned to column j, or 0
+    let mut way = vec![0usize; m + 1];
+    let mut minv = vec![0i64; m + 1];
+    let mut used = vec![false; m + 1];
+    for i in 1..=n {
+        p[0] = i;
+        let mut j0 = 0;
+        minv.fill(i64::MAX);
+        used.fill(false);
+        // Grow a shortest alternating path tree from row i until it reaches a free column
+        loop {
+            used[j0] = true;
+            let i0 = p[j0];
+            let mut delta = i64::MAX;
+            let mut j1 = 0;
+            for j in 1..=m {
+                if !used[j] {
+                    let cur = cost[i0 - 1][j - 1] - u[i0] - v[j];
+                    if cur < minv[j] {
+                        minv[j] = cur;
+                        way[j] = j0;
+                    }
+                    if minv[j] < delta {
+                        delta = minv[j];
+                        j1 = j;
+                    }
+                }
+            }
+            for j in 0..=m {
+                if used[j] {
+                    u[p[j]] += delta;
+                    v[j] -= delta;
+                } else {
+                    minv[j] -= delta;
+                }
+            }
+            j0 = j1;
+            if p[j0] == 0 {
+                break;
+            }
+        }
+        // Augment along the path
+        while j0 != 0 {
+            let j1 = way[j0];
+            p[j0] = p[j1];
+            j0 = j1;
+        }
+    }
+
+    let mut assignment = vec![0; n];
+    for j in 1..=m {
+        if p[j] != 0 {
+            assignment[p[j] - 1] = j - 1;
+        }
+    }
+    let total = (0..n).map(|i| cost[i][assignment[i]]).sum();
+    (total, assignment)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+
+    // Minimum over all injections of rows into columns
+    fn brute_force(cost: &[Vec<i64>], i: usize, used: &mut [bool]) -> i64 {
+        if i == cost.len() {
+            return 0;
+        }
+        let mut best = i64::MAX;
+        for j in 0..used.len() {
+            if !used[j] {
+                used[j] = true;
+                best = best.min(cost[i][j] + brute_force(cost, i + 1, used));
+                used[j] = false;
+            }
+        }
+        best
+    }
+
+    #[test]
+    fn check_hungarian() {
+        let mut rng = rand::rngs::SmallRng::seed_from_u64(22);
+        for _ in 0..300 {
+            let n = rng.random_range(1..6);
+            let m = rng.random_range(n..8);
+            let cost: Vec<Vec<i64>> = (0..n)
+                .map(|_| (0..m).map(|_| rng.random_range(-100..100)).collect())
+                .collect();
+            let (total, assignment) = hungarian(&cost);
+            assert_eq!(brute_force(&cost, 0, &mut vec![false; m]), total);
+            let mut seen = vec![false; m];
+            for (i, &j) in assignment.iter().enumerate() {
+                assert!(!seen[j]);
+                seen[j] = true;
+                assert!(i < n);
+            }
+            assert_eq!(total, (0..n).map(|i| cost[i][assignment[i]]).sum::<i64>());
+        }
+        assert_eq!((0, vec![]), hungarian(&[]));
+    }
+}
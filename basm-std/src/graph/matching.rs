@@ -0,0 +1,758 @@
+use alloc::collections::VecDeque;
+use alloc::vec;
+use alloc::vec::Vec;
+
+const NONE: usize = usize::MAX;
+
+/// The result of general graph matching.
+pub struct MatchingResult {
+    /// The number of matched pairs.
+    pub size: usize,
+    /// `mate[u]`: The node matched with node `u`, or `None` if `u` is unmatched.
+    pub mate: Vec<Option<usize>>,
+}
+
+impl MatchingResult {
+    /// Returns the matched pairs `(u, v)` with `u < v`, in increasing order of `u`.
+    pub fn pairs(&self) -> Vec<(usize, usize)> {
+        self.mate
+            .iter()
+            .enumerate()
+            .filter_map(|(u, &v)| v.filter(|&v| u < v).map(|v| (u, v)))
+            .collect()
+    }
+}
+
+/// Maximum cardinality matching on general (not necessarily bipartite) undirected graphs,
+/// using Edmonds' blossom algorithm in `O(n**3)` time.
+///
+/// For bipartite graphs, [`BipartiteMatching`](super::bipartite::BipartiteMatching) is faster.
+///
+/// Example:
+/// ```
+/// use basm_std::graph::matching::MatchingGraph;
+/// // A triangle with a pendant vertex
+/// let mut g = MatchingGraph::new();
+/// g.add_edge(0, 1);
+/// g.add_edge(1, 2);
+/// g.add_edge(2, 0);
+/// g.add_edge(2, 3);
+/// let result = g.solve();
+/// assert_eq!(2, result.size);
+/// assert_eq!(Some(3), result.mate[2]);
+/// ```
+pub struct MatchingGraph {
+    /// Adjacency list of the graph
+    adj: Vec<Vec<usize>>,
+    /// Number of edges added
+    edge_count: usize,
+}
+
+impl Default for MatchingGraph {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MatchingGraph {
+    /// Create a new, empty graph.
+    pub fn new() -> Self {
+        MatchingGraph {
+            adj: vec![],
+            edge_count: 0,
+        }
+    }
+
+    /// Ensures the graph has at least `n` nodes, so that nodes without any incident edges can be included in the final output.
+    ///
+    /// Depending on the edges inserted, the graph may have more than `n` nodes.
+    pub fn reserve(&mut self, n: usize) {
+        if self.adj.len() < n {
+            self.adj.resize(n, Vec::new());
+        }
+    }
+
+    /// Read-only access to the adjacency lists.
+    pub fn adj_list(&self) -> &Vec<Vec<usize>> {
+        &self.adj
+    }
+
+    /// Add an undirected edge between `u` and `v`.
+    /// Automatically resizes internal storage to accommodate higher node indices.
+    pub fn add_edge(&mut self, u: usize, v: usize) {
+        self.reserve(core::cmp::max(u, v) + 1);
+        self.adj[u].push(v);
+        self.adj[v].push(u);
+        self.edge_count += 1;
+    }
+
+    /// Computes a maximum cardinality matching.
+    pub fn solve(&self) -> MatchingResult {
+        let n = self.adj.len();
+        let mut state = BlossomState {
+            adj: &self.adj,
+            mate: vec![NONE; n],
+            p: vec![NONE; n],
+            base: (0..n).collect(),
+            used: vec![false; n],
+            blossom: vec![false; n],
+            queue: VecDeque::new(),
+        };
+        // Greedy initial matching
+        for u in 0..n {
+            if state.mate[u] == NONE
+                && let Some(&v) = self.adj[u]
+                    .iter()
+                    .find(|&&v| v != u && state.mate[v] == NONE)
+            {
+                state.mate[u] = v;
+                state.mate[v] = u;
+            }
+        }
+        for u in 0..n {
+            if state.mate[u] == NONE {
+                state.find_path(u);
+            }
+        }
+        let mate: Vec<Option<usize>> = state
+            .mate
+            .iter()
+            .map(|&v| Some(v).filter(|&v| v != NONE))
+            .collect();
+        let size = mate.iter().filter(|v| v.is_some()).count() / 2;
+        MatchingResult { size, mate }
+    }
+}
+
+struct BlossomState<'a> {
+    adj: &'a [Vec<usize>],
+    mate: Vec<usize>,
+    /// `p[v]`: The predecessor of an odd node `v` in the alternating tree
+    p: Vec<usize>,
+    /// `base[v]`: The base of the blossom containing `v`
+    base: Vec<usize>,
+    /// `used[v]`: Whether `v` is an even node of the alternating tree
+    used: Vec<bool>,
+    blossom: Vec<bool>,
+    queue: VecDeque<usize>,
+}
+
+impl BlossomState<'_> {
+    fn lca(&self, mut a: usize, mut b: usize) -> usize {
+        let mut visited = vec![false; self.mate.len()];
+        loop {
+            a = self.base[a];
+            visited[a] = true;
+            if self.mate[a] == NONE {
+                break;
+            }
+            a = self.p[self.mate[a]];
+        }
+        loop {
+            b = self.base[b];
+            if visited[b] {
+                return b;
+            }
+            b = self.p[self.mate[b]];
+        }
+    }
+
+    fn mark_path(&mut self, mut v: usize, b: usize, mut child: usize) {
+        while self.base[v] != b {
+            self.blossom[self.base[v]] = true;
+            self.blossom[self.base[self.mate[v]]] = true;
+            self.p[v] = child;
+            child = self.mate[v];
+            v = self.p[self.mate[v]];
+        }
+    }
+
+    /// Grows an alternating tree from the free node `root`, and augments the matching if possible.
+    fn find_path(&mut self, root: usize) -> bool {
+        let n = self.mate.len();
+        self.used.fill(false);
+        self.p.fill(NONE);
+        for (i, b) in self.base.iter_mut().enumerate() {
+            *b = i;
+        }
+        self.used[root] = true;
+        self.queue.clear();
+        self.queue.push_back(root);
+        while let Some(v) = self.queue.pop_front() {
+            for &to in self.adj[v].iter() {
+                if self.base[v] == self.base[to] || self.mate[v] == to {
+                    continue;
+                }
+                if to == root || (self.mate[to] != NONE && self.p[self.mate[to]] != NONE) {
+                    // An edge between two even nodes closes an odd cycle; contract it
+                    let cur_base = self.lca(v, to);
+                    self.blossom.fill(false);
+                    self.mark_path(v, cur_base, to);
+                    self.mark_path(to, cur_base, v);
+                    for i in 0..n {
+                        if self.blossom[self.base[i]] {
+                            self.base[i] = cur_base;
+                            if !self.used[i] {
+                                self.used[i] = true;
+                                self.queue.push_back(i);
+                            }
+                        }
+                    }
+                } else if self.p[to] == NONE {
+                    self.p[to] = v;
+                    if self.mate[to] == NONE {
+                        // Augment along the path from root to `to`
+                        let mut v = to;
+                        while v != NONE {
+                            let pv = self.p[v];
+                            let ppv = self.mate[pv];
+                            self.mate[v] = pv;
+                            self.mate[pv] = v;
+                            v = ppv;
+                        }
+                        return true;
+                    }
+                    self.used[self.mate[to]] = true;
+                    self.queue.push_back(self.mate[to]);
+                }
+            }
+        }
+        false
+    }
+}
+
+/// Maximum weight matching on general undirected graphs,
+/// using the primal-dual blossom algorithm in `O(n**3)` time and `O(n**2)` space.
+///
+/// The matching maximizes the total weight, not the number of pairs; edges of nonpositive weight are never used.
+/// To find the maximum weight among the maximum cardinality matchings, add a large constant
+/// (greater than `n` times the largest absolute weight) to every weight.
+///
+/// Example:
+/// ```
+/// use basm_std::graph::matching::WeightedMatchingGraph;
+/// // A path 0 - 1 - 2 - 3
+/// let mut g = WeightedMatchingGraph::new();
+/// g.add_edge(0, 1, 5);
+/// g.add_edge(1, 2, 8);
+/// g.add_edge(2, 3, 5);
+/// let (weight, result) = g.solve();
+/// assert_eq!((10, 2), (weight, result.size));
+/// assert_eq!(vec![(0, 1), (2, 3)], result.pairs());
+/// ```
+pub struct WeightedMatchingGraph {
+    /// Number of nodes
+    n: usize,
+    /// List of the edges added, as `(u, v, w)`
+    edges: Vec<(usize, usize, i64)>,
+}
+
+impl Default for WeightedMatchingGraph {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WeightedMatchingGraph {
+    /// Create a new, empty graph.
+    pub fn new() -> Self {
+        WeightedMatchingGraph {
+            n: 0,
+            edges: vec![],
+        }
+    }
+
+    /// Ensures the graph has at least `n` nodes, so that nodes without any incident edges can be included in the final output.
+    ///
+    /// Depending on the edges inserted, the graph may have more than `n` nodes.
+    pub fn reserve(&mut self, n: usize) {
+        self.n = core::cmp::max(self.n, n);
+    }
+
+    /// Add an undirected edge between `u` and `v` with weight `w`.
+    /// Automatically resizes internal storage to accommodate higher node indices.
+    /// Of parallel edges, only the heaviest one matters.
+    pub fn add_edge(&mut self, u: usize, v: usize, w: i64) {
+        self.reserve(core::cmp::max(u, v) + 1);
+        self.edges.push((u, v, w));
+    }
+
+    /// Computes a maximum weight matching.
+    ///
+    /// Returns `(total weight, matching)`.
+    pub fn solve(&self) -> (i64, MatchingResult) {
+        let n = self.n;
+        let mut state = WeightedBlossomState::new(n);
+        for &(u, v, w) in &self.edges {
+            if u != v && w > 0 {
+                let (u, v) = (u + 1, v + 1);
+                let w = core::cmp::max(w, state.g[u][v].w);
+                state.g[u][v].w = w;
+                state.g[v][u].w = w;
+            }
+        }
+        let weight = state.run();
+        let mut mate = vec![None; n];
+        for u in 1..=n {
+            if state.mate[u] != 0 {
+                mate[u - 1] = Some(state.mate[u] - 1);
+            }
+        }
+        let size = mate.iter().filter(|v| v.is_some()).count() / 2;
+        (weight, MatchingResult { size, mate })
+    }
+}
+
+#[derive(Clone, Copy)]
+struct WEdge {
+    u: usize,
+    v: usize,
+    w: i64,
+}
+
+/// The state of the weighted blossom algorithm.
+///
+/// Nodes are numbered from 1, and blossoms from `n + 1`; 0 means none.
+/// `g[x][y]` is the edge of the original graph realizing the tightest connection between `x` and `y`,
+/// which may be blossoms.
+struct WeightedBlossomState {
+    n: usize,
+    n_x: usize,
+    g: Vec<Vec<WEdge>>,
+    /// `lab[x]`: The dual variable of node or blossom `x`, doubled so that it stays integral
+    lab: Vec<i64>,
+    mate: Vec<usize>,
+    slack: Vec<usize>,
+    /// `st[x]`: The outermost blossom containing `x`
+    st: Vec<usize>,
+    pa: Vec<usize>,
+    /// `flower_from[b][x]`: The child of blossom `b` containing node `x`
+    flower_from: Vec<Vec<usize>>,
+    /// `s[x]`: 0 for even, 1 for odd and -1 for unlabeled
+    s: Vec<i8>,
+    vis: Vec<usize>,
+    vis_stamp: usize,
+    flower: Vec<Vec<usize>>,
+    queue: VecDeque<usize>,
+}
+
+impl WeightedBlossomState {
+    fn new(n: usize) -> Self {
+        let m = 2 * n + 1;
+        let mut g = vec![vec![WEdge { u: 0, v: 0, w: 0 }; m]; m];
+        for (u, row) in g.iter_mut().enumerate().take(n + 1) {
+            for (v, e) in row.iter_mut().enumerate().take(n + 1) {
+                (e.u, e.v) = (u, v);
+            }
+        }
+        Self {
+            n,
+            n_x: n,
+            g,
+            lab: vec![0; m],
+            mate: vec![0; m],
+            slack: vec![0; m],
+            st: (0..m).collect(),
+            pa: vec![0; m],
+            flower_from: vec![vec![0; n + 1]; m],
+            s: vec![-1; m],
+            vis: vec![0; m],
+            vis_stamp: 0,
+            flower: vec![vec![]; m],
+            queue: VecDeque::new(),
+        }
+    }
+
+    fn e_delta(&self, e: WEdge) -> i64 {
+        self.lab[e.u] + self.lab[e.v] - e.w * 2
+    }
+
+    fn update_slack(&mut self, u: usize, x: usize) {
+        if self.slack[x] == 0 || self.e_delta(self.g[u][x]) < self.e_delta(self.g[self.slack[x]][x])
+        {
+            self.slack[x] = u;
+        }
+    }
+
+    fn set_slack(&mut self, x: usize) {
+        self.slack[x] = 0;
+        for u in 1..=self.n {
+            if self.g[u][x].w > 0 && self.st[u] != x && self.s[self.st[u]] == 0 {
+                self.update_slack(u, x);
+            }
+        }
+    }
+
+    fn q_push(&mut self, x: usize) {
+        if x <= self.n {
+            self.queue.push_back(x);
+        } else {
+            for i in 0..self.flower[x].len() {
+                self.q_push(self.flower[x][i]);
+            }
+        }
+    }
+
+    fn set_st(&mut self, x: usize, b: usize) {
+        self.st[x] = b;
+        if x > self.n {
+            for i in 0..self.flower[x].len() {
+                self.set_st(self.flower[x][i], b);
+            }
+        }
+    }
+
+    /// Returns the position of `xr` in `flower[b]`, reversing the cycle if needed so that the position is even.
+    fn get_pr(&mut self, b: usize, xr: usize) -> usize {
+        let pr = self.flower[b].iter().position(|&x| x == xr).unwrap();
+        if pr % 2 == 1 {
+            self.flower[b][1..].reverse();
+            self.flower[b].len() - pr
+        } else {
+            pr
+        }
+    }
+
+    fn set_match(&mut self, u: usize, v: usize) {
+        self.mate[u] = self.g[u][v].v;
+        if u > self.n {
+            let e = self.g[u][v];
+            let xr = self.flower_from[u][e.u];
+            let pr = self.get_pr(u, xr);
+            for i in 0..pr {
+                let (a, b) = (self.flower[u][i], self.flower[u][i ^ 1]);
+                self.set_match(a, b);
+            }
+            self.set_match(xr, v);
+            self.flower[u].rotate_left(pr);
+        }
+    }
+
+    fn augment(&mut self, mut u: usize, mut v: usize) {
+        loop {
+            let xnv = self.st[self.mate[u]];
+            self.set_match(u, v);
+            if xnv == 0 {
+                return;
+            }
+            let next = self.st[self.pa[xnv]];
+            self.set_match(xnv, next);
+            (u, v) = (next, xnv);
+        }
+    }
+
+    fn get_lca(&mut self, mut u: usize, mut v: usize) -> usize {
+        self.vis_stamp += 1;
+        while u != 0 || v != 0 {
+            if u != 0 {
+                if self.vis[u] == self.vis_stamp {
+                    return u;
+                }
+                self.vis[u] = self.vis_stamp;
+                u = self.st[self.mate[u]];
+                if u != 0 {
+                    u = self.st[self.pa[u]];
+                }
+            }
+            core::mem::swap(&mut u, &mut v);
+        }
+        0
+    }
+
+    fn add_blossom(&mut self, u: usize, lca: usize, v: usize) {
+        let n = self.n;
+        let mut b = n + 1;
+        while b <= self.n_x && self.st[b] != 0 {
+            b += 1;
+        }
+        if b > self.n_x {
+            self.n_x += 1;
+        }
+        self.lab[b] = 0;
+        self.s[b] = 0;
+        self.mate[b] = self.mate[lca];
+        self.flower[b].clear();
+        self.flower[b].push(lca);
+        let mut x = u;
+        while x != lca {
+            let y = self.st[self.mate[x]];
+            self.flower[b].push(x);
+            self.flower[b].push(y);
+            self.q_push(y);
+            x = self.st[self.pa[y]];
+        }
+        self.flower[b][1..].reverse();
+        let mut x = v;
+        while x != lca {
+            let y = self.st[self.mate[x]];
+            self.flower[b].push(x);
+            self.flower[b].push(y);
+            self.q_push(y);
+            x = self.st[self.pa[y]];
+        }
+        self.set_st(b, b);
+        for x in 1..=self.n_x {
+            self.g[b][x].w = 0;
+            self.g[x][b].w = 0;
+        }
+        self.flower_from[b].fill(0);
+        for i in 0..self.flower[b].len() {
+            let xs = self.flower[b][i];
+            for x in 1..=self.n_x {
+                if self.g[b][x].w == 0 || self.e_delta(self.g[xs][x]) < self.e_delta(self.g[b][x]) {
+                    self.g[b][x] = self.g[xs][x];
+                    self.g[x][b] = self.g[x][xs];
+                }
+            }
+            for x in 1..=n {
+                if self.flower_from[xs][x] != 0 {
+                    self.flower_from[b][x] = xs;
+                }
+            }
+        }
+        self.set_slack(b);
+    }
+
+    fn expand_blossom(&mut self, b: usize) {
+        for i in 0..self.flower[b].len() {
+            let x = self.flower[b][i];
+            self.set_st(x, x);
+        }
+        let xr = self.flower_from[b][self.g[b][self.pa[b]].u];
+        let pr = self.get_pr(b, xr);
+        for i in (0..pr).step_by(2) {
+            let (xs, xns) = (self.flower[b][i], self.flower[b][i + 1]);
+            self.pa[xs] = self.g[xns][xs].u;
+            self.s[xs] = 1;
+            self.s[xns] = 0;
+            self.slack[xs] = 0;
+            self.set_slack(xns);
+            self.q_push(xns);
+        }
+        self.s[xr] = 1;
+        self.pa[xr] = self.pa[b];
+        for i in pr + 1..self.flower[b].len() {
+            let xs = self.flower[b][i];
+            self.s[xs] = -1;
+            self.set_slack(xs);
+        }
+        self.st[b] = 0;
+    }
+
+    fn on_found_edge(&mut self, e: WEdge) -> bool {
+        let (u, v) = (self.st[e.u], self.st[e.v]);
+        if self.s[v] == -1 {
+            self.pa[v] = e.u;
+            self.s[v] = 1;
+            let nu = self.st[self.mate[v]];
+            self.slack[v] = 0;
+            self.slack[nu] = 0;
+            self.s[nu] = 0;
+            self.q_push(nu);
+        } else if self.s[v] == 0 {
+            let lca = self.get_lca(u, v);
+            if lca == 0 {
+                self.augment(u, v);
+                self.augment(v, u);
+                return true;
+            }
+            self.add_blossom(u, lca, v);
+        }
+        false
+    }
+
+    /// Runs one phase, and returns whether the matching was augmented.
+    fn matching(&mut self) -> bool {
+        let n = self.n;
+        self.s[1..=self.n_x].fill(-1);
+        self.slack[1..=self.n_x].fill(0);
+        self.queue.clear();
+        for x in 1..=self.n_x {
+            if self.st[x] == x && self.mate[x] == 0 {
+                self.pa[x] = 0;
+                self.s[x] = 0;
+                self.q_push(x);
+            }
+        }
+        if self.queue.is_empty() {
+            return false;
+        }
+        loop {
+            while let Some(u) = self.queue.pop_front() {
+                if self.s[self.st[u]] == 1 {
+                    continue;
+                }
+                for v in 1..=n {
+                    if self.g[u][v].w > 0 && self.st[u] != self.st[v] {
+                        if self.e_delta(self.g[u][v]) == 0 {
+                            if self.on_found_edge(self.g[u][v]) {
+                                return true;
+                            }
+                        } else {
+                            self.update_slack(u, self.st[v]);
+                        }
+                    }
+                }
+            }
+            // Adjust the duals
+            let mut d = i64::MAX;
+            for b in n + 1..=self.n_x {
+                if self.st[b] == b && self.s[b] == 1 {
+                    d = d.min(self.lab[b] / 2);
+                }
+            }
+            for x in 1..=self.n_x {
+                if self.st[x] == x && self.slack[x] != 0 {
+                    let delta = self.e_delta(self.g[self.slack[x]][x]);
+                    if self.s[x] == -1 {
+                        d = d.min(delta);
+                    } else if self.s[x] == 0 {
+                        d = d.min(delta / 2);
+                    }
+                }
+            }
+            // Done when the dual of an even node would become zero
+            if (1..=n).any(|u| self.s[self.st[u]] == 0 && self.lab[u] <= d) {
+                return false;
+            }
+            for u in 1..=n {
+                match self.s[self.st[u]] {
+                    0 => self.lab[u] -= d,
+                    1 => self.lab[u] += d,
+                    _ => {}
+                }
+            }
+            for b in n + 1..=self.n_x {
+                if self.st[b] == b {
+                    match self.s[b] {
+                        0 => self.lab[b] += d * 2,
+                        1 => self.lab[b] -= d * 2,
+                        _ => {}
+                    }
+                }
+            }
+            self.queue.clear();
+            for x in 1..=self.n_x {
+                if self.st[x] == x
+                    && self.slack[x] != 0
+                    && self.st[self.slack[x]] != x
+                    && self.e_delta(self.g[self.slack[x]][x]) == 0
+                    && self.on_found_edge(self.g[self.slack[x]][x])
+                {
+                    return true;
+                }
+            }
+            for b in n + 1..=self.n_x {
+                if self.st[b] == b && self.s[b] == 1 && self.lab[b] == 0 {
+                    self.expand_blossom(b);
+                }
+            }
+        }
+    }
+
+    /// Runs the algorithm, and returns the total weight.
+    fn run(&mut self) -> i64 {
+        let n = self.n;
+        for u in 1..=n {
+            self.flower_from[u][u] = u;
+        }
+        let w_max = (1..=n)
+            .flat_map(|u| (1..=n).map(move |v| (u, v)))
+            .map(|(u, v)| self.g[u][v].w)
+            .max()
+            .unwrap_or(0);
+        self.lab[1..=n].fill(w_max);
+        while self.matching() {}
+        (1..=n)
+            .filter(|&u| self.mate[u] != 0 && self.mate[u] < u)
+            .map(|u| self.g[u][self.mate[u]].w)
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+
+    // dp[mask] = the maximum (size, weight) matching on the nodes in mask
+    fn brute_force(n: usize, w: &[Vec<i64>]) -> (Vec<usize>, Vec<i64>) {
+        let mut size = vec![0; 1 << n];
+        let mut weight = vec![0; 1 << n];
+        for mask in 1usize..1 << n {
+            let u = mask.trailing_zeros() as usize;
+            let rest = mask & !(1 << u);
+            (size[mask], weight[mask]) = (size[rest], weight[rest]);
+            for (v, &wv) in w[u].iter().enumerate().skip(u + 1) {
+                if rest >> v & 1 != 0 && wv > 0 {
+                    let other = rest & !(1 << v);
+                    size[mask] = size[mask].max(size[other] + 1);
+                    weight[mask] = weight[mask].max(weight[other] + wv);
+                }
+            }
+        }
+        (size, weight)
+    }
+
+    fn validate(result: &MatchingResult, w: &[Vec<i64>]) -> i64 {
+        let mut total = 0;
+        for (u, &v) in result.mate.iter().enumerate() {
+            if let Some(v) = v {
+                assert_eq!(Some(u), result.mate[v]);
+                assert!(w[u][v] > 0);
+                total += w[u][v];
+            }
+        }
+        assert_eq!(
+            2 * result.size,
+            result.mate.iter().filter(|v| v.is_some()).count()
+        );
+        assert_eq!(result.size, result.pairs().len());
+        total / 2
+    }
+
+    #[test]
+    fn check_general_matching() {
+        let mut rng = rand::rngs::SmallRng::seed_from_u64(23);
+        for _ in 0..500 {
+            let n = rng.random_range(1..=12);
+            let density = rng.random_range(0.1..0.9);
+            let max_w = if rng.random_bool(0.3) { 3 } else { 1000 };
+            let mut g = MatchingGraph::new();
+            let mut wg = WeightedMatchingGraph::new();
+            g.reserve(n);
+            wg.reserve(n);
+            let mut w = vec![vec![0i64; n]; n];
+            let all_pairs = (0..n).flat_map(|u| (u + 1..n).map(move |v| (u, v)));
+            for (u, v) in all_pairs {
+                if rng.random_bool(density) {
+                    let x = rng.random_range(1..=max_w);
+                    g.add_edge(u, v);
+                    wg.add_edge(v, u, x);
+                    // A lighter parallel edge is ignored
+                    wg.add_edge(u, v, x / 2);
+                    w[u][v] = x;
+                    w[v][u] = x;
+                }
+            }
+            // A self-loop and an edge of negative weight are ignored
+            wg.add_edge(0, 0, 100);
+            if n >= 2 {
+                wg.add_edge(0, 1, -5);
+            }
+            let (size, weight) = brute_force(n, &w);
+            let unit: Vec<Vec<i64>> = w
+                .iter()
+                .map(|row| row.iter().map(|&x| (x > 0) as i64).collect())
+                .collect();
+
+            let result = g.solve();
+            assert_eq!(size[(1 << n) - 1], result.size);
+            validate(&result, &unit);
+
+            let (total, result) = wg.solve();
+            assert_eq!(weight[(1 << n) - 1], total);
+            assert_eq!(total, validate(&result, &w));
+        }
+    }
+}
@@ -10,11 +10,45 @@ struct Edge {
     f: i64,
 }
 
+/// The algorithm used by [`FlowGraph`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MaxFlowAlgorithm {
+    /// FIFO-like push-relabel with global relabeling. A good default for general graphs.
+    #[default]
+    PushRelabel,
+    /// Dinic's algorithm, which runs in `O(m sqrt(n))` time on unit-capacity bipartite graphs
+    /// and is often the fastest on sparse graphs with small capacities.
+    Dinic,
+    /// Highest-label push-relabel with the gap heuristic, which runs in `O(n**2 sqrt(m))` time.
+    Hlpp,
+}
+
 /// Maximum flow solver without costs.
+///
+/// The flow is kept in the graph: [`FlowGraph::max_flow`] computes it in place, after which
+/// the flow on each edge and the minimum cut can be retrieved. Edges added afterwards are picked up
+/// by calling [`FlowGraph::max_flow`] again, which only augments the current flow.
+///
+/// Example:
+/// ```
+/// use basm_std::graph::maxflow::{FlowGraph, MaxFlowAlgorithm};
+/// let mut g = FlowGraph::with_algorithm(MaxFlowAlgorithm::Dinic);
+/// let a = g.add_edge(0, 1, 3, false);
+/// let b = g.add_edge(1, 2, 2, false);
+/// assert_eq!(2, g.max_flow(0, 2));
+/// assert_eq!((2, 2), (g.flow(a), g.flow(b)));
+/// assert_eq!(vec![b], g.cut_edges());
+/// g.add_edge(0, 2, 5, false);
+/// assert_eq!(7, g.max_flow(0, 2));
+/// ```
+#[derive(Clone)]
 pub struct FlowGraph {
     adj: Vec<Vec<usize>>,
     e: Vec<Edge>,
     edge_count: usize,
+    algorithm: MaxFlowAlgorithm,
+    // The source and the sink of the flow currently stored in `e`
+    terminals: Option<(usize, usize)>,
 }
 
 impl Default for FlowGraph {
@@ -25,13 +59,26 @@ impl Default for FlowGraph {
 
 impl FlowGraph {
     pub fn new() -> Self {
+        Self::with_algorithm(MaxFlowAlgorithm::default())
+    }
+    /// Creates an empty graph which computes flows with `algorithm`.
+    pub fn with_algorithm(algorithm: MaxFlowAlgorithm) -> Self {
         Self {
             adj: vec![],
             e: vec![],
             edge_count: 0,
+            algorithm,
+            terminals: None,
         }
     }
-    pub fn add_edge(&mut self, u: usize, v: usize, c: i64, bidirectional: bool) {
+    /// Changes the algorithm used by subsequent calls to [`FlowGraph::max_flow`].
+    pub fn set_algorithm(&mut self, algorithm: MaxFlowAlgorithm) {
+        self.algorithm = algorithm;
+    }
+    /// Adds an edge from `u` to `v` with capacity `c`, and returns its id.
+    /// Ids are assigned sequentially from 0.
+    /// If `bidirectional` is true, the edge can also carry up to `c` units from `v` to `u`.
+    pub fn add_edge(&mut self, u: usize, v: usize, c: i64, bidirectional: bool) -> usize {
         assert!(c >= 0);
         if self.adj.len() < u + 1 {
             self.adj.resize(u + 1, vec![]);
@@ -48,18 +95,104 @@ impl FlowGraph {
             f: 0,
         });
         self.edge_count += 1;
+        self.edge_count - 1
+    }
+    /// Returns the number of edges.
+    pub fn edge_count(&self) -> usize {
+        self.edge_count
     }
-    /// Solves the maximum flow problem with source `s` and sink `t`.
+    /// Returns the flow on the edge `id` in the direction it was added.
+    /// The flow is negative if a bidirectional edge carries flow backwards.
+    pub fn flow(&self, id: usize) -> i64 {
+        self.e[2 * id].f
+    }
+    /// Computes the maximum flow from `s` to `t` in place, and returns its value.
     /// `s` and `t` must be distinct.
     ///
-    /// Return value: (maximum flow value, vertices in s-cut, vertices in t-cut)
-    pub fn solve(&self, s: usize, t: usize) -> (i64, Vec<usize>, Vec<usize>) {
+    /// If the previous call had the same `s` and `t`, the current flow is augmented rather than
+    /// recomputed from scratch, so this is cheap after adding a few edges.
+    /// Otherwise, the flow is reset first.
+    pub fn max_flow(&mut self, s: usize, t: usize) -> i64 {
         assert!(s != t);
-        let (mut adj, mut e) = (self.adj.clone(), self.e.clone());
         let bound = max(s, t);
-        if bound >= adj.len() {
-            adj.resize(bound + 1, vec![]);
+        if bound >= self.adj.len() {
+            self.adj.resize(bound + 1, vec![]);
+        }
+        if self.terminals != Some((s, t)) {
+            for e in self.e.iter_mut() {
+                e.f = 0;
+            }
+            self.terminals = Some((s, t));
+        }
+        match self.algorithm {
+            MaxFlowAlgorithm::PushRelabel => self.push_relabel(s, t),
+            MaxFlowAlgorithm::Dinic => self.dinic(s, t),
+            MaxFlowAlgorithm::Hlpp => self.hlpp(s, t),
         }
+        -self.adj[t].iter().map(|&eid| self.e[eid].f).sum::<i64>()
+    }
+    /// Returns the vertices on the source side and the sink side of a minimum cut,
+    /// for the flow computed by the last call to [`FlowGraph::max_flow`].
+    ///
+    /// Return value: (vertices in s-cut, vertices in t-cut)
+    pub fn min_cut(&self) -> (Vec<usize>, Vec<usize>) {
+        let visited = self.source_side();
+        let mut s_cut = vec![];
+        let mut t_cut = vec![];
+        for (u, &visited_u) in visited.iter().enumerate() {
+            if visited_u {
+                s_cut.push(u);
+            } else {
+                t_cut.push(u);
+            }
+        }
+        (s_cut, t_cut)
+    }
+    /// Returns the ids of the edges crossing the minimum cut from the source side to the sink side,
+    /// for the flow computed by the last call to [`FlowGraph::max_flow`].
+    /// Their capacities sum to the maximum flow value.
+    pub fn cut_edges(&self) -> Vec<usize> {
+        let visited = self.source_side();
+        (0..self.edge_count)
+            .filter(|&id| {
+                let (fwd, bwd) = (&self.e[2 * id], &self.e[2 * id + 1]);
+                let (u, v) = (bwd.v, fwd.v);
+                (visited[u] && !visited[v] && fwd.c > 0) || (visited[v] && !visited[u] && bwd.c > 0)
+            })
+            .collect()
+    }
+    fn source_side(&self) -> Vec<bool> {
+        let (s, _) = self
+            .terminals
+            .expect("max_flow must be called before querying the cut");
+        let (adj, e) = (&self.adj, &self.e);
+        let mut visited = vec![false; adj.len()];
+        let mut queue = VecDeque::<usize>::new();
+        visited[s] = true;
+        queue.push_back(s);
+        while let Some(u) = queue.pop_front() {
+            for &eid in &adj[u] {
+                let v = e[eid].v;
+                if e[eid].c - e[eid].f > 0 && !visited[v] {
+                    visited[v] = true;
+                    queue.push_back(v);
+                }
+            }
+        }
+        visited
+    }
+    /// Solves the maximum flow problem with source `s` and sink `t`, leaving the graph untouched.
+    /// `s` and `t` must be distinct.
+    ///
+    /// Return value: (maximum flow value, vertices in s-cut, vertices in t-cut)
+    pub fn solve(&self, s: usize, t: usize) -> (i64, Vec<usize>, Vec<usize>) {
+        let mut g = self.clone();
+        let flow = g.max_flow(s, t);
+        let (s_cut, t_cut) = g.min_cut();
+        (flow, s_cut, t_cut)
+    }
+    fn push_relabel(&mut self, s: usize, t: usize) {
+        let (adj, e) = (&self.adj, &mut self.e);
         let n = adj.len();
         let m = self.edge_count;
 
@@ -81,8 +214,8 @@ impl FlowGraph {
         };
         for &eid in &adj[s] {
             // push preflow from s
-            let df = e[eid].c;
-            if df > 0 && push(&mut e, &mut p, s, eid, df) {
+            let df = e[eid].c - e[eid].f;
+            if df > 0 && push(e, &mut p, s, eid, df) {
                 active[0].push(e[eid].v);
                 head = 0;
             }
@@ -106,7 +239,7 @@ impl FlowGraph {
                         if h[u] == h[v] + 1 {
                             let df = min(p[u], e[eid].c - e[eid].f);
                             if df > 0 {
-                                if push(&mut e, &mut p, u, eid, df) {
+                                if push(e, &mut p, u, eid, df) {
                                     if active[h[v]].is_empty() {
                                         (next[h[u]], next[h[v]]) = (h[v], next[h[u]]);
                                     }
@@ -193,79 +326,340 @@ impl FlowGraph {
             }
         }
 
-        // Phase II: send excesses back to source via doing a DFS from the source
-        fn dfs_phase2(
-            adj: &Vec<Vec<usize>>,
+        // Phase II: send excesses back to source
+        return_excess(adj, e, &mut p, s, t);
+    }
+    fn dinic(&mut self, s: usize, t: usize) {
+        fn dfs(
+            adj: &[Vec<usize>],
             e: &mut [Edge],
-            p: &mut [i64],
-            h: &[usize],
-            visited: &mut [bool],
-            stack: &mut Vec<usize>,
+            level: &[usize],
+            it: &mut [usize],
             u: usize,
-        ) {
-            if visited[u] {
-                // Back-edge; cancel the minimal flow along the cycle
-                let mut df = i64::MAX;
-                for &eid in stack.iter().rev() {
-                    df = min(df, e[eid].f);
-                    if e[eid ^ 1].v == u {
-                        break;
-                    }
-                }
-                for &eid in stack.iter().rev() {
-                    e[eid].f -= df;
-                    e[eid ^ 1].f += df;
-                    if e[eid ^ 1].v == u {
-                        break;
+            t: usize,
+            limit: i64,
+        ) -> i64 {
+            if u == t {
+                return limit;
+            }
+            let mut pushed = 0;
+            while it[u] < adj[u].len() {
+                let eid = adj[u][it[u]];
+                let v = e[eid].v;
+                let r = e[eid].c - e[eid].f;
+                if r > 0 && level[v] == level[u] + 1 {
+                    let df = dfs(adj, e, level, it, v, t, min(limit - pushed, r));
+                    if df > 0 {
+                        e[eid].f += df;
+                        e[eid ^ 1].f -= df;
+                        pushed += df;
+                        if pushed == limit {
+                            // The edge may still have residual capacity; keep the iterator on it
+                            return pushed;
+                        }
                     }
                 }
-            } else {
-                // Continue on DFS
-                visited[u] = true;
+                it[u] += 1;
+            }
+            pushed
+        }
+        let (adj, e) = (&self.adj, &mut self.e);
+        let n = adj.len();
+        let mut level = vec![usize::MAX; n];
+        let mut it = vec![0usize; n];
+        loop {
+            // Build the level graph of the residual graph
+            level.fill(usize::MAX);
+            level[s] = 0;
+            let mut queue = VecDeque::<usize>::new();
+            queue.push_back(s);
+            while let Some(u) = queue.pop_front() {
                 for &eid in &adj[u] {
                     let v = e[eid].v;
-                    if e[eid].f > 0 && h[v] >= adj.len() {
-                        stack.push(eid);
-                        dfs_phase2(adj, e, p, h, visited, stack, v);
-                        stack.pop();
-                        let df = e[eid].f;
-                        e[eid].f -= df;
-                        e[eid ^ 1].f += df;
-                        p[u] += df;
-                        p[v] -= df;
+                    if e[eid].c - e[eid].f > 0 && level[v] == usize::MAX {
+                        level[v] = level[u] + 1;
+                        queue.push_back(v);
                     }
                 }
             }
+            if level[t] == usize::MAX {
+                break;
+            }
+            // Find a blocking flow
+            it.fill(0);
+            while dfs(adj, e, &level, &mut it, s, t, i64::MAX) > 0 {}
         }
-        let mut visited = vec![false; n];
-        let mut stack = vec![];
-        dfs_phase2(&adj, &mut e, &mut p, &h, &mut visited, &mut stack, s);
+    }
+    fn hlpp(&mut self, s: usize, t: usize) {
+        let (adj, e) = (&self.adj, &mut self.e);
+        let n = adj.len();
 
-        // Compute cut
-        let mut visited = vec![false; n];
+        // Exact distances to the sink; vertices which cannot reach the sink are labeled n
+        let mut h = vec![n; n];
+        h[t] = 0;
         let mut queue = VecDeque::<usize>::new();
-        visited[s] = true;
-        queue.push_back(s);
-        while !queue.is_empty() {
-            let u = *queue.front().unwrap();
-            queue.pop_front();
+        queue.push_back(t);
+        while let Some(u) = queue.pop_front() {
             for &eid in &adj[u] {
                 let v = e[eid].v;
-                if e[eid].c - e[eid].f > 0 && !visited[v] {
-                    visited[v] = true;
+                if e[eid ^ 1].c - e[eid ^ 1].f > 0 && h[v] == n && v != s {
+                    h[v] = h[u] + 1;
                     queue.push_back(v);
                 }
             }
         }
-        let mut s_cut = vec![];
-        let mut t_cut = vec![];
-        for (u, &visited_u) in visited.iter().enumerate().take(n) {
-            if visited_u {
-                s_cut.push(u);
+        h[s] = n;
+        // cnt[k] = the number of vertices labeled k, for k < n
+        let mut cnt = vec![0usize; n];
+        for &hu in &h {
+            if hu < n {
+                cnt[hu] += 1;
+            }
+        }
+
+        let mut p = vec![0i64; n];
+        let mut bucket = vec![vec![]; n];
+        let mut highest = 0;
+        for &eid in &adj[s] {
+            let (v, df) = (e[eid].v, e[eid].c - e[eid].f);
+            if df > 0 && v != s {
+                e[eid].f += df;
+                e[eid ^ 1].f -= df;
+                p[s] -= df;
+                p[v] += df;
+                if p[v] == df && v != t && h[v] < n {
+                    bucket[h[v]].push(v);
+                    highest = max(highest, h[v]);
+                }
+            }
+        }
+
+        let mut cur = vec![0usize; n];
+        loop {
+            while highest > 0 && bucket[highest].is_empty() {
+                highest -= 1;
+            }
+            let Some(u) = bucket[highest].pop() else {
+                break;
+            };
+            // Discharge u, until it has no excess or it can no longer reach the sink
+            while p[u] > 0 {
+                if cur[u] == adj[u].len() {
+                    let old = h[u];
+                    let mut new = 2 * n;
+                    for &eid in &adj[u] {
+                        if e[eid].c - e[eid].f > 0 {
+                            new = min(new, h[e[eid].v] + 1);
+                        }
+                    }
+                    cur[u] = 0;
+                    cnt[old] -= 1;
+                    if cnt[old] == 0 {
+                        // Gap heuristic: the vertices above the gap can no longer reach the sink
+                        for hv in h.iter_mut() {
+                            if old < *hv && *hv < n {
+                                cnt[*hv] -= 1;
+                                *hv = n;
+                            }
+                        }
+                        for b in bucket.iter_mut().skip(old + 1) {
+                            b.clear();
+                        }
+                        h[u] = n;
+                        break;
+                    }
+                    h[u] = min(new, n);
+                    if h[u] == n {
+                        break;
+                    }
+                    cnt[h[u]] += 1;
+                    continue;
+                }
+                let eid = adj[u][cur[u]];
+                let v = e[eid].v;
+                let r = e[eid].c - e[eid].f;
+                if r > 0 && h[u] == h[v] + 1 {
+                    let df = min(p[u], r);
+                    e[eid].f += df;
+                    e[eid ^ 1].f -= df;
+                    p[u] -= df;
+                    p[v] += df;
+                    if p[v] == df && v != s && v != t {
+                        bucket[h[v]].push(v);
+                        highest = max(highest, h[v]);
+                    }
+                    if p[u] == 0 {
+                        break;
+                    }
+                }
+                cur[u] += 1;
+            }
+        }
+
+        return_excess(adj, e, &mut p, s, t);
+    }
+}
+
+/// Turns a maximum preflow into a maximum flow, by sending the excess of every vertex other than `t` back to `s`.
+///
+/// From a vertex with excess, edges carrying flow are walked backwards until `s` is reached,
+/// and the flow on the path is reduced; if a vertex repeats, the flow on the cycle is cancelled instead.
+/// Since flows only decrease, each edge is skipped at most once, and this takes `O(nm)` time.
+fn return_excess(adj: &[Vec<usize>], e: &mut [Edge], p: &mut [i64], s: usize, t: usize) {
+    let n = adj.len();
+    let mut it = vec![0usize; n];
+    let mut pos = vec![usize::MAX; n];
+    let mut verts: Vec<usize> = vec![];
+    let mut eids: Vec<usize> = vec![];
+    for u in 0..n {
+        if u == s || u == t {
+            continue;
+        }
+        while p[u] > 0 {
+            verts.push(u);
+            pos[u] = 0;
+            loop {
+                let x = *verts.last().unwrap();
+                if x == s {
+                    // Reduce the flow on the path from s to u
+                    let df = eids.iter().fold(p[u], |df, &eid| min(df, -e[eid].f));
+                    for &eid in &eids {
+                        e[eid].f += df;
+                        e[eid ^ 1].f -= df;
+                    }
+                    p[u] -= df;
+                    break;
+                }
+                // Every vertex on the path other than s has inflow
+                while e[adj[x][it[x]]].f >= 0 {
+                    it[x] += 1;
+                }
+                let eid = adj[x][it[x]];
+                let y = e[eid].v;
+                if pos[y] == usize::MAX {
+                    pos[y] = verts.len();
+                    verts.push(y);
+                    eids.push(eid);
+                    continue;
+                }
+                // Cancel the flow on the cycle through y
+                let j = pos[y];
+                let df = eids[j..]
+                    .iter()
+                    .fold(-e[eid].f, |df, &eid| min(df, -e[eid].f));
+                e[eid].f += df;
+                e[eid ^ 1].f -= df;
+                for &eid in &eids[j..] {
+                    e[eid].f += df;
+                    e[eid ^ 1].f -= df;
+                }
+                for &v in &verts[j + 1..] {
+                    pos[v] = usize::MAX;
+                }
+                verts.truncate(j + 1);
+                eids.truncate(j);
+            }
+            for &v in &verts {
+                pos[v] = usize::MAX;
+            }
+            verts.clear();
+            eids.clear();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+
+    const ALGORITHMS: [MaxFlowAlgorithm; 3] = [
+        MaxFlowAlgorithm::PushRelabel,
+        MaxFlowAlgorithm::Dinic,
+        MaxFlowAlgorithm::Hlpp,
+    ];
+
+    // Checks capacity constraints, flow conservation and the cut certificate
+    fn validate(
+        g: &FlowGraph,
+        edges: &[(usize, usize, i64, bool)],
+        s: usize,
+        t: usize,
+        value: i64,
+    ) {
+        let n = g.adj.len();
+        let mut net = vec![0i64; n];
+        for (id, &(u, v, c, bidirectional)) in edges.iter().enumerate() {
+            let f = g.flow(id);
+            assert!(f <= c && f >= if bidirectional { -c } else { 0 });
+            net[u] -= f;
+            net[v] += f;
+        }
+        for (u, &x) in net.iter().enumerate() {
+            if u == s {
+                assert_eq!(-value, x);
+            } else if u == t {
+                assert_eq!(value, x);
             } else {
-                t_cut.push(u);
+                assert_eq!(0, x);
             }
         }
-        (p[t], s_cut, t_cut)
+        let (s_cut, t_cut) = g.min_cut();
+        assert!(s_cut.contains(&s) && t_cut.contains(&t));
+        let cut: i64 = g.cut_edges().iter().map(|&id| edges[id].2).sum();
+        assert_eq!(value, cut);
+    }
+
+    #[test]
+    fn check_maxflow_algorithms() {
+        let mut rng = rand::rngs::SmallRng::seed_from_u64(21);
+        for _ in 0..300 {
+            let n = rng.random_range(2..12);
+            let m = rng.random_range(0..40);
+            let unit = rng.random_bool(0.3);
+            let edges: Vec<(usize, usize, i64, bool)> = (0..m)
+                .map(|_| {
+                    let c = if unit { 1 } else { rng.random_range(0..20) };
+                    let bidirectional = rng.random_bool(0.2);
+                    (
+                        rng.random_range(0..n),
+                        rng.random_range(0..n),
+                        c,
+                        bidirectional,
+                    )
+                })
+                .collect();
+            let (s, t) = (0, n - 1);
+            let mut expected = None;
+            for algorithm in ALGORITHMS {
+                // Add the edges in two batches to exercise incremental solving
+                let mut g = FlowGraph::with_algorithm(algorithm);
+                let half = m / 2;
+                for &(u, v, c, bidirectional) in &edges[..half] {
+                    g.add_edge(u, v, c, bidirectional);
+                }
+                let partial = g.max_flow(s, t);
+                validate(&g, &edges[..half], s, t, partial);
+                for &(u, v, c, bidirectional) in &edges[half..] {
+                    g.add_edge(u, v, c, bidirectional);
+                }
+                let value = g.max_flow(s, t);
+                assert!(partial <= value);
+                validate(&g, &edges, s, t, value);
+                assert_eq!(*expected.get_or_insert(value), value);
+                assert_eq!(value, g.solve(s, t).0);
+            }
+        }
+    }
+
+    #[test]
+    fn check_maxflow_terminal_change() {
+        let mut g = FlowGraph::new();
+        g.add_edge(0, 1, 5, false);
+        g.add_edge(1, 2, 3, true);
+        assert_eq!(3, g.max_flow(0, 2));
+        assert_eq!(3, g.max_flow(2, 1));
+        assert_eq!(-3, g.flow(1));
+        assert_eq!((vec![2], vec![0, 1]), g.min_cut());
     }
 }
@@ -9,15 +9,43 @@ struct Edge {
     capacity: i64,
     cost: i64,
     f: i64,
+    /// The lower bound of the flow; nonzero only on the forward half of an edge
+    lower: i64,
 }
 
 /// (Maximum) flow solver with costs.
+///
+/// Besides [`MinCostFlowGraph::solve`], which leaves the graph untouched, the flow can be computed in place
+/// with [`MinCostFlowGraph::solve_in_place`], [`MinCostFlowGraph::slope`] or [`MinCostFlowGraph::circulation`],
+/// after which the flow on each edge can be retrieved with [`MinCostFlowGraph::flow`].
+/// Edges may have lower bounds on their flow; flows with demands can be modeled with them.
+///
+/// Example:
+/// ```
+/// use basm_std::graph::mcmf::{MinCostFlowGraph, MinCostFlowMode, MinCostFlowResult};
+/// let mut g = MinCostFlowGraph::new();
+/// let a = g.add_edge(0, 1, 2, 1, false);
+/// let b = g.add_edge(0, 1, 2, 3, false);
+/// let c = g.add_edge_with_bounds(1, 2, 1, 3, 0);
+/// let slope = g.slope(0, 2, MinCostFlowMode::MaxFlowMinCost, None).unwrap();
+/// assert_eq!(
+///     vec![
+///         MinCostFlowResult { flow: 1, cost: 1 }, // forced by the lower bound of c
+///         MinCostFlowResult { flow: 2, cost: 2 },
+///         MinCostFlowResult { flow: 3, cost: 5 },
+///     ],
+///     slope,
+/// );
+/// assert_eq!((2, 1, 3), (g.flow(a), g.flow(b), g.flow(c)));
+/// ```
+#[derive(Clone)]
 pub struct MinCostFlowGraph {
     adj: Vec<Vec<usize>>,
     e: Vec<Edge>,
     edge_count: usize,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum MinCostFlowMode {
     /// Compute a maximum flow, and if multiple cost values are possible for the flow, choose minimum.
     MaxFlowMinCost,
@@ -53,8 +81,44 @@ impl MinCostFlowGraph {
             edge_count: 0,
         }
     }
-    pub fn add_edge(&mut self, u: usize, v: usize, capacity: i64, cost: i64, bidirectional: bool) {
+    /// Adds an edge from `u` to `v`, and returns its id. Ids are assigned sequentially from 0.
+    /// If `bidirectional` is true, the edge can also carry up to `capacity` units from `v` to `u`,
+    /// at the cost of `-cost` per unit.
+    pub fn add_edge(
+        &mut self,
+        u: usize,
+        v: usize,
+        capacity: i64,
+        cost: i64,
+        bidirectional: bool,
+    ) -> usize {
         assert!(capacity >= 0);
+        let reverse_capacity = if bidirectional { capacity } else { 0 };
+        self.push_edge(u, v, capacity, reverse_capacity, cost)
+    }
+    /// Adds an edge from `u` to `v` which must carry at least `lower` and at most `upper` units of flow,
+    /// and returns its id.
+    pub fn add_edge_with_bounds(
+        &mut self,
+        u: usize,
+        v: usize,
+        lower: i64,
+        upper: i64,
+        cost: i64,
+    ) -> usize {
+        assert!(0 <= lower && lower <= upper);
+        let id = self.push_edge(u, v, upper - lower, 0, cost);
+        self.e[2 * id].lower = lower;
+        id
+    }
+    fn push_edge(
+        &mut self,
+        u: usize,
+        v: usize,
+        capacity: i64,
+        reverse_capacity: i64,
+        cost: i64,
+    ) -> usize {
         if self.adj.len() < u + 1 {
             self.adj.resize(u + 1, vec![]);
         }
@@ -68,29 +132,90 @@ impl MinCostFlowGraph {
             capacity,
             cost,
             f: 0,
+            lower: 0,
         });
         self.adj[v].push(self.e.len());
         self.e.push(Edge {
             u: v,
             v: u,
-            capacity: if bidirectional { capacity } else { 0 },
+            capacity: reverse_capacity,
             cost: -cost,
             f: 0,
+            lower: 0,
         });
         self.edge_count += 1;
+        self.edge_count - 1
+    }
+    /// Removes the edges with ids `edge_count..`, which must have been added after all the others.
+    fn truncate_edges(&mut self, edge_count: usize) {
+        for eid in (2 * edge_count..self.e.len()).rev() {
+            let popped = self.adj[self.e[eid].u].pop();
+            debug_assert_eq!(Some(eid), popped);
+        }
+        self.e.truncate(2 * edge_count);
+        self.edge_count = edge_count;
+    }
+    /// Returns the flow on the edge `id` in the direction it was added, including its lower bound,
+    /// as computed by the last in-place computation.
+    /// The flow is negative if a bidirectional edge carries flow backwards.
+    pub fn flow(&self, id: usize) -> i64 {
+        self.e[2 * id].f + self.e[2 * id].lower
     }
-    /// Computes maximum flow that has the minimum cost (the maximum cost if `maximize_cost` is true).
+    fn total_cost(&self) -> i64 {
+        (0..self.edge_count)
+            .map(|id| self.flow(id) * self.e[2 * id].cost)
+            .sum()
+    }
+    fn negate_costs(&mut self) {
+        for e_ent in self.e.iter_mut() {
+            e_ent.cost = -e_ent.cost;
+        }
+    }
+    /// Computes a flow from `s` to `t` as specified by `mode`, leaving the graph untouched.
     /// `s` and `t` must be distinct.
     ///
-    /// Returns `None` if the graph has a negative cost cycle.
+    /// Returns `None` if the graph has a negative cost cycle, or if the lower bounds cannot be satisfied.
     pub fn solve(&self, s: usize, t: usize, mode: MinCostFlowMode) -> Option<MinCostFlowResult> {
+        self.clone().solve_in_place(s, t, mode, None)
+    }
+    /// Computes a flow from `s` to `t` as specified by `mode`, of value at most `limit` if given, in place.
+    /// `s` and `t` must be distinct.
+    /// With a limit `k` and [`MinCostFlowMode::MaxFlowMinCost`], this computes the minimum cost flow
+    /// of value `k`, or of the maximum value if it is less than `k`.
+    ///
+    /// Returns `None` if the graph has a negative cost cycle, or if the lower bounds cannot be satisfied
+    /// with a flow of value at most `limit`.
+    pub fn solve_in_place(
+        &mut self,
+        s: usize,
+        t: usize,
+        mode: MinCostFlowMode,
+        limit: Option<i64>,
+    ) -> Option<MinCostFlowResult> {
+        self.slope(s, t, mode, limit)
+            .map(|slope| *slope.last().unwrap())
+    }
+    /// Computes a flow in place as [`MinCostFlowGraph::solve_in_place`] does, and returns the cost
+    /// as a piecewise linear function of the flow value, as the list of its breakpoints in increasing order of flow.
+    /// The function is convex (concave if the cost is maximized), and the last breakpoint is the computed flow.
+    ///
+    /// Without lower bounds, the first breakpoint is `(0, 0)`. With lower bounds,
+    /// it is the optimal-cost flow of the minimum value satisfying them.
+    ///
+    /// Returns `None` if the graph has a negative cost cycle, or if the lower bounds cannot be satisfied
+    /// with a flow of value at most `limit`.
+    pub fn slope(
+        &mut self,
+        s: usize,
+        t: usize,
+        mode: MinCostFlowMode,
+        limit: Option<i64>,
+    ) -> Option<Vec<MinCostFlowResult>> {
         assert!(s != t);
-        let (mut adj, mut e) = (self.adj.clone(), self.e.clone());
         let bound = s.max(t);
-        if bound >= adj.len() {
-            adj.resize(bound + 1, vec![]);
+        if bound >= self.adj.len() {
+            self.adj.resize(bound + 1, vec![]);
         }
-        let n = adj.len();
 
         // Negate costs if cost maximization is requested
         type Mode = MinCostFlowMode;
@@ -99,124 +224,241 @@ impl MinCostFlowGraph {
             Mode::MaxFlowMaxCost | Mode::MaxCostMaxFlow | Mode::MaxCostMinFlow
         );
         if maximize_cost {
-            for e_ent in e.iter_mut() {
-                e_ent.cost = -e_ent.cost;
-            }
+            self.negate_costs();
         }
-
-        // Step 1: Compute cost distances from the source with Bellman-Ford
-        // (Dijkstra won't work on the first step because of possible negative costs)
-        // * TODO: replace Bellman-Ford with SPFA for speed
-        let mut s_dist = vec![(i64::MAX, usize::MAX); n]; // (dist, last_edge)
-        s_dist[s] = (0, usize::MAX);
-        for i in 0..n + 1 {
-            let mut updated = false;
-            for u in 0..n {
-                if s_dist[u].0 == i64::MAX {
-                    continue;
-                }
-                for &eid in adj[u].iter() {
-                    if e[eid].capacity - e[eid].f > 0 {
-                        let v = e[eid].v;
-                        let new_dist = s_dist[u].0 + e[eid].cost;
-                        if new_dist < s_dist[v].0 {
-                            s_dist[v] = (new_dist, eid);
-                            updated = true;
-                        }
-                    }
-                }
+        let mut out = self.slope_min_cost(s, t, mode, limit.unwrap_or(i64::MAX));
+        if maximize_cost {
+            self.negate_costs();
+            for pt in out.iter_mut().flatten() {
+                pt.cost = -pt.cost;
             }
-            if !updated {
-                break;
-            } else if i == n {
-                // Bellman-Ford detected a negative cycle
+        }
+        out
+    }
+    fn slope_min_cost(
+        &mut self,
+        s: usize,
+        t: usize,
+        mode: MinCostFlowMode,
+        limit: i64,
+    ) -> Option<Vec<MinCostFlowResult>> {
+        for e_ent in self.e.iter_mut() {
+            e_ent.f = 0;
+        }
+        let mut value = 0;
+        if self.e.iter().any(|e_ent| e_ent.lower > 0) {
+            // Find the minimum cost flow of the minimum value satisfying the lower bounds,
+            // by adding an uncapacitated edge from t to s which costs more than any simple path
+            let m = self.edge_count;
+            let big = 1 + self.e.iter().map(|e_ent| e_ent.cost.abs()).sum::<i64>();
+            self.push_edge(t, s, i64::MAX / 4, 0, big);
+            let feasible = self.satisfy_lower_bounds();
+            value = self.e[2 * m].f;
+            self.truncate_edges(m);
+            if !feasible? || value > limit {
                 return None;
             }
         }
 
-        // Step 2: Main loop
-        let mut ans = MinCostFlowResult::default();
-        let mut ans_mincost_maxflow = MinCostFlowResult::default();
-        let mut ans_mincost_minflow = MinCostFlowResult::default();
-        loop {
-            // Run Dijkstra with weights adjusted as w'[u->v] = s_dist[u] + w[u->v] - s_dist[v]
-            // (i.e., Johnson's algorithm; see section 9.4 of Jeff Erickson's Algorithm book (2019) for details)
-            let mut s_dist_new = vec![(i64::MAX, usize::MAX); n]; // (dist, last_edge)
-            s_dist_new[s] = (s_dist[s].0, usize::MAX);
-            let mut pq = BinaryHeap::new();
-            pq.push((Reverse(s_dist_new[s].0), s));
-            while let Some(x) = pq.pop() {
-                if x.0.0 != s_dist_new[x.1].0 {
-                    // Entry is stale
-                    continue;
-                }
-                let u = x.1;
-                for &eid in adj[u].iter() {
-                    if e[eid].capacity - e[eid].f > 0 {
-                        let v = e[eid].v;
-                        let new_dist = x.0.0 + (s_dist[u].0 + e[eid].cost - s_dist[v].0);
-                        if new_dist < s_dist_new[v].0 {
-                            s_dist_new[v] = (new_dist, eid);
-                            pq.push((Reverse(s_dist_new[v].0), v));
-                        }
-                    }
-                }
+        type Mode = MinCostFlowMode;
+        let mut slope = vec![MinCostFlowResult {
+            flow: value,
+            cost: self.total_cost(),
+        }];
+        // Augment while the cost per unit does not exceed the threshold
+        let threshold = match mode {
+            Mode::MaxFlowMinCost | Mode::MaxFlowMaxCost => i64::MAX,
+            Mode::MinCostMaxFlow | Mode::MaxCostMaxFlow => 0,
+            Mode::MinCostMinFlow | Mode::MaxCostMinFlow => -1,
+        };
+        let path = augment(&self.adj, &mut self.e, s, t, limit - value, threshold)?;
+        let mut last_unit_cost = None;
+        for (flow, unit_cost) in path {
+            let mut pt = *slope.last().unwrap();
+            pt.flow += flow;
+            pt.cost += flow * unit_cost;
+            if last_unit_cost == Some(unit_cost) {
+                *slope.last_mut().unwrap() = pt;
+            } else {
+                slope.push(pt);
             }
-            for u in 0..n {
-                if s_dist_new[u].0 != i64::MAX {
-                    s_dist_new[u].0 -= s_dist[s].0 - s_dist[u].0;
-                }
+            last_unit_cost = Some(unit_cost);
+        }
+        Some(slope)
+    }
+    /// Routes the excesses and deficits implied by the lower bounds and the current flow,
+    /// through a super source and a super sink, at the minimum cost.
+    /// Returns `Some(false)` if they cannot all be routed, and `None` if a negative cost cycle is found.
+    fn satisfy_lower_bounds(&mut self) -> Option<bool> {
+        let n = self.adj.len();
+        let mut excess = vec![0i64; n];
+        for e_ent in self.e.iter() {
+            excess[e_ent.v] += e_ent.lower + e_ent.f.max(0);
+            excess[e_ent.u] -= e_ent.lower + e_ent.f.max(0);
+        }
+        let m = self.edge_count;
+        let (ss, tt) = (n, n + 1);
+        self.adj.resize(n + 2, vec![]);
+        let mut need = 0;
+        for (u, &x) in excess.iter().enumerate() {
+            if x > 0 {
+                self.push_edge(ss, u, x, 0, 0);
+                need += x;
+            } else if x < 0 {
+                self.push_edge(u, tt, -x, 0, 0);
             }
-
-            // Terminate if t is not reachable
-            let mut x = s_dist_new[t];
-            if x.0 == i64::MAX {
-                break;
+        }
+        let path = augment(&self.adj, &mut self.e, ss, tt, i64::MAX, i64::MAX);
+        self.truncate_edges(m);
+        self.adj.truncate(n);
+        let sent: i64 = path?.iter().map(|x| x.0).sum();
+        Some(sent == need)
+    }
+    /// Computes a minimum cost circulation satisfying the lower bounds in place, and returns its cost.
+    /// Unlike the other functions, negative cost cycles are allowed, and are saturated as needed.
+    ///
+    /// Returns `None` if the lower bounds cannot be satisfied.
+    ///
+    /// Example:
+    /// ```
+    /// use basm_std::graph::mcmf::MinCostFlowGraph;
+    /// let mut g = MinCostFlowGraph::new();
+    /// g.add_edge_with_bounds(0, 1, 2, 5, 3);
+    /// let back = g.add_edge(1, 0, 4, -1, false);
+    /// g.add_edge(1, 2, 1, -10, false);
+    /// g.add_edge(2, 0, 1, 0, false);
+    /// // 0 -> 1 -> 2 -> 0 once, and 0 -> 1 -> 0 once more to meet the lower bound
+    /// assert_eq!(Some(-5), g.circulation());
+    /// assert_eq!(1, g.flow(back));
+    /// ```
+    pub fn circulation(&mut self) -> Option<i64> {
+        // Saturating every negative cost edge leaves no negative cost edges in the residual graph,
+        // so the remaining imbalance can be routed by shortest paths
+        for e_ent in self.e.iter_mut() {
+            e_ent.f = 0;
+        }
+        for eid in 0..self.e.len() {
+            if self.e[eid].cost < 0 {
+                let df = self.e[eid].capacity;
+                self.e[eid].f = df;
+                self.e[eid ^ 1].f = -df;
             }
+        }
+        let feasible = self.satisfy_lower_bounds()?;
+        feasible.then(|| self.total_cost())
+    }
+}
 
-            // Augment if t is reachable
-            let mut flow = i64::MAX;
-            while x.1 != usize::MAX {
-                let eid = x.1;
-                flow = flow.min(e[eid].capacity - e[eid].f);
-                x = s_dist_new[e[eid].u];
+/// Augments the flow along shortest paths from `s` to `t`, while the cost per unit of the path is
+/// at most `threshold` and the total amount is less than `limit`.
+///
+/// Returns the list of `(amount, cost per unit)` of the augmentations,
+/// or `None` if the residual graph has a negative cost cycle reachable from `s`.
+fn augment(
+    adj: &[Vec<usize>],
+    e: &mut [Edge],
+    s: usize,
+    t: usize,
+    limit: i64,
+    threshold: i64,
+) -> Option<Vec<(i64, i64)>> {
+    let n = adj.len();
+
+    // Step 1: Compute cost distances from the source with Bellman-Ford
+    // (Dijkstra won't work on the first step because of possible negative costs)
+    // * TODO: replace Bellman-Ford with SPFA for speed
+    let mut s_dist = vec![(i64::MAX, usize::MAX); n]; // (dist, last_edge)
+    s_dist[s] = (0, usize::MAX);
+    for i in 0..n + 1 {
+        let mut updated = false;
+        for u in 0..n {
+            if s_dist[u].0 == i64::MAX {
+                continue;
             }
-            x = s_dist_new[t];
-            let mut cost = 0;
-            while x.1 != usize::MAX {
-                let eid = x.1;
-                e[eid].f += flow;
-                e[eid ^ 1].f -= flow;
-                cost += e[eid].cost;
-                x = s_dist_new[e[eid].u];
+            for &eid in adj[u].iter() {
+                if e[eid].capacity - e[eid].f > 0 {
+                    let v = e[eid].v;
+                    let new_dist = s_dist[u].0 + e[eid].cost;
+                    if new_dist < s_dist[v].0 {
+                        s_dist[v] = (new_dist, eid);
+                        updated = true;
+                    }
+                }
             }
-            ans.flow += flow;
-            ans.cost += cost * flow;
-            if cost < 0 {
-                ans_mincost_minflow = ans;
+        }
+        if !updated {
+            break;
+        } else if i == n {
+            // Bellman-Ford detected a negative cycle
+            return None;
+        }
+    }
+
+    // Step 2: Main loop
+    let mut out = vec![];
+    let mut total = 0;
+    while total < limit {
+        // Run Dijkstra with weights adjusted as w'[u->v] = s_dist[u] + w[u->v] - s_dist[v]
+        // (i.e., Johnson's algorithm; see section 9.4 of Jeff Erickson's Algorithm book (2019) for details)
+        let mut s_dist_new = vec![(i64::MAX, usize::MAX); n]; // (dist, last_edge)
+        s_dist_new[s] = (s_dist[s].0, usize::MAX);
+        let mut pq = BinaryHeap::new();
+        pq.push((Reverse(s_dist_new[s].0), s));
+        while let Some(x) = pq.pop() {
+            if x.0.0 != s_dist_new[x.1].0 {
+                // Entry is stale
+                continue;
             }
-            if cost <= 0 {
-                ans_mincost_maxflow = ans;
+            let u = x.1;
+            for &eid in adj[u].iter() {
+                if e[eid].capacity - e[eid].f > 0 {
+                    let v = e[eid].v;
+                    let new_dist = x.0.0 + (s_dist[u].0 + e[eid].cost - s_dist[v].0);
+                    if new_dist < s_dist_new[v].0 {
+                        s_dist_new[v] = (new_dist, eid);
+                        pq.push((Reverse(s_dist_new[v].0), v));
+                    }
+                }
+            }
+        }
+        for u in 0..n {
+            if s_dist_new[u].0 != i64::MAX {
+                s_dist_new[u].0 -= s_dist[s].0 - s_dist[u].0;
             }
-
-            // Update s_dist
-            s_dist = s_dist_new;
         }
 
-        // Select the right one
-        ans = match mode {
-            Mode::MaxFlowMinCost | Mode::MaxFlowMaxCost => ans,
-            Mode::MinCostMaxFlow | Mode::MaxCostMaxFlow => ans_mincost_maxflow,
-            Mode::MinCostMinFlow | Mode::MaxCostMinFlow => ans_mincost_minflow,
-        };
+        // Terminate if t is not reachable
+        let mut x = s_dist_new[t];
+        if x.0 == i64::MAX {
+            break;
+        }
 
-        // Negate cost if cost maximization is requested
-        if maximize_cost {
-            ans.cost = -ans.cost;
+        // Augment if t is reachable and the path is cheap enough
+        let mut flow = limit - total;
+        let mut cost = 0;
+        while x.1 != usize::MAX {
+            let eid = x.1;
+            flow = flow.min(e[eid].capacity - e[eid].f);
+            cost += e[eid].cost;
+            x = s_dist_new[e[eid].u];
+        }
+        if cost > threshold {
+            break;
         }
+        x = s_dist_new[t];
+        while x.1 != usize::MAX {
+            let eid = x.1;
+            e[eid].f += flow;
+            e[eid ^ 1].f -= flow;
+            x = s_dist_new[e[eid].u];
+        }
+        total += flow;
+        out.push((flow, cost));
 
-        Some(ans)
+        // Update s_dist
+        s_dist = s_dist_new;
     }
+    Some(out)
 }
 
 #[cfg(test)]
@@ -273,4 +515,168 @@ mod test {
             g.solve(0, 3, MinCostFlowMode::MaxFlowMaxCost),
         );
     }
+
+    // For each flow value, the minimum and maximum costs over all the integral flows satisfying the bounds
+    fn brute_force(
+        n: usize,
+        edges: &[(usize, usize, i64, i64, i64)],
+        s: Option<(usize, usize)>,
+    ) -> Vec<Option<(i64, i64)>> {
+        let mut out = vec![None; 64];
+        let mut f: Vec<i64> = edges.iter().map(|x| x.2).collect();
+        loop {
+            let mut net = vec![0i64; n];
+            for (i, &(u, v, ..)) in edges.iter().enumerate() {
+                net[u] -= f[i];
+                net[v] += f[i];
+            }
+            let (value, ok) = match s {
+                Some((s, t)) => (
+                    net[t],
+                    net[t] >= 0 && (0..n).all(|u| u == s || u == t || net[u] == 0),
+                ),
+                None => (0, net.iter().all(|&x| x == 0)),
+            };
+            if ok {
+                let cost: i64 = edges.iter().zip(&f).map(|(x, &f)| f * x.4).sum();
+                let entry = out[value as usize].get_or_insert((cost, cost));
+                *entry = (entry.0.min(cost), entry.1.max(cost));
+            }
+            // Next combination
+            let mut i = 0;
+            while i < edges.len() && f[i] == edges[i].3 {
+                f[i] = edges[i].2;
+                i += 1;
+            }
+            if i == edges.len() {
+                return out;
+            }
+            f[i] += 1;
+        }
+    }
+
+    #[test]
+    fn check_mcmf_bounds_and_slope() {
+        use rand::{Rng, SeedableRng};
+        type Mode = MinCostFlowMode;
+        let mut rng = rand::rngs::SmallRng::seed_from_u64(24);
+        for iter in 0..400 {
+            let n = rng.random_range(2..6);
+            let dag = iter % 2 == 0;
+            let edges: Vec<(usize, usize, i64, i64, i64)> = (0..rng.random_range(1..7))
+                .map(|_| {
+                    let (mut u, mut v) = (rng.random_range(0..n), rng.random_range(0..n));
+                    while u == v {
+                        (u, v) = (rng.random_range(0..n), rng.random_range(0..n));
+                    }
+                    if dag && u > v {
+                        (u, v) = (v, u);
+                    }
+                    let upper = rng.random_range(0..4);
+                    let lower = if rng.random_bool(0.3) {
+                        rng.random_range(0..=upper)
+                    } else {
+                        0
+                    };
+                    // In a DAG there are no cycles, so negative costs are safe
+                    let cost = if dag {
+                        rng.random_range(-5..10)
+                    } else {
+                        rng.random_range(0..10)
+                    };
+                    (u, v, lower, upper, cost)
+                })
+                .collect();
+            let mut g = MinCostFlowGraph::new();
+            g.add_edge(0, n - 1, 0, 0, false); // make sure every node exists
+            for &(u, v, lower, upper, cost) in &edges {
+                g.add_edge_with_bounds(u, v, lower, upper, cost);
+            }
+            let (s, t) = (0, n - 1);
+            let table = brute_force(n, &edges, Some((s, t)));
+            let check_flow = |g: &MinCostFlowGraph, result: MinCostFlowResult| {
+                let mut net = vec![0i64; n];
+                let mut cost = 0;
+                for (i, &(u, v, lower, upper, c)) in edges.iter().enumerate() {
+                    let f = g.flow(i + 1);
+                    assert!(lower <= f && f <= upper);
+                    net[u] -= f;
+                    net[v] += f;
+                    cost += f * c;
+                }
+                assert_eq!(result.flow, net[t]);
+                assert_eq!(result.cost, cost);
+            };
+            for limit in [None, Some(0), Some(1), Some(3)] {
+                let k = limit.unwrap_or(i64::MAX);
+                let values: Vec<(i64, (i64, i64))> = (0..table.len())
+                    .filter_map(|v| table[v].map(|x| (v as i64, x)))
+                    .filter(|&(v, _)| v <= k)
+                    .collect();
+                for mode in [
+                    Mode::MaxFlowMinCost,
+                    Mode::MaxFlowMaxCost,
+                    Mode::MinCostMaxFlow,
+                    Mode::MinCostMinFlow,
+                    Mode::MaxCostMaxFlow,
+                    Mode::MaxCostMinFlow,
+                ] {
+                    let maximize = matches!(
+                        mode,
+                        Mode::MaxFlowMaxCost | Mode::MaxCostMaxFlow | Mode::MaxCostMinFlow
+                    );
+                    if maximize && !dag {
+                        // Cycles may have positive costs, which are rejected
+                        continue;
+                    }
+                    let slope = g.slope(s, t, mode, limit);
+                    if values.is_empty() {
+                        assert!(slope.is_none());
+                        continue;
+                    }
+                    let slope = slope.unwrap();
+                    let result = *slope.last().unwrap();
+                    check_flow(&g, result);
+                    let (max_value, (min_cost, max_cost)) = *values.last().unwrap();
+                    let best_min = values.iter().map(|x| x.1.0).min().unwrap();
+                    let best_max = values.iter().map(|x| x.1.1).max().unwrap();
+                    let expected = match mode {
+                        Mode::MaxFlowMinCost => (max_value, min_cost),
+                        Mode::MaxFlowMaxCost => (max_value, max_cost),
+                        Mode::MinCostMaxFlow => (
+                            values.iter().rfind(|x| x.1.0 == best_min).unwrap().0,
+                            best_min,
+                        ),
+                        Mode::MinCostMinFlow => (
+                            values.iter().find(|x| x.1.0 == best_min).unwrap().0,
+                            best_min,
+                        ),
+                        Mode::MaxCostMaxFlow => (
+                            values.iter().rfind(|x| x.1.1 == best_max).unwrap().0,
+                            best_max,
+                        ),
+                        Mode::MaxCostMinFlow => (
+                            values.iter().find(|x| x.1.1 == best_max).unwrap().0,
+                            best_max,
+                        ),
+                    };
+                    assert_eq!(expected, (result.flow, result.cost));
+                    // Every breakpoint is optimal for its flow value
+                    for pt in &slope {
+                        let (lo, hi) = table[pt.flow as usize].unwrap();
+                        assert_eq!(if maximize { hi } else { lo }, pt.cost);
+                    }
+                    if mode == Mode::MaxFlowMinCost {
+                        assert_eq!(result, g.clone().solve_in_place(s, t, mode, limit).unwrap());
+                    }
+                }
+            }
+
+            let expected = brute_force(n, &edges, None)[0].map(|x| x.0);
+            assert_eq!(expected, g.circulation());
+            if let Some(cost) = expected {
+                check_flow(&g, MinCostFlowResult { flow: 0, cost });
+            }
+        }
+    }
 }
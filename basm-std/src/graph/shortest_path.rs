@@ -0,0 +1,603 @@
+use super::{DfsIter, DfsTarget, JaggedDfsIter};
+use crate::collections::JaggedVec;
+use alloc::collections::{BinaryHeap, VecDeque};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::Reverse;
+use core::ops::Add;
+
+/// Edge weights usable in shortest path computations.
+pub trait Weight: Copy + Ord + Add<Output = Self> {
+    const ZERO: Self;
+    const ONE: Self;
+}
+
+/// Edge weights usable with [`dijkstra_radix`]. Nonnegative values must fit in a `u64`.
+pub trait RadixWeight: Weight {
+    fn to_u64(self) -> u64;
+}
+
+macro_rules! impl_weight {
+    ($($t:ty),*) => { $(
+        impl Weight for $t {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+        }
+    )* };
+}
+impl_weight!(
+    i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize
+);
+
+macro_rules! impl_radix_weight {
+    ($($t:ty),*) => { $(
+        impl RadixWeight for $t {
+            fn to_u64(self) -> u64 {
+                self as u64
+            }
+        }
+    )* };
+}
+impl_radix_weight!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+/// A monotone priority queue with `u64` keys, which pops entries in increasing order of keys.
+///
+/// A pushed key must not be less than the last popped key.
+/// Each entry is moved between buckets at most 64 times, so that operations take `O(log C)` amortized time,
+/// where `C` is the largest key.
+///
+/// Example:
+/// ```
+/// use basm_std::graph::shortest_path::RadixHeap;
+/// let mut heap = RadixHeap::new();
+/// heap.push(5, 'a');
+/// heap.push(3, 'b');
+/// assert_eq!(Some((3, 'b')), heap.pop());
+/// heap.push(4, 'c');
+/// assert_eq!(Some((4, 'c')), heap.pop());
+/// assert_eq!(Some((5, 'a')), heap.pop());
+/// assert_eq!(None, heap.pop());
+/// ```
+#[derive(Clone)]
+pub struct RadixHeap<V> {
+    // buckets[i] holds the entries whose keys differ from `last` first at bit `i - 1`
+    buckets: [Vec<(u64, V)>; 65],
+    last: u64,
+    len: usize,
+}
+
+impl<V> Default for RadixHeap<V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<V> RadixHeap<V> {
+    pub fn new() -> Self {
+        Self {
+            buckets: [const { Vec::new() }; 65],
+            last: 0,
+            len: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    fn bucket(&self, key: u64) -> usize {
+        (u64::BITS - (key ^ self.last).leading_zeros()) as usize
+    }
+
+    pub fn push(&mut self, key: u64, value: V) {
+        assert!(
+            key >= self.last,
+            "keys must not be less than the last popped key"
+        );
+        let i = self.bucket(key);
+        self.buckets[i].push((key, value));
+        self.len += 1;
+    }
+
+    pub fn pop(&mut self) -> Option<(u64, V)> {
+        if self.len == 0 {
+            return None;
+        }
+        if self.buckets[0].is_empty() {
+            let i = self.buckets.iter().position(|b| !b.is_empty()).unwrap();
+            let bucket = core::mem::take(&mut self.buckets[i]);
+            self.last = bucket.iter().map(|&(key, _)| key).min().unwrap();
+            for (key, value) in bucket {
+                let j = self.bucket(key);
+                self.buckets[j].push((key, value));
+            }
+        }
+        self.len -= 1;
+        self.buckets[0].pop()
+    }
+}
+
+/// The result of a single source shortest path computation.
+pub struct ShortestPaths<T> {
+    /// `dist[v]`: The length of a shortest path from the source to `v`, or `None` if `v` is unreachable.
+    pub dist: Vec<Option<T>>,
+    /// `parent[v]`: `(u, e)` if the last edge of the shortest path to `v` is the edge `e` from `u`,
+    /// or `None` if `v` is the source or unreachable. Edge ids are the link ids of the [`JaggedVec`].
+    pub parent: Vec<Option<(usize, usize)>>,
+}
+
+impl<T: Weight> ShortestPaths<T> {
+    fn new(n: usize, src: usize) -> Self {
+        let mut dist = vec![None; n];
+        dist[src] = Some(T::ZERO);
+        Self {
+            dist,
+            parent: vec![None; n],
+        }
+    }
+
+    /// Returns the vertices on a shortest path from the source to `to`, including both ends,
+    /// or `None` if `to` is unreachable.
+    pub fn path(&self, to: usize) -> Option<Vec<usize>> {
+        self.dist[to]?;
+        let mut path = vec![to];
+        let mut v = to;
+        while let Some((u, _)) = self.parent[v] {
+            path.push(u);
+            v = u;
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    /// Returns the ids of the edges on a shortest path from the source to `to`,
+    /// or `None` if `to` is unreachable.
+    pub fn path_edges(&self, to: usize) -> Option<Vec<usize>> {
+        self.dist[to]?;
+        let mut path = vec![];
+        let mut v = to;
+        while let Some((u, e)) = self.parent[v] {
+            path.push(e);
+            v = u;
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+// Iterates over `(to, id, weight)` of the edges from `u`
+fn edges<T>(graph: &JaggedVec<(u32, T)>, u: usize) -> impl Iterator<Item = (usize, usize, T)> + '_
+where
+    T: Copy,
+{
+    let mut iter = DfsTarget::<T>::dfs_iter(graph, u);
+    core::iter::from_fn(move || {
+        let current = iter.clone();
+        <JaggedDfsIter as DfsIter<T>>::next(&mut iter, graph).then(|| {
+            (
+                <JaggedDfsIter as DfsIter<T>>::to(&current, graph),
+                <JaggedDfsIter as DfsIter<T>>::id(&current, graph),
+                *<JaggedDfsIter as DfsIter<T>>::data(&current, graph),
+            )
+        })
+    })
+}
+
+/// Computes shortest paths from `src` with Dijkstra's algorithm using a binary heap, in `O(m log m)` time.
+///
+/// `graph.push(u, (v, w))` denotes an edge from `u` to `v` of weight `w`. All weights must be nonnegative.
+///
+/// Example:
+/// ```
+/// use basm_std::collections::JaggedVec;
+/// use basm_std::graph::shortest_path::dijkstra;
+/// let mut graph = JaggedVec::new();
+/// graph.resize(4);
+/// graph.push(0, (1, 4));
+/// graph.push(0, (2, 1));
+/// graph.push(2, (1, 2));
+/// let sp = dijkstra(&graph, 0);
+/// assert_eq!(vec![Some(0), Some(3), Some(1), None], sp.dist);
+/// assert_eq!(Some(vec![0, 2, 1]), sp.path(1));
+/// assert_eq!(Some(vec![1, 2]), sp.path_edges(1));
+/// ```
+pub fn dijkstra<T: Weight>(graph: &JaggedVec<(u32, T)>, src: usize) -> ShortestPaths<T> {
+    let mut sp = ShortestPaths::<T>::new(graph.row(), src);
+    let mut heap = BinaryHeap::new();
+    heap.push(Reverse((T::ZERO, src)));
+    while let Some(Reverse((d, u))) = heap.pop() {
+        if sp.dist[u] != Some(d) {
+            continue;
+        }
+        for (v, e, w) in edges(graph, u) {
+            let nd = d + w;
+            if sp.dist[v].is_none_or(|dv| nd < dv) {
+                sp.dist[v] = Some(nd);
+                sp.parent[v] = Some((u, e));
+                heap.push(Reverse((nd, v)));
+            }
+        }
+    }
+    sp
+}
+
+/// Computes shortest paths from `src` with Dijkstra's algorithm using a [`RadixHeap`],
+/// in `O(m + n log C)` time where `C` is the largest distance.
+///
+/// `graph.push(u, (v, w))` denotes an edge from `u` to `v` of weight `w`. All weights must be nonnegative.
+/// This is usually faster than [`dijkstra`] for integer weights that are not too large.
+pub fn dijkstra_radix<T: RadixWeight>(graph: &JaggedVec<(u32, T)>, src: usize) -> ShortestPaths<T> {
+    let mut sp = ShortestPaths::<T>::new(graph.row(), src);
+    let mut heap = RadixHeap::new();
+    heap.push(0, src);
+    while let Some((key, u)) = heap.pop() {
+        let d = sp.dist[u].unwrap();
+        if d.to_u64() != key {
+            continue;
+        }
+        for (v, e, w) in edges(graph, u) {
+            let nd = d + w;
+            if sp.dist[v].is_none_or(|dv| nd < dv) {
+                sp.dist[v] = Some(nd);
+                sp.parent[v] = Some((u, e));
+                heap.push(nd.to_u64(), v);
+            }
+        }
+    }
+    sp
+}
+
+/// Computes shortest paths from `src` with 0-1 BFS, in `O(n + m)` time.
+///
+/// `graph.push(u, (v, w))` denotes an edge from `u` to `v` of weight `w`, which must be either 0 or 1.
+///
+/// Example:
+/// ```
+/// use basm_std::collections::JaggedVec;
+/// use basm_std::graph::shortest_path::bfs01;
+/// let mut graph = JaggedVec::new();
+/// graph.resize(3);
+/// graph.push(0, (1, 1));
+/// graph.push(0, (2, 0));
+/// graph.push(2, (1, 0));
+/// let sp = bfs01(&graph, 0);
+/// assert_eq!(vec![Some(0), Some(0), Some(0)], sp.dist);
+/// assert_eq!(Some(vec![0, 2, 1]), sp.path(1));
+/// ```
+pub fn bfs01<T: Weight>(graph: &JaggedVec<(u32, T)>, src: usize) -> ShortestPaths<T> {
+    let mut sp = ShortestPaths::<T>::new(graph.row(), src);
+    let mut done = vec![false; graph.row()];
+    let mut deque = VecDeque::new();
+    deque.push_back(src);
+    while let Some(u) = deque.pop_front() {
+        if done[u] {
+            continue;
+        }
+        done[u] = true;
+        let d = sp.dist[u].unwrap();
+        for (v, e, w) in edges(graph, u) {
+            assert!(w == T::ZERO || w == T::ONE, "weights must be either 0 or 1");
+            let nd = d + w;
+            if sp.dist[v].is_none_or(|dv| nd < dv) {
+                sp.dist[v] = Some(nd);
+                sp.parent[v] = Some((u, e));
+                if w == T::ZERO {
+                    deque.push_front(v);
+                } else {
+                    deque.push_back(v);
+                }
+            }
+        }
+    }
+    sp
+}
+
+/// Computes shortest paths from `src` with the queue-based Bellman–Ford algorithm (SPFA),
+/// in `O(nm)` time in the worst case.
+///
+/// `graph.push(u, (v, w))` denotes an edge from `u` to `v` of weight `w`. Weights may be negative.
+/// If a negative cycle is reachable from `src`, returns `Err(cycle)`,
+/// where the cycle `cycle[0] -> cycle[1] -> ... -> cycle[0]` has negative total weight.
+///
+/// Example:
+/// ```
+/// use basm_std::collections::JaggedVec;
+/// use basm_std::graph::shortest_path::bellman_ford;
+/// let mut graph = JaggedVec::new();
+/// graph.resize(3);
+/// graph.push(0, (1, 2));
+/// graph.push(1, (2, -3));
+/// let sp = bellman_ford(&graph, 0).unwrap();
+/// assert_eq!(vec![Some(0), Some(2), Some(-1)], sp.dist);
+/// graph.push(2, (1, 1));
+/// assert_eq!(Err(vec![1, 2]), bellman_ford(&graph, 0).map(|sp| sp.dist));
+/// ```
+pub fn bellman_ford<T: Weight>(
+    graph: &JaggedVec<(u32, T)>,
+    src: usize,
+) -> Result<ShortestPaths<T>, Vec<usize>> {
+    let n = graph.row();
+    let mut sp = ShortestPaths::new(n, src);
+    let mut in_queue = vec![false; n];
+    let mut queue = VecDeque::new();
+    queue.push_back(src);
+    in_queue[src] = true;
+    // Any cycle of parent pointers has negative weight, and one appears eventually if a negative cycle is
+    // reachable. Looking for it after every `n` relaxations costs amortized O(1) time per relaxation.
+    let mut relaxations = 0;
+    while let Some(u) = queue.pop_front() {
+        in_queue[u] = false;
+        let d = sp.dist[u].unwrap();
+        for (v, e, w) in edges(graph, u) {
+            let nd = d + w;
+            if sp.dist[v].is_none_or(|dv| nd < dv) {
+                sp.dist[v] = Some(nd);
+                sp.parent[v] = Some((u, e));
+                relaxations += 1;
+                if relaxations % n == 0
+                    && let Some(cycle) = find_parent_cycle(&sp.parent)
+                {
+                    return Err(cycle);
+                }
+                if !in_queue[v] {
+                    in_queue[v] = true;
+                    queue.push_back(v);
+                }
+            }
+        }
+    }
+    Ok(sp)
+}
+
+// Returns a cycle formed by the parent pointers, in the direction of the edges.
+fn find_parent_cycle(parent: &[Option<(usize, usize)>]) -> Option<Vec<usize>> {
+    // mark[v] = 1 + the vertex from which the walk that reached `v` started
+    let mut mark = vec![0; parent.len()];
+    for start in 0..parent.len() {
+        let mut v = start;
+        while mark[v] == 0 {
+            mark[v] = start + 1;
+            match parent[v] {
+                Some((u, _)) => v = u,
+                None => break,
+            }
+        }
+        if mark[v] == start + 1 && parent[v].is_some() {
+            let mut cycle = vec![v];
+            let mut u = parent[v].unwrap().0;
+            while u != v {
+                cycle.push(u);
+                u = parent[u].unwrap().0;
+            }
+            cycle.reverse();
+            let min = (0..cycle.len()).min_by_key(|&i| cycle[i]).unwrap();
+            cycle.rotate_left(min);
+            return Some(cycle);
+        }
+    }
+    None
+}
+
+/// The result of an all pairs shortest path computation.
+pub struct AllShortestPaths<T> {
+    /// `dist[u][v]`: The length of a shortest path from `u` to `v`, or `None` if `v` is unreachable from `u`.
+    pub dist: Vec<Vec<Option<T>>>,
+    /// `next[u][v]`: The vertex following `u` on the shortest path from `u` to `v`,
+    /// or `None` if `u == v` or `v` is unreachable from `u`.
+    pub next: Vec<Vec<Option<usize>>>,
+}
+
+impl<T> AllShortestPaths<T> {
+    /// Returns the vertices on a shortest path from `from` to `to`, including both ends,
+    /// or `None` if `to` is unreachable from `from`.
+    pub fn path(&self, from: usize, to: usize) -> Option<Vec<usize>> {
+        self.dist[from][to].as_ref()?;
+        let mut path = vec![from];
+        let mut u = from;
+        while let Some(v) = self.next[u][to] {
+            path.push(v);
+            u = v;
+        }
+        Some(path)
+    }
+}
+
+/// Computes shortest paths between all pairs of vertices with the Floyd–Warshall algorithm, in `O(n**3)` time.
+///
+/// `graph.push(u, (v, w))` denotes an edge from `u` to `v` of weight `w`. Weights may be negative.
+/// Returns `None` if the graph has a negative cycle.
+///
+/// Example:
+/// ```
+/// use basm_std::collections::JaggedVec;
+/// use basm_std::graph::shortest_path::floyd_warshall;
+/// let mut graph = JaggedVec::new();
+/// graph.resize(3);
+/// graph.push(0, (1, 5));
+/// graph.push(1, (2, -2));
+/// graph.push(0, (2, 4));
+/// let sp = floyd_warshall(&graph).unwrap();
+/// assert_eq!(vec![Some(0), Some(5), Some(3)], sp.dist[0]);
+/// assert_eq!(vec![None, None, Some(0)], sp.dist[2]);
+/// assert_eq!(Some(vec![0, 1, 2]), sp.path(0, 2));
+/// graph.push(2, (1, 1));
+/// assert!(floyd_warshall(&graph).is_none());
+/// ```
+pub fn floyd_warshall<T: Weight>(graph: &JaggedVec<(u32, T)>) -> Option<AllShortestPaths<T>> {
+    let n = graph.row();
+    let mut dist = vec![vec![None; n]; n];
+    let mut next = vec![vec![None; n]; n];
+    for u in 0..n {
+        dist[u][u] = Some(T::ZERO);
+        for (v, _, w) in edges(graph, u) {
+            if dist[u][v].is_none_or(|d| w < d) {
+                dist[u][v] = Some(w);
+                next[u][v] = (u != v).then_some(v);
+            }
+        }
+    }
+    for k in 0..n {
+        let dist_k = dist[k].clone();
+        for i in 0..n {
+            let Some(dik) = dist[i][k] else {
+                continue;
+            };
+            let nik = next[i][k];
+            for (j, &dkj) in dist_k.iter().enumerate() {
+                if let Some(dkj) = dkj {
+                    let nd = dik + dkj;
+                    if dist[i][j].is_none_or(|dij| nd < dij) {
+                        dist[i][j] = Some(nd);
+                        next[i][j] = nik;
+                    }
+                }
+            }
+        }
+        // Stop as soon as a negative cycle appears, so that the distances cannot blow up
+        if (0..n).any(|i| dist[i][i] < Some(T::ZERO)) {
+            return None;
+        }
+    }
+    Some(AllShortestPaths { dist, next })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+
+    type EdgeList = Vec<(usize, usize, i64)>;
+
+    fn random_graph(
+        rng: &mut rand::rngs::SmallRng,
+        n: usize,
+        m: usize,
+        w: core::ops::Range<i64>,
+    ) -> (JaggedVec<(u32, i64)>, EdgeList) {
+        let mut graph = JaggedVec::new();
+        graph.resize(n);
+        let mut list = vec![];
+        for _ in 0..m {
+            let (u, v, w) = (
+                rng.random_range(0..n),
+                rng.random_range(0..n),
+                rng.random_range(w.clone()),
+            );
+            graph.push(u, (v as u32, w));
+            list.push((u, v, w));
+        }
+        (graph, list)
+    }
+
+    // Checks that the parent pointers form shortest paths
+    fn check_paths(sp: &ShortestPaths<i64>, list: &[(usize, usize, i64)], src: usize) {
+        for v in 0..sp.dist.len() {
+            let (Some(path), Some(path_edges)) = (sp.path(v), sp.path_edges(v)) else {
+                assert!(sp.dist[v].is_none());
+                continue;
+            };
+            assert_eq!(path.len(), path_edges.len() + 1);
+            assert_eq!((src, v), (path[0], *path.last().unwrap()));
+            let mut total = 0;
+            for (i, &e) in path_edges.iter().enumerate() {
+                assert_eq!((path[i], path[i + 1]), (list[e].0, list[e].1));
+                total += list[e].2;
+            }
+            assert_eq!(sp.dist[v], Some(total));
+        }
+    }
+
+    #[test]
+    fn check_shortest_paths() {
+        let mut rng = rand::rngs::SmallRng::seed_from_u64(25);
+        for iter in 0..600 {
+            let n = rng.random_range(1..12);
+            let m = rng.random_range(0..40);
+            let w = match iter % 3 {
+                0 => 0..2,
+                1 => 0..100,
+                _ => -20..100,
+            };
+            let (graph, list) = random_graph(&mut rng, n, m, w);
+            let src = rng.random_range(0..n);
+
+            // Reference: the plain Bellman–Ford algorithm
+            let mut dist = vec![None; n];
+            dist[src] = Some(0i64);
+            let mut negative_cycle = false;
+            for round in 0..=n {
+                let mut updated = false;
+                for &(u, v, w) in &list {
+                    if let Some(du) = dist[u]
+                        && dist[v].is_none_or(|dv| du + w < dv)
+                    {
+                        dist[v] = Some(du + w);
+                        updated = true;
+                    }
+                }
+                negative_cycle = updated && round == n;
+            }
+
+            match bellman_ford(&graph, src) {
+                Ok(sp) => {
+                    assert!(!negative_cycle);
+                    assert_eq!(dist, sp.dist);
+                    check_paths(&sp, &list, src);
+                }
+                Err(cycle) => {
+                    assert!(negative_cycle);
+                    let mut total = 0;
+                    for (i, &u) in cycle.iter().enumerate() {
+                        let v = cycle[(i + 1) % cycle.len()];
+                        total += list
+                            .iter()
+                            .filter(|e| (e.0, e.1) == (u, v))
+                            .map(|e| e.2)
+                            .min()
+                            .unwrap();
+                    }
+                    assert!(total < 0);
+                }
+            }
+
+            match floyd_warshall(&graph) {
+                Some(all) => {
+                    assert!(!negative_cycle);
+                    assert_eq!(dist, all.dist[src]);
+                    for (v, &dv) in dist.iter().enumerate() {
+                        let Some(path) = all.path(src, v) else {
+                            continue;
+                        };
+                        let mut total = 0;
+                        for p in path.windows(2) {
+                            total += list
+                                .iter()
+                                .filter(|e| (e.0, e.1) == (p[0], p[1]))
+                                .map(|e| e.2)
+                                .min()
+                                .unwrap();
+                        }
+                        assert_eq!(dv, Some(total));
+                    }
+                }
+                None => assert!(iter % 3 == 2),
+            }
+
+            if iter % 3 != 2 {
+                for sp in [dijkstra(&graph, src), dijkstra_radix(&graph, src)] {
+                    assert_eq!(dist, sp.dist);
+                    check_paths(&sp, &list, src);
+                }
+            }
+            if iter % 3 == 0 {
+                let sp = bfs01(&graph, src);
+                assert_eq!(dist, sp.dist);
+                check_paths(&sp, &list, src);
+            }
+        }
+    }
+}
@@ -1,15 +1,31 @@
+mod bigint;
+pub use bigint::{BigInt, BigUint, ParseBigIntError};
 mod charpoly;
 pub use charpoly::charpoly_u64;
+mod crt;
+pub use crt::{CrtOps, crt, garner};
+mod matrix;
+pub use matrix::Matrix;
 pub mod miller_rabin;
 pub use miller_rabin::*;
 mod modint_dynamic;
-pub use modint_dynamic::FastModOps;
+pub use modint_dynamic::{DynModInt, FastModOps};
+mod modint_static;
+pub use modint_static::{ModInt, ModInt998244353, ModInt1000000007};
 mod sieve;
 pub use sieve::LinearSieve;
 mod pollard_rho;
 pub use pollard_rho::{divisors, factorize, factorize_dedup};
+mod prime_count;
+pub use prime_count::{
+    divisor_sum, mertens, multiplicative_sum, prime_count, prime_sum, totient_sum,
+};
 mod reeds_sloane;
-pub use reeds_sloane::{linear_fit, reeds_sloane};
+pub use reeds_sloane::{linear_fit, linear_guess_nth, reeds_sloane};
+mod segmented_sieve;
+pub use segmented_sieve::SegmentedSieve;
+mod residue;
+pub use residue::{discrete_log, kth_root_mod, primitive_root, sqrt_mod};
 
 pub mod ntt;
 pub use ntt::*;
@@ -0,0 +1,1143 @@
+use super::multiply_u64;
+use crate::platform::io::{Print, Readable, ReaderTrait, Writer};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::fmt;
+use core::ops::*;
+use core::str::FromStr;
+
+/// Below this many limbs (of the shorter operand), schoolbook multiplication is used.
+const KARATSUBA_THRESHOLD: usize = 32;
+/// From this many limbs (of the shorter operand), NTT multiplication (`multiply_u64`) is used.
+const NTT_THRESHOLD: usize = 256;
+/// Below this many limbs (of the divisor or the quotient), schoolbook division is used.
+const NEWTON_THRESHOLD: usize = 64;
+/// Decimal conversion switches to the quadratic algorithm below `1 << DEC_BASECASE_LOG` chunks.
+const DEC_BASECASE_LOG: usize = 5;
+/// Each chunk of the decimal conversion holds 19 digits.
+const DEC_CHUNK: u64 = 10_000_000_000_000_000_000;
+const DEC_CHUNK_DIGITS: usize = 19;
+
+fn trim(v: &mut Vec<u64>) {
+    while v.last() == Some(&0) {
+        v.pop();
+    }
+}
+
+/// Compares two little-endian integers without trailing zeros.
+fn cmp_slice(a: &[u64], b: &[u64]) -> Ordering {
+    a.len()
+        .cmp(&b.len())
+        .then_with(|| a.iter().rev().cmp(b.iter().rev()))
+}
+
+/// Computes `a += b << (64 * off)`. The result must fit in `a`.
+fn add_at(a: &mut [u64], off: usize, b: &[u64]) {
+    let mut carry = false;
+    for (j, &x) in b.iter().enumerate() {
+        let i = off + j;
+        if i >= a.len() {
+            debug_assert!(!carry && b[j..].iter().all(|&x| x == 0));
+            return;
+        }
+        let (s, o1) = a[i].overflowing_add(x);
+        let (s, o2) = s.overflowing_add(carry as u64);
+        a[i] = s;
+        carry = o1 || o2;
+    }
+    let mut i = off + b.len();
+    while carry {
+        let (s, o) = a[i].overflowing_add(1);
+        a[i] = s;
+        carry = o;
+        i += 1;
+    }
+}
+
+/// Computes `a -= b` where `b.len() <= a.len()`. Returns the final borrow.
+fn sub_slice(a: &mut [u64], b: &[u64]) -> bool {
+    let mut borrow = false;
+    for (i, x) in a.iter_mut().enumerate() {
+        if i >= b.len() && !borrow {
+            break;
+        }
+        let y = if i < b.len() { b[i] } else { 0 };
+        let (s, o1) = x.overflowing_sub(y);
+        let (s, o2) = s.overflowing_sub(borrow as u64);
+        *x = s;
+        borrow = o1 || o2;
+    }
+    borrow
+}
+
+fn add_vec(a: &[u64], b: &[u64]) -> Vec<u64> {
+    let (a, b) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+    let mut out = Vec::with_capacity(a.len() + 1);
+    out.extend_from_slice(a);
+    out.push(0);
+    add_at(&mut out, 0, b);
+    out
+}
+
+fn mul_schoolbook(a: &[u64], b: &[u64]) -> Vec<u64> {
+    let mut out = vec![0u64; a.len() + b.len()];
+    for (i, &x) in a.iter().enumerate() {
+        let mut carry = 0u128;
+        for (j, &y) in b.iter().enumerate() {
+            let t = x as u128 * y as u128 + out[i + j] as u128 + carry;
+            out[i + j] = t as u64;
+            carry = t >> 64;
+        }
+        out[i + b.len()] = carry as u64;
+    }
+    out
+}
+
+/// Multiplies two little-endian integers. The output has length `a.len() + b.len()`
+/// (or zero if either input is empty), possibly with trailing zeros.
+fn mul_vec(a: &[u64], b: &[u64]) -> Vec<u64> {
+    let (a, b) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+    if b.is_empty() {
+        Vec::new()
+    } else if b.len() < KARATSUBA_THRESHOLD {
+        mul_schoolbook(a, b)
+    } else if b.len() >= NTT_THRESHOLD {
+        multiply_u64(a, b)
+    } else if 2 * b.len() <= a.len() {
+        /* unbalanced: split `a` into chunks of length `b.len()` */
+        let mut out = vec![0u64; a.len() + b.len()];
+        for (i, chunk) in a.chunks(b.len()).enumerate() {
+            add_at(&mut out, i * b.len(), &mul_vec(chunk, b));
+        }
+        out
+    } else {
+        /* Karatsuba; note that b.len() > m holds */
+        let m = a.len() / 2;
+        let (a0, a1) = a.split_at(m);
+        let (b0, b1) = b.split_at(m);
+        let z0 = mul_vec(a0, b0);
+        let z2 = mul_vec(a1, b1);
+        let mut z1 = mul_vec(&add_vec(a0, a1), &add_vec(b0, b1));
+        sub_slice(&mut z1, &z0);
+        sub_slice(&mut z1, &z2);
+        let mut out = vec![0u64; a.len() + b.len()];
+        add_at(&mut out, 0, &z0);
+        add_at(&mut out, m, &z1);
+        add_at(&mut out, 2 * m, &z2);
+        out
+    }
+}
+
+/// Divides `a` by `d` in place and returns the remainder.
+fn divrem_u64(a: &mut [u64], d: u64) -> u64 {
+    let mut r = 0u128;
+    for x in a.iter_mut().rev() {
+        let cur = (r << 64) | *x as u128;
+        *x = (cur / d as u128) as u64;
+        r = cur % d as u128;
+    }
+    r as u64
+}
+
+/// Computes `a = a * m + c`.
+fn muladd_u64(a: &mut Vec<u64>, m: u64, c: u64) {
+    let mut carry = c as u128;
+    for x in a.iter_mut() {
+        let t = *x as u128 * m as u128 + carry;
+        *x = t as u64;
+        carry = t >> 64;
+    }
+    if carry > 0 {
+        a.push(carry as u64);
+    }
+}
+
+fn shl_bits(a: &[u64], s: u32) -> Vec<u64> {
+    let mut out = Vec::with_capacity(a.len() + 1);
+    if s == 0 {
+        out.extend_from_slice(a);
+        out.push(0);
+    } else {
+        let mut carry = 0;
+        for &x in a {
+            out.push((x << s) | carry);
+            carry = x >> (64 - s);
+        }
+        out.push(carry);
+    }
+    out
+}
+
+fn shr_bits(a: &[u64], s: u32) -> Vec<u64> {
+    if s == 0 {
+        return a.to_vec();
+    }
+    let mut out = Vec::with_capacity(a.len());
+    for i in 0..a.len() {
+        let hi = if i + 1 < a.len() {
+            a[i + 1] << (64 - s)
+        } else {
+            0
+        };
+        out.push((a[i] >> s) | hi);
+    }
+    out
+}
+
+/// Knuth's Algorithm D. Requires `b.len() >= 2`, `a.len() >= b.len()` and no trailing zeros.
+fn divmod_knuth(a: &[u64], b: &[u64]) -> (Vec<u64>, Vec<u64>) {
+    let (n, m) = (a.len(), b.len());
+    let s = b[m - 1].leading_zeros();
+    let b = shl_bits(b, s);
+    let b = &b[..m];
+    let mut u = shl_bits(a, s);
+    let mut q = vec![0u64; n - m + 1];
+    let (b1, b0) = (b[m - 1] as u128, b[m - 2] as u128);
+    for j in (0..=n - m).rev() {
+        let num = ((u[j + m] as u128) << 64) | u[j + m - 1] as u128;
+        let mut qhat = num / b1;
+        let mut rhat = num % b1;
+        if qhat > u64::MAX as u128 {
+            qhat = u64::MAX as u128;
+            rhat = num - qhat * b1;
+        }
+        while rhat <= u64::MAX as u128 && qhat * b0 > ((rhat << 64) | u[j + m - 2] as u128) {
+            qhat -= 1;
+            rhat += b1;
+        }
+        /* multiply and subtract */
+        let (mut carry, mut borrow) = (0u128, false);
+        for i in 0..m {
+            let p = qhat * b[i] as u128 + carry;
+            carry = p >> 64;
+            let (t, o1) = u[i + j].overflowing_sub(p as u64);
+            let (t, o2) = t.overflowing_sub(borrow as u64);
+            u[i + j] = t;
+            borrow = o1 || o2;
+        }
+        let (t, o1) = u[j + m].overflowing_sub(carry as u64);
+        let (t, o2) = t.overflowing_sub(borrow as u64);
+        u[j + m] = t;
+        if o1 || o2 {
+            /* qhat was one too large; add back */
+            qhat -= 1;
+            let mut c = 0u128;
+            for i in 0..m {
+                let t = u[i + j] as u128 + b[i] as u128 + c;
+                u[i + j] = t as u64;
+                c = t >> 64;
+            }
+            u[j + m] = u[j + m].wrapping_add(c as u64);
+        }
+        q[j] = qhat as u64;
+    }
+    let mut r = shr_bits(&u[..m], s);
+    trim(&mut q);
+    trim(&mut r);
+    (q, r)
+}
+
+/// Approximates `floor(2**(64 * (b.len() + l)) / b)` by Newton's iteration.
+/// The error is bounded by a small constant. `b` must not have trailing zeros.
+fn recip(b: &[u64], l: usize) -> Vec<u64> {
+    let m = b.len();
+    if m > l + 2 {
+        /* only the top limbs of `b` contribute to the precision we need */
+        return recip(&b[m - l - 2..], l);
+    }
+    if l <= NEWTON_THRESHOLD {
+        let mut num = vec![0u64; m + l + 1];
+        num[m + l] = 1;
+        return if m == 1 {
+            divrem_u64(&mut num, b[0]);
+            trim(&mut num);
+            num
+        } else {
+            divmod_knuth(&num, b).0
+        };
+    }
+    /* x0 ~ B**(m+h) / b; then x1 = x0 * B**(l-h) + x0 * (B**(m+h) - b * x0) / B**(m+2h-l) */
+    let h = l.div_ceil(2) + 1;
+    let x0 = recip(b, h);
+    let mut bx = mul_vec(b, &x0);
+    trim(&mut bx);
+    let mut pow = vec![0u64; m + h + 1];
+    pow[m + h] = 1;
+    let neg = cmp_slice(&bx, &pow) == Ordering::Greater;
+    let mut e = if neg {
+        sub_slice(&mut bx, &pow);
+        bx
+    } else {
+        sub_slice(&mut pow, &bx);
+        pow
+    };
+    trim(&mut e);
+    let t = mul_vec(&x0, &e);
+    let shift = m + 2 * h - l;
+    let mut t = if shift < t.len() {
+        t[shift..].to_vec()
+    } else {
+        Vec::new()
+    };
+    trim(&mut t);
+    let mut out = vec![0u64; l - h];
+    out.extend_from_slice(&x0);
+    out.push(0);
+    if neg {
+        sub_slice(&mut out, &t);
+    } else {
+        add_at(&mut out, 0, &t);
+    }
+    trim(&mut out);
+    out
+}
+
+fn increment(a: &mut Vec<u64>) {
+    a.push(0);
+    add_at(a, 0, &[1]);
+    trim(a);
+}
+
+/// Computes the quotient and the remainder. Inputs must not have trailing zeros, and `b` must be nonzero.
+fn divmod_vec(a: &[u64], b: &[u64]) -> (Vec<u64>, Vec<u64>) {
+    assert!(!b.is_empty(), "division by zero");
+    if cmp_slice(a, b) == Ordering::Less {
+        return (Vec::new(), a.to_vec());
+    }
+    let (n, m) = (a.len(), b.len());
+    if m == 1 {
+        let mut q = a.to_vec();
+        let r = divrem_u64(&mut q, b[0]);
+        trim(&mut q);
+        return (q, if r == 0 { Vec::new() } else { vec![r] });
+    }
+    if m < NEWTON_THRESHOLD || n - m < NEWTON_THRESHOLD {
+        return divmod_knuth(a, b);
+    }
+    /* estimate the quotient with the reciprocal, then correct it */
+    let l = n - m + 1;
+    let v = recip(b, l);
+    let qv = mul_vec(a, &v);
+    let mut q = if m + l < qv.len() {
+        qv[m + l..].to_vec()
+    } else {
+        Vec::new()
+    };
+    trim(&mut q);
+    let mut qb = mul_vec(&q, b);
+    trim(&mut qb);
+    while cmp_slice(&qb, a) == Ordering::Greater {
+        sub_slice(&mut q, &[1]);
+        trim(&mut q);
+        sub_slice(&mut qb, b);
+        trim(&mut qb);
+    }
+    let mut r = a.to_vec();
+    sub_slice(&mut r, &qb);
+    trim(&mut r);
+    while cmp_slice(&r, b) != Ordering::Less {
+        sub_slice(&mut r, b);
+        trim(&mut r);
+        increment(&mut q);
+    }
+    (q, r)
+}
+
+/// Returns `[10**19, 10**38, 10**76, ...]`, i.e., `pows[k] = 10**(19 * 2**k)`, up to the first entry exceeding `x`.
+fn dec_pows_exceeding(x: &[u64]) -> Vec<Vec<u64>> {
+    let mut pows = vec![vec![DEC_CHUNK]];
+    while cmp_slice(pows.last().unwrap(), x) != Ordering::Greater {
+        let p = pows.last().unwrap();
+        let mut sq = mul_vec(p, p);
+        trim(&mut sq);
+        pows.push(sq);
+    }
+    pows
+}
+
+/// Writes exactly `2**k` base-`10**19` chunks of `x < pows[k]` into `out` (least significant first).
+fn to_dec_chunks(mut x: Vec<u64>, pows: &[Vec<u64>], k: usize, out: &mut Vec<u64>) {
+    if k <= DEC_BASECASE_LOG {
+        for _ in 0..(1usize << k) {
+            out.push(divrem_u64(&mut x, DEC_CHUNK));
+            trim(&mut x);
+        }
+    } else {
+        let (q, r) = divmod_vec(&x, &pows[k - 1]);
+        to_dec_chunks(r, pows, k - 1, out);
+        to_dec_chunks(q, pows, k - 1, out);
+    }
+}
+
+fn to_dec_bytes(x: &[u64]) -> Vec<u8> {
+    if x.is_empty() {
+        return vec![b'0'];
+    }
+    let pows = dec_pows_exceeding(x);
+    let mut chunks = Vec::new();
+    to_dec_chunks(x.to_vec(), &pows, pows.len() - 1, &mut chunks);
+    trim(&mut chunks);
+    let mut out = Vec::with_capacity(chunks.len() * DEC_CHUNK_DIGITS);
+    let mut buf = [0u8; DEC_CHUNK_DIGITS];
+    for (i, &c) in chunks.iter().rev().enumerate() {
+        let mut c = c;
+        for b in buf.iter_mut().rev() {
+            *b = b'0' + (c % 10) as u8;
+            c /= 10;
+        }
+        let skip = if i == 0 {
+            buf.iter().position(|&b| b != b'0').unwrap()
+        } else {
+            0
+        };
+        out.extend_from_slice(&buf[skip..]);
+    }
+    out
+}
+
+/// Parses decimal digits (all of which must be in `b'0'..=b'9'`).
+fn from_dec_rec(s: &[u8], pows: &mut Vec<Vec<u64>>) -> Vec<u64> {
+    if s.len() <= DEC_CHUNK_DIGITS << DEC_BASECASE_LOG {
+        let mut out = Vec::new();
+        let first = s.len() % DEC_CHUNK_DIGITS;
+        let (head, tail) = s.split_at(first);
+        let parse = |s: &[u8]| s.iter().fold(0u64, |acc, &b| acc * 10 + (b - b'0') as u64);
+        muladd_u64(&mut out, 0, parse(head));
+        for chunk in tail.chunks(DEC_CHUNK_DIGITS) {
+            muladd_u64(&mut out, DEC_CHUNK, parse(chunk));
+        }
+        trim(&mut out);
+        return out;
+    }
+    /* split off the lowest 19 * 2**j digits where 19 * 2**j < s.len() <= 19 * 2**(j+1) */
+    let mut j = 0;
+    while DEC_CHUNK_DIGITS << (j + 1) < s.len() {
+        j += 1;
+    }
+    while pows.len() <= j {
+        let p = pows.last().unwrap();
+        let mut sq = mul_vec(p, p);
+        trim(&mut sq);
+        pows.push(sq);
+    }
+    let (hi, lo) = s.split_at(s.len() - (DEC_CHUNK_DIGITS << j));
+    let hi = from_dec_rec(hi, pows);
+    let lo = from_dec_rec(lo, pows);
+    let mut out = mul_vec(&hi, &pows[j]);
+    out.push(0);
+    if out.len() < lo.len() {
+        out.resize(lo.len(), 0);
+    }
+    add_at(&mut out, 0, &lo);
+    trim(&mut out);
+    out
+}
+
+/// An error returned when parsing a [`BigUint`] or a [`BigInt`] fails.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ParseBigIntError;
+
+/// Arbitrary-precision unsigned integer.
+///
+/// Multiplication switches from schoolbook to Karatsuba to NTT (`multiply_u64`) as the operands grow,
+/// and division uses Newton's reciprocal iteration for large operands.
+/// Decimal conversion in both directions is done by divide and conquer.
+///
+/// Example:
+/// ```
+/// use basm_std::math::BigUint;
+/// let x: BigUint = "123456789012345678901234567890".parse().unwrap();
+/// let y = BigUint::from(2u64).pow(100);
+/// assert_eq!("1267650600228229401496703205376", y.to_string());
+/// assert_eq!(x, &x * &y / &y);
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash, Default)]
+pub struct BigUint {
+    /* little-endian limbs without trailing zeros */
+    d: Vec<u64>,
+}
+
+impl BigUint {
+    /// Returns zero.
+    pub fn zero() -> Self {
+        Self { d: Vec::new() }
+    }
+    /// Returns one.
+    pub fn one() -> Self {
+        Self { d: vec![1] }
+    }
+    /// Creates a `BigUint` from little-endian `u64` limbs.
+    pub fn from_limbs(limbs: &[u64]) -> Self {
+        let mut d = limbs.to_vec();
+        trim(&mut d);
+        Self { d }
+    }
+    /// Returns the little-endian `u64` limbs. The most significant limb is nonzero, and zero has no limbs.
+    pub fn limbs(&self) -> &[u64] {
+        &self.d
+    }
+    /// Returns `true` if `self` equals zero.
+    pub fn is_zero(&self) -> bool {
+        self.d.is_empty()
+    }
+    /// Returns the number of bits needed to represent `self`. Zero needs zero bits.
+    pub fn bits(&self) -> u64 {
+        match self.d.last() {
+            None => 0,
+            Some(x) => 64 * self.d.len() as u64 - x.leading_zeros() as u64,
+        }
+    }
+    /// Converts `self` into `u64`, returning `None` on overflow.
+    pub fn to_u64(&self) -> Option<u64> {
+        match self.d.len() {
+            0 => Some(0),
+            1 => Some(self.d[0]),
+            _ => None,
+        }
+    }
+    /// Converts `self` into `u128`, returning `None` on overflow.
+    pub fn to_u128(&self) -> Option<u128> {
+        match self.d.len() {
+            0 => Some(0),
+            1 => Some(self.d[0] as u128),
+            2 => Some(((self.d[1] as u128) << 64) | self.d[0] as u128),
+            _ => None,
+        }
+    }
+    /// Computes `self ** exponent`.
+    pub fn pow(&self, mut exponent: u64) -> Self {
+        let mut base = self.clone();
+        let mut out = Self::one();
+        while exponent > 0 {
+            if exponent & 1 != 0 {
+                out = &out * &base;
+            }
+            exponent >>= 1;
+            if exponent > 0 {
+                base = &base * &base;
+            }
+        }
+        out
+    }
+    /// Computes the quotient and the remainder of `self / rhs`.
+    ///
+    /// This function will panic if `rhs` is zero.
+    pub fn divmod(&self, rhs: &Self) -> (Self, Self) {
+        let (q, r) = divmod_vec(&self.d, &rhs.d);
+        (Self { d: q }, Self { d: r })
+    }
+    /// Computes `self - rhs`, returning `None` if the result would be negative.
+    pub fn checked_sub(&self, rhs: &Self) -> Option<Self> {
+        if cmp_slice(&self.d, &rhs.d) == Ordering::Less {
+            None
+        } else {
+            let mut d = self.d.clone();
+            sub_slice(&mut d, &rhs.d);
+            trim(&mut d);
+            Some(Self { d })
+        }
+    }
+    /// Returns the decimal representation of `self` as ASCII bytes.
+    pub fn to_dec_bytes(&self) -> Vec<u8> {
+        to_dec_bytes(&self.d)
+    }
+    /// Parses a decimal representation given as ASCII bytes. Returns `None` if `s` is empty or contains a non-digit.
+    pub fn from_dec_bytes(s: &[u8]) -> Option<Self> {
+        if s.is_empty() || !s.iter().all(u8::is_ascii_digit) {
+            return None;
+        }
+        let mut pows = vec![vec![DEC_CHUNK]];
+        Some(Self {
+            d: from_dec_rec(s, &mut pows),
+        })
+    }
+}
+
+impl From<u64> for BigUint {
+    fn from(x: u64) -> Self {
+        Self::from_limbs(&[x])
+    }
+}
+
+impl From<u32> for BigUint {
+    fn from(x: u32) -> Self {
+        Self::from(x as u64)
+    }
+}
+
+impl From<usize> for BigUint {
+    fn from(x: usize) -> Self {
+        Self::from(x as u64)
+    }
+}
+
+impl From<u128> for BigUint {
+    fn from(x: u128) -> Self {
+        Self::from_limbs(&[x as u64, (x >> 64) as u64])
+    }
+}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &Self) -> Ordering {
+        cmp_slice(&self.d, &other.d)
+    }
+}
+
+impl Add<&BigUint> for &BigUint {
+    type Output = BigUint;
+    fn add(self, rhs: &BigUint) -> BigUint {
+        let mut d = add_vec(&self.d, &rhs.d);
+        trim(&mut d);
+        BigUint { d }
+    }
+}
+
+/// Panics if the result would be negative. Use [`BigUint::checked_sub`] to handle that case.
+impl Sub<&BigUint> for &BigUint {
+    type Output = BigUint;
+    fn sub(self, rhs: &BigUint) -> BigUint {
+        self.checked_sub(rhs)
+            .expect("attempt to subtract with overflow")
+    }
+}
+
+impl Mul<&BigUint> for &BigUint {
+    type Output = BigUint;
+    fn mul(self, rhs: &BigUint) -> BigUint {
+        let mut d = mul_vec(&self.d, &rhs.d);
+        trim(&mut d);
+        BigUint { d }
+    }
+}
+
+impl Div<&BigUint> for &BigUint {
+    type Output = BigUint;
+    fn div(self, rhs: &BigUint) -> BigUint {
+        self.divmod(rhs).0
+    }
+}
+
+impl Rem<&BigUint> for &BigUint {
+    type Output = BigUint;
+    fn rem(self, rhs: &BigUint) -> BigUint {
+        self.divmod(rhs).1
+    }
+}
+
+macro_rules! forward_binary_ops {
+    ($t:ty, $($tr:ident $f:ident $tr_assign:ident $f_assign:ident),*) => { $(
+        impl $tr<$t> for $t {
+            type Output = $t;
+            fn $f(self, rhs: $t) -> $t {
+                $tr::$f(&self, &rhs)
+            }
+        }
+        impl $tr<&$t> for $t {
+            type Output = $t;
+            fn $f(self, rhs: &$t) -> $t {
+                $tr::$f(&self, rhs)
+            }
+        }
+        impl $tr<$t> for &$t {
+            type Output = $t;
+            fn $f(self, rhs: $t) -> $t {
+                $tr::$f(self, &rhs)
+            }
+        }
+        impl $tr_assign<$t> for $t {
+            fn $f_assign(&mut self, rhs: $t) {
+                *self = $tr::$f(&*self, &rhs);
+            }
+        }
+        impl $tr_assign<&$t> for $t {
+            fn $f_assign(&mut self, rhs: &$t) {
+                *self = $tr::$f(&*self, rhs);
+            }
+        }
+    )* };
+}
+forward_binary_ops!(
+    BigUint,
+    Add add AddAssign add_assign,
+    Sub sub SubAssign sub_assign,
+    Mul mul MulAssign mul_assign,
+    Div div DivAssign div_assign,
+    Rem rem RemAssign rem_assign
+);
+
+impl Shl<usize> for &BigUint {
+    type Output = BigUint;
+    fn shl(self, bits: usize) -> BigUint {
+        if self.is_zero() {
+            return BigUint::zero();
+        }
+        let mut d = vec![0u64; bits / 64];
+        d.extend(shl_bits(&self.d, (bits % 64) as u32));
+        trim(&mut d);
+        BigUint { d }
+    }
+}
+
+impl Shr<usize> for &BigUint {
+    type Output = BigUint;
+    fn shr(self, bits: usize) -> BigUint {
+        if bits / 64 >= self.d.len() {
+            return BigUint::zero();
+        }
+        let mut d = shr_bits(&self.d[bits / 64..], (bits % 64) as u32);
+        trim(&mut d);
+        BigUint { d }
+    }
+}
+
+impl Shl<usize> for BigUint {
+    type Output = BigUint;
+    fn shl(self, bits: usize) -> BigUint {
+        &self << bits
+    }
+}
+
+impl Shr<usize> for BigUint {
+    type Output = BigUint;
+    fn shr(self, bits: usize) -> BigUint {
+        &self >> bits
+    }
+}
+
+impl fmt::Display for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = self.to_dec_bytes();
+        f.pad_integral(true, "", core::str::from_utf8(&s).unwrap())
+    }
+}
+
+impl fmt::Debug for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl FromStr for BigUint {
+    type Err = ParseBigIntError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_dec_bytes(s.as_bytes()).ok_or(ParseBigIntError)
+    }
+}
+
+/// Reads a nonnegative decimal integer of arbitrary length.
+impl Readable for BigUint {
+    fn read(reader: &mut impl ReaderTrait) -> Self {
+        reader.word().parse().unwrap()
+    }
+}
+
+/// Writes a single `BigUint` in decimal to standard output. Note that `print()` doesn't add a newline at the end of the output. If a newline is needed, use `println()`.
+impl<const N: usize> Print<&BigUint> for Writer<N> {
+    fn print(&mut self, x: &BigUint) {
+        self.bytes(&x.to_dec_bytes());
+    }
+    fn println(&mut self, x: &BigUint) {
+        self.print(x);
+        self.byte(b'\n');
+    }
+}
+
+/// Writes a single `BigUint` in decimal to standard output. Note that `print()` doesn't add a newline at the end of the output. If a newline is needed, use `println()`.
+impl<const N: usize> Print<BigUint> for Writer<N> {
+    fn print(&mut self, x: BigUint) {
+        self.print(&x);
+    }
+    fn println(&mut self, x: BigUint) {
+        self.println(&x);
+    }
+}
+
+/// Arbitrary-precision signed integer, stored as a sign and a [`BigUint`] magnitude.
+///
+/// Division and remainder truncate toward zero, as with primitive integers.
+///
+/// Example:
+/// ```
+/// use basm_std::math::BigInt;
+/// let x: BigInt = "-12345678901234567890".parse().unwrap();
+/// let y = BigInt::from(1_000_000_007i64);
+/// assert_eq!("-12345678814", (&x / &y).to_string());
+/// assert_eq!("-814816192", (&x % &y).to_string());
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash, Default)]
+pub struct BigInt {
+    /* always false when `mag` is zero */
+    neg: bool,
+    mag: BigUint,
+}
+
+impl BigInt {
+    /// Creates a `BigInt` from its sign and magnitude.
+    pub fn from_sign_mag(neg: bool, mag: BigUint) -> Self {
+        Self {
+            neg: neg && !mag.is_zero(),
+            mag,
+        }
+    }
+    /// Returns zero.
+    pub fn zero() -> Self {
+        Self::default()
+    }
+    /// Returns one.
+    pub fn one() -> Self {
+        Self::from(BigUint::one())
+    }
+    /// Returns `true` if `self` equals zero.
+    pub fn is_zero(&self) -> bool {
+        self.mag.is_zero()
+    }
+    /// Returns `true` if `self` is strictly negative.
+    pub fn is_negative(&self) -> bool {
+        self.neg
+    }
+    /// Returns the magnitude (absolute value) of `self`.
+    pub fn magnitude(&self) -> &BigUint {
+        &self.mag
+    }
+    /// Returns the magnitude (absolute value) of `self`, consuming it.
+    pub fn into_magnitude(self) -> BigUint {
+        self.mag
+    }
+    /// Computes `self ** exponent`.
+    pub fn pow(&self, exponent: u64) -> Self {
+        Self::from_sign_mag(self.neg && exponent & 1 != 0, self.mag.pow(exponent))
+    }
+    /// Computes the quotient and the remainder of `self / rhs`, truncating toward zero.
+    ///
+    /// This function will panic if `rhs` is zero.
+    pub fn divmod(&self, rhs: &Self) -> (Self, Self) {
+        let (q, r) = self.mag.divmod(&rhs.mag);
+        (
+            Self::from_sign_mag(self.neg != rhs.neg, q),
+            Self::from_sign_mag(self.neg, r),
+        )
+    }
+    /// Converts `self` into `i64`, returning `None` on overflow.
+    pub fn to_i64(&self) -> Option<i64> {
+        let m = self.mag.to_u64()?;
+        if self.neg {
+            0i64.checked_sub_unsigned(m)
+        } else {
+            i64::try_from(m).ok()
+        }
+    }
+    /// Returns the decimal representation of `self` as ASCII bytes.
+    pub fn to_dec_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        if self.neg {
+            out.push(b'-');
+        }
+        out.extend(self.mag.to_dec_bytes());
+        out
+    }
+    /// Parses a decimal representation, optionally preceded by `-` or `+`, given as ASCII bytes.
+    pub fn from_dec_bytes(s: &[u8]) -> Option<Self> {
+        match s.first() {
+            Some(b'-') => Some(Self::from_sign_mag(true, BigUint::from_dec_bytes(&s[1..])?)),
+            Some(b'+') => Some(Self::from(BigUint::from_dec_bytes(&s[1..])?)),
+            _ => Some(Self::from(BigUint::from_dec_bytes(s)?)),
+        }
+    }
+}
+
+impl From<BigUint> for BigInt {
+    fn from(mag: BigUint) -> Self {
+        Self { neg: false, mag }
+    }
+}
+
+impl From<i64> for BigInt {
+    fn from(x: i64) -> Self {
+        Self::from_sign_mag(x < 0, BigUint::from(x.unsigned_abs()))
+    }
+}
+
+impl From<i128> for BigInt {
+    fn from(x: i128) -> Self {
+        Self::from_sign_mag(x < 0, BigUint::from(x.unsigned_abs()))
+    }
+}
+
+impl From<u64> for BigInt {
+    fn from(x: u64) -> Self {
+        Self::from(BigUint::from(x))
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.neg, other.neg) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => self.mag.cmp(&other.mag),
+            (true, true) => other.mag.cmp(&self.mag),
+        }
+    }
+}
+
+impl Neg for BigInt {
+    type Output = BigInt;
+    fn neg(self) -> BigInt {
+        Self::from_sign_mag(!self.neg, self.mag)
+    }
+}
+
+impl Neg for &BigInt {
+    type Output = BigInt;
+    fn neg(self) -> BigInt {
+        -self.clone()
+    }
+}
+
+impl Add<&BigInt> for &BigInt {
+    type Output = BigInt;
+    fn add(self, rhs: &BigInt) -> BigInt {
+        if self.neg == rhs.neg {
+            BigInt::from_sign_mag(self.neg, &self.mag + &rhs.mag)
+        } else if self.mag >= rhs.mag {
+            BigInt::from_sign_mag(self.neg, &self.mag - &rhs.mag)
+        } else {
+            BigInt::from_sign_mag(rhs.neg, &rhs.mag - &self.mag)
+        }
+    }
+}
+
+impl Sub<&BigInt> for &BigInt {
+    type Output = BigInt;
+    fn sub(self, rhs: &BigInt) -> BigInt {
+        if self.neg != rhs.neg {
+            BigInt::from_sign_mag(self.neg, &self.mag + &rhs.mag)
+        } else if self.mag >= rhs.mag {
+            BigInt::from_sign_mag(self.neg, &self.mag - &rhs.mag)
+        } else {
+            BigInt::from_sign_mag(!self.neg, &rhs.mag - &self.mag)
+        }
+    }
+}
+
+impl Mul<&BigInt> for &BigInt {
+    type Output = BigInt;
+    fn mul(self, rhs: &BigInt) -> BigInt {
+        BigInt::from_sign_mag(self.neg != rhs.neg, &self.mag * &rhs.mag)
+    }
+}
+
+impl Div<&BigInt> for &BigInt {
+    type Output = BigInt;
+    fn div(self, rhs: &BigInt) -> BigInt {
+        self.divmod(rhs).0
+    }
+}
+
+impl Rem<&BigInt> for &BigInt {
+    type Output = BigInt;
+    fn rem(self, rhs: &BigInt) -> BigInt {
+        self.divmod(rhs).1
+    }
+}
+
+forward_binary_ops!(
+    BigInt,
+    Add add AddAssign add_assign,
+    Sub sub SubAssign sub_assign,
+    Mul mul MulAssign mul_assign,
+    Div div DivAssign div_assign,
+    Rem rem RemAssign rem_assign
+);
+
+impl fmt::Display for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = self.mag.to_dec_bytes();
+        f.pad_integral(!self.neg, "", core::str::from_utf8(&s).unwrap())
+    }
+}
+
+impl fmt::Debug for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl FromStr for BigInt {
+    type Err = ParseBigIntError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_dec_bytes(s.as_bytes()).ok_or(ParseBigIntError)
+    }
+}
+
+/// Reads a decimal integer of arbitrary length. A leading minus sign is accepted.
+impl Readable for BigInt {
+    fn read(reader: &mut impl ReaderTrait) -> Self {
+        reader.word().parse().unwrap()
+    }
+}
+
+/// Writes a single `BigInt` in decimal to standard output. Note that `print()` doesn't add a newline at the end of the output. If a newline is needed, use `println()`.
+impl<const N: usize> Print<&BigInt> for Writer<N> {
+    fn print(&mut self, x: &BigInt) {
+        if x.neg {
+            self.byte(b'-');
+        }
+        self.print(&x.mag);
+    }
+    fn println(&mut self, x: &BigInt) {
+        self.print(x);
+        self.byte(b'\n');
+    }
+}
+
+/// Writes a single `BigInt` in decimal to standard output. Note that `print()` doesn't add a newline at the end of the output. If a newline is needed, use `println()`.
+impl<const N: usize> Print<BigInt> for Writer<N> {
+    fn print(&mut self, x: BigInt) {
+        self.print(&x);
+    }
+    fn println(&mut self, x: BigInt) {
+        self.println(&x);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::string::ToString;
+    use rand::{Rng, SeedableRng};
+
+    fn random_limbs(rng: &mut rand::rngs::SmallRng, len: usize) -> Vec<u64> {
+        let mut out: Vec<u64> = (0..len).map(|_| rng.random()).collect();
+        if let Some(x) = out.last_mut() {
+            *x |= 1;
+        }
+        out
+    }
+
+    #[test]
+    fn check_biguint_small() {
+        let mut rng = rand::rngs::SmallRng::seed_from_u64(1);
+        for _ in 0..1000 {
+            let (a, b): (u64, u64) = (rng.random(), rng.random::<u64>() >> rng.random_range(0..64));
+            let (x, y) = (BigUint::from(a), BigUint::from(b));
+            assert_eq!(Some(a as u128 + b as u128), (&x + &y).to_u128());
+            assert_eq!(Some(a as u128 * b as u128), (&x * &y).to_u128());
+            assert_eq!(
+                a.checked_sub(b),
+                x.checked_sub(&y).map(|z| z.to_u64().unwrap())
+            );
+            if let Some(q) = a.checked_div(b) {
+                assert_eq!(Some(q), (&x / &y).to_u64());
+                assert_eq!(Some(a % b), (&x % &y).to_u64());
+            }
+            assert_eq!(a.cmp(&b), x.cmp(&y));
+            assert_eq!(a.to_string(), x.to_string());
+            assert_eq!(x, a.to_string().parse().unwrap());
+        }
+        assert_eq!(
+            "1267650600228229401496703205376",
+            BigUint::from(2u64).pow(100).to_string()
+        );
+        assert_eq!(BigUint::from(1u64 << 36), BigUint::from(1u64 << 40) >> 4);
+        assert_eq!(BigUint::from(1u128 << 100), BigUint::from(1u64 << 40) << 60);
+        assert_eq!(Err(ParseBigIntError), "12a".parse::<BigUint>());
+        assert_eq!(Err(ParseBigIntError), "".parse::<BigUint>());
+    }
+
+    #[test]
+    fn check_biguint_mul() {
+        let mut rng = rand::rngs::SmallRng::seed_from_u64(2);
+        for (n, m) in [
+            (33, 33),
+            (100, 70),
+            (200, 40),
+            (300, 300),
+            (1000, 260),
+            (517, 129),
+        ] {
+            let a = random_limbs(&mut rng, n);
+            let b = random_limbs(&mut rng, m);
+            assert_eq!(mul_schoolbook(&a, &b), mul_vec(&a, &b));
+        }
+    }
+
+    #[test]
+    fn check_biguint_divmod() {
+        let mut rng = rand::rngs::SmallRng::seed_from_u64(3);
+        for (n, m) in [
+            (5, 2),
+            (50, 30),
+            (200, 64),
+            (300, 100),
+            (600, 300),
+            (700, 65),
+            (500, 499),
+        ] {
+            let a = BigUint::from_limbs(&random_limbs(&mut rng, n));
+            let b = BigUint::from_limbs(&random_limbs(&mut rng, m));
+            let (q, r) = a.divmod(&b);
+            assert!(r < b);
+            assert_eq!(a, &q * &b + &r);
+        }
+        /* divisors with many maximal limbs stress the quotient correction */
+        let a = BigUint::from_limbs(&[u64::MAX; 400]);
+        let b = BigUint::from_limbs(&[u64::MAX; 150]);
+        let (q, r) = a.divmod(&b);
+        assert!(r < b);
+        assert_eq!(a, &q * &b + &r);
+    }
+
+    #[test]
+    fn check_biguint_decimal() {
+        let mut rng = rand::rngs::SmallRng::seed_from_u64(4);
+        for len in [1, 10, 40, 100, 500] {
+            let a = BigUint::from_limbs(&random_limbs(&mut rng, len));
+            assert_eq!(a, a.to_string().parse().unwrap());
+        }
+        let ten = BigUint::from(10u64);
+        let s: alloc::string::String = core::iter::once('1')
+            .chain(core::iter::repeat_n('0', 3000))
+            .collect();
+        assert_eq!(ten.pow(3000), s.parse().unwrap());
+        assert_eq!(s, ten.pow(3000).to_string());
+        let nines: alloc::string::String = core::iter::repeat_n('9', 2000).collect();
+        assert_eq!(nines, (ten.pow(2000) - BigUint::one()).to_string());
+        assert_eq!("0", BigUint::zero().to_string());
+    }
+
+    #[test]
+    fn check_bigint() {
+        for a in [-7i64, -6, -1, 0, 1, 5, 7, i64::MIN + 1, i64::MAX] {
+            for b in [-3i64, -2, -1, 1, 2, 3, 1 << 40] {
+                let (x, y) = (BigInt::from(a), BigInt::from(b));
+                assert_eq!(BigInt::from(a as i128 + b as i128), &x + &y);
+                assert_eq!(BigInt::from(a as i128 - b as i128), &x - &y);
+                assert_eq!(BigInt::from(a as i128 * b as i128), &x * &y);
+                assert_eq!(BigInt::from(a as i128 / b as i128), &x / &y);
+                assert_eq!(BigInt::from(a as i128 % b as i128), &x % &y);
+                assert_eq!(a.cmp(&b), x.cmp(&y));
+            }
+            assert_eq!(a.to_string(), BigInt::from(a).to_string());
+            assert_eq!(Some(a), BigInt::from(a).to_i64());
+        }
+        assert_eq!(BigInt::zero(), "-0".parse().unwrap());
+        assert!(!"-0".parse::<BigInt>().unwrap().is_negative());
+        assert_eq!("-8", BigInt::from(-2i64).pow(3).to_string());
+        assert_eq!(Some(i64::MIN), BigInt::from(i64::MIN).to_i64());
+        assert_eq!(None, (BigInt::from(i64::MAX) + BigInt::one()).to_i64());
+    }
+}
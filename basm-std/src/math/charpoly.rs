@@ -14,13 +14,19 @@ fn swap_col(x: &mut [Vec<u64>], i: usize, j: usize) {
     }
 }
 
-fn mul2x2_row(x: &mut [Vec<u64>], src: usize, dst: usize, mul: [[u64; 2]; 2], ops: &FastModOps) {
+pub(crate) fn mul2x2_row(
+    x: &mut [Vec<u64>],
+    src: usize,
+    dst: usize,
+    mul: [[u64; 2]; 2],
+    ops: &FastModOps,
+) {
     assert!(src != dst);
 
     // add rows
     let [[p, q], [r, s]] = mul;
     let (p, q, r, s) = (ops.premul(p), ops.premul(q), ops.premul(r), ops.premul(s));
-    for k in 0..x.len() {
+    for k in 0..x[src].len() {
         let (a, b) = (x[src][k], x[dst][k]);
         x[src][k] = ops.add(p(a), q(b));
         x[dst][k] = ops.add(r(a), s(b));
@@ -44,8 +50,10 @@ fn mul2x2_col(x: &mut [Vec<u64>], src: usize, dst: usize, mul: [[u64; 2]; 2], op
 ///
 /// `modulo` is used for modular reduction of the returned matrix.
 ///
+/// The returned matrix always has determinant 1.
+///
 /// Note that we assume `modulo` does not equal 1.
-fn egcd_matrix(mut a: u64, mut b: u64, ops: &FastModOps) -> [[u64; 2]; 2] {
+pub(crate) fn egcd_matrix(mut a: u64, mut b: u64, ops: &FastModOps) -> [[u64; 2]; 2] {
     let (mut c, mut parity) = if a < b {
         (a, b) = (b, a);
         ([0, 1, 1, 0], true)
@@ -0,0 +1,155 @@
+use super::{GcdOps, ModOps, gcd, modadd, modinv, modmul, modsub};
+use alloc::{vec, vec::Vec};
+
+/// Integer operations required by [`crt`], implemented for all unsigned integer types.
+pub trait CrtOps: ModOps<Self> + GcdOps {
+    /// Returns `self * rhs`, or `None` on overflow.
+    fn checked_mul(self, rhs: Self) -> Option<Self>;
+}
+
+macro_rules! impl_crt_ops {
+    ($($t:ty),*) => { $(
+        impl CrtOps for $t {
+            fn checked_mul(self, rhs: $t) -> Option<$t> { <$t>::checked_mul(self, rhs) }
+        }
+    )* };
+}
+impl_crt_ops!(u8, u16, u32, u64, u128, usize);
+
+/// Solves the system of congruences `x === r (mod m)` for every `(r, m)` in `congruences`.
+/// The moduli need not be pairwise coprime.
+///
+/// If the system is consistent, the result is `(r, lcm)` wrapped in `Some`,
+/// where `lcm` is the least common multiple of all moduli and `0 <= r < lcm`;
+/// the solutions are exactly the integers congruent to `r` modulo `lcm`.
+/// If the system is inconsistent or `lcm` does not fit in `T`, the result is `None`.
+/// An empty system yields `Some((0, 1))`.
+///
+/// Intermediate products never overflow; `u128` moduli are handled with `modmul128`.
+/// This function will panic if some modulus is zero.
+///
+/// Example:
+/// ```
+/// use basm_std::math::crt;
+/// assert_eq!(Some((23, 105)), crt(&[(2u64, 3), (3, 5), (2, 7)]));
+/// assert_eq!(Some((10, 12)), crt(&[(4u64, 6), (2, 4)]));
+/// assert_eq!(None, crt(&[(1u64, 6), (2, 4)]));
+/// ```
+pub fn crt<T: CrtOps>(congruences: &[(T, T)]) -> Option<(T, T)> {
+    let (zero, one): (T, T) = (0.into(), 1.into());
+    let (mut r0, mut m0) = (zero, one);
+    for &(r1, m1) in congruences {
+        assert!(m1 != zero);
+        let r1 = r1 % m1;
+        let g = gcd(m0, m1);
+        if r0 % g != r1 % g {
+            return None;
+        }
+        /* x = r0 + m0 * t, where (m0 / g) * t === (r1 - r0) / g (mod m1 / g) */
+        let m1g = m1 / g;
+        let t = if m1g == one {
+            zero
+        } else {
+            let d = modsub(r1, r0 % m1, m1) / g;
+            modmul(d, modinv((m0 / g) % m1g, m1g)?, m1g)
+        };
+        let m = m0.checked_mul(m1g)?;
+        (r0, m0) = (r0 + m0 * t, m);
+    }
+    Some((r0, m0))
+}
+
+/// Computes `x` mod `modulo` with Garner's algorithm in `O(k**2)` time,
+/// where `x` is the unique integer in `[0, m_0 * m_1 * ... * m_{k-1})` satisfying
+/// `x === r_i (mod m_i)` for every `(r_i, m_i)` in `congruences`.
+/// The product of the moduli may be arbitrarily large, since it is never formed explicitly.
+///
+/// If the moduli are pairwise coprime, the result is wrapped in `Some`. Otherwise, the result is `None`.
+/// This is the same reconstruction step that the three-prime NTT multiplication performs internally.
+///
+/// The result is computed in modulo `modulo`.
+/// If `modulo` equals 0, it is treated as `2**64`.
+/// This function will panic if some `m_i` is zero.
+///
+/// Example:
+/// ```
+/// use basm_std::math::garner;
+/// // x = 10**20 + 7 is recovered from its residues modulo three primes
+/// let ps = [998_244_353u64, 1_000_000_007, 1_000_000_009];
+/// let x = 100_000_000_000_000_000_007u128;
+/// let congruences: Vec<_> = ps.iter().map(|&p| ((x % p as u128) as u64, p)).collect();
+/// assert_eq!(Some((x % 1_000_000_000_000) as u64), garner(&congruences, 1_000_000_000_000));
+/// assert_eq!(Some(x as u64), garner(&congruences, 0));
+/// ```
+pub fn garner(congruences: &[(u64, u64)], modulo: u64) -> Option<u64> {
+    let k = congruences.len();
+    let moduli: Vec<u64> = congruences
+        .iter()
+        .map(|&(_, m)| m)
+        .chain(core::iter::once(modulo))
+        .collect();
+    /* x = t_0 + t_1 * m_0 + t_2 * m_0 * m_1 + ...;
+    coef[j] = m_0 * ... * m_{i-1} and cons[j] = t_0 + ... + t_{i-1} * m_0 * ... * m_{i-2}, both modulo moduli[j] */
+    let mut coef: Vec<u64> = moduli.iter().map(|&m| modadd(0, 1, m)).collect();
+    let mut cons = vec![0u64; k + 1];
+    for (i, &(r, m)) in congruences.iter().enumerate() {
+        assert!(m != 0);
+        let t = if m == 1 {
+            0
+        } else {
+            modmul(modsub(r, cons[i], m), modinv(coef[i], m)?, m)
+        };
+        for j in i + 1..=k {
+            let mj = moduli[j];
+            cons[j] = modadd(cons[j], modmul(t, coef[j], mj), mj);
+            coef[j] = modmul(coef[j], m, mj);
+        }
+    }
+    Some(cons[k])
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+
+    #[test]
+    fn check_crt() {
+        let mut rng = rand::rngs::SmallRng::seed_from_u64(8);
+        for _ in 0..1000 {
+            let k = rng.random_range(1..=4);
+            let moduli: Vec<u64> = (0..k).map(|_| rng.random_range(1..=60)).collect();
+            let x = rng.random_range(0..1_000_000_000u64);
+            let congruences: Vec<(u64, u64)> = moduli.iter().map(|&m| (x + m * 5, m)).collect();
+            let l = moduli.iter().fold(1, |acc, &m| crate::math::lcm(acc, m));
+            assert_eq!(Some((x % l, l)), crt(&congruences));
+            let congruences: Vec<(u64, u64)> = moduli.iter().map(|&m| (rng.random(), m)).collect();
+            if let Some((r, l)) = crt(&congruences) {
+                assert!(
+                    congruences
+                        .iter()
+                        .all(|&(ri, mi)| r % mi == ri % mi && l % mi == 0)
+                );
+            }
+        }
+        assert_eq!(Some((0u32, 1)), crt::<u32>(&[]));
+        assert_eq!(None, crt(&[(0u8, 16), (0, 17)]));
+        let (p, q) = (u64::MAX as u128 - 58, u64::MAX as u128 - 82); // primes below 2**64
+        let x = p * q - 12345;
+        assert_eq!(Some((x, p * q)), crt(&[(x % p, p), (x % q, q)]));
+    }
+
+    #[test]
+    fn check_garner() {
+        let ps = [998_244_353u64, 1_000_000_007, 1_000_000_009, 13, 1];
+        let x = 123_456_789_012_345_678_901_234_567u128;
+        let congruences: Vec<_> = ps.iter().map(|&p| ((x % p as u128) as u64, p)).collect();
+        let prod: u128 = ps.iter().map(|&p| p as u128).product();
+        for modulo in [1u64, 2, 1_000_000_007, u64::MAX] {
+            let expected = (x % prod % modulo as u128) as u64;
+            assert_eq!(Some(expected), garner(&congruences, modulo));
+        }
+        assert_eq!(Some(0), garner(&[], 17));
+        assert_eq!(None, garner(&[(1, 4), (3, 6)], 100));
+    }
+}
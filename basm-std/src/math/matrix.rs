@@ -0,0 +1,516 @@
+use crate::math::charpoly::{egcd_matrix, mul2x2_row};
+use crate::math::charpoly_u64;
+use crate::math::modint_dynamic::FastModOps;
+use crate::math::modinv;
+use alloc::{vec, vec::Vec};
+use core::ops::{Add, Index, IndexMut, Mul, Sub};
+
+/// Dense matrix stored in row-major order.
+///
+/// Arithmetic (`+`, `-`, `*` and [`Matrix::pow`]) is available for any element type
+/// with the corresponding operators, e.g., [`ModInt`](crate::math::ModInt) or `f64`.
+/// For raw `u64` entries under a (possibly non-prime) modulus, use the `_mod` methods,
+/// which share the modular kernels with [`charpoly_u64`].
+///
+/// Example:
+/// ```
+/// use basm_std::math::Matrix;
+/// let fib = Matrix::from_rows(&[[1u64, 1], [1, 0]]);
+/// assert_eq!(832040, fib.pow_mod(30, 1_000_000_007)[(0, 1)]);
+/// assert_eq!(1_000_000_006, fib.det_mod(1_000_000_007));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Matrix<T> {
+    rows: usize,
+    cols: usize,
+    data: Vec<T>,
+}
+
+impl<T> Matrix<T> {
+    /// Creates a `rows`-by-`cols` matrix from its entries in row-major order.
+    ///
+    /// This function will panic if `data.len() != rows * cols`.
+    pub fn from_vec(rows: usize, cols: usize, data: Vec<T>) -> Self {
+        assert_eq!(rows * cols, data.len());
+        Self { rows, cols, data }
+    }
+    /// Returns the number of rows.
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+    /// Returns the number of columns.
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+    /// Returns `true` if the matrix is square.
+    pub fn is_square(&self) -> bool {
+        self.rows == self.cols
+    }
+    /// Returns the `i`-th row as a slice.
+    pub fn row(&self, i: usize) -> &[T] {
+        &self.data[i * self.cols..(i + 1) * self.cols]
+    }
+    /// Returns the `i`-th row as a mutable slice.
+    pub fn row_mut(&mut self, i: usize) -> &mut [T] {
+        &mut self.data[i * self.cols..(i + 1) * self.cols]
+    }
+    /// Returns all entries in row-major order.
+    pub fn as_slice(&self) -> &[T] {
+        &self.data
+    }
+    /// Consumes the matrix and returns all entries in row-major order.
+    pub fn into_vec(self) -> Vec<T> {
+        self.data
+    }
+}
+
+impl<T: Clone> Matrix<T> {
+    /// Creates a matrix from a slice of rows. All rows must have the same length.
+    pub fn from_rows<R: AsRef<[T]>>(rows: &[R]) -> Self {
+        let cols = rows.first().map_or(0, |r| r.as_ref().len());
+        let mut data = Vec::with_capacity(rows.len() * cols);
+        for r in rows {
+            assert_eq!(cols, r.as_ref().len());
+            data.extend_from_slice(r.as_ref());
+        }
+        Self {
+            rows: rows.len(),
+            cols,
+            data,
+        }
+    }
+    /// Returns the transpose of the matrix.
+    pub fn transpose(&self) -> Self {
+        let mut data = Vec::with_capacity(self.data.len());
+        for j in 0..self.cols {
+            for i in 0..self.rows {
+                data.push(self[(i, j)].clone());
+            }
+        }
+        Self {
+            rows: self.cols,
+            cols: self.rows,
+            data,
+        }
+    }
+    fn to_rows(&self) -> Vec<Vec<T>> {
+        (0..self.rows).map(|i| self.row(i).to_vec()).collect()
+    }
+}
+
+impl<T: Clone + Default> Matrix<T> {
+    /// Creates a `rows`-by-`cols` matrix filled with `T::default()`.
+    pub fn new(rows: usize, cols: usize) -> Self {
+        Self {
+            rows,
+            cols,
+            data: vec![T::default(); rows * cols],
+        }
+    }
+}
+
+impl<T: Clone + Default + From<u8>> Matrix<T> {
+    /// Creates the `n`-by-`n` identity matrix, where zero is `T::default()` and one is `T::from(1)`.
+    pub fn identity(n: usize) -> Self {
+        let mut out = Self::new(n, n);
+        for i in 0..n {
+            out[(i, i)] = T::from(1);
+        }
+        out
+    }
+}
+
+impl<T: Clone + Default + From<u8> + Add<Output = T> + Mul<Output = T>> Matrix<T> {
+    /// Computes `self ** exponent` in `O(n**3 lg exponent)` time.
+    ///
+    /// This function will panic if the matrix is not square.
+    pub fn pow(&self, mut exponent: u64) -> Self {
+        assert!(self.is_square());
+        let mut base = self.clone();
+        let mut out = Self::identity(self.rows);
+        while exponent > 0 {
+            if exponent & 1 != 0 {
+                out = &out * &base;
+            }
+            exponent >>= 1;
+            if exponent > 0 {
+                base = &base * &base;
+            }
+        }
+        out
+    }
+}
+
+impl<T> Index<(usize, usize)> for Matrix<T> {
+    type Output = T;
+    fn index(&self, (i, j): (usize, usize)) -> &T {
+        debug_assert!(i < self.rows && j < self.cols);
+        &self.data[i * self.cols + j]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Matrix<T> {
+    fn index_mut(&mut self, (i, j): (usize, usize)) -> &mut T {
+        debug_assert!(i < self.rows && j < self.cols);
+        &mut self.data[i * self.cols + j]
+    }
+}
+
+impl<T: Clone + Add<Output = T>> Add for &Matrix<T> {
+    type Output = Matrix<T>;
+    fn add(self, rhs: &Matrix<T>) -> Matrix<T> {
+        assert!(self.rows == rhs.rows && self.cols == rhs.cols);
+        let data = self
+            .data
+            .iter()
+            .zip(rhs.data.iter())
+            .map(|(a, b)| a.clone() + b.clone())
+            .collect();
+        Matrix::from_vec(self.rows, self.cols, data)
+    }
+}
+
+impl<T: Clone + Sub<Output = T>> Sub for &Matrix<T> {
+    type Output = Matrix<T>;
+    fn sub(self, rhs: &Matrix<T>) -> Matrix<T> {
+        assert!(self.rows == rhs.rows && self.cols == rhs.cols);
+        let data = self
+            .data
+            .iter()
+            .zip(rhs.data.iter())
+            .map(|(a, b)| a.clone() - b.clone())
+            .collect();
+        Matrix::from_vec(self.rows, self.cols, data)
+    }
+}
+
+impl<T: Clone + Default + Add<Output = T> + Mul<Output = T>> Mul for &Matrix<T> {
+    type Output = Matrix<T>;
+    fn mul(self, rhs: &Matrix<T>) -> Matrix<T> {
+        assert_eq!(self.cols, rhs.rows);
+        let mut out = Matrix::<T>::new(self.rows, rhs.cols);
+        for i in 0..self.rows {
+            for k in 0..self.cols {
+                let a = &self[(i, k)];
+                let out_row = out.row_mut(i);
+                for (o, b) in out_row.iter_mut().zip(rhs.row(k)) {
+                    *o = o.clone() + a.clone() * b.clone();
+                }
+            }
+        }
+        out
+    }
+}
+
+macro_rules! forward_matrix_ops {
+    ($($tr:ident $f:ident [$($bounds:tt)*]),*) => { $(
+        impl<T: $($bounds)*> $tr for Matrix<T> {
+            type Output = Matrix<T>;
+            fn $f(self, rhs: Matrix<T>) -> Matrix<T> {
+                $tr::$f(&self, &rhs)
+            }
+        }
+    )* };
+}
+forward_matrix_ops!(
+    Add add [Clone + Add<Output = T>],
+    Sub sub [Clone + Sub<Output = T>],
+    Mul mul [Clone + Default + Add<Output = T> + Mul<Output = T>]
+);
+
+/// Reduces the first `cols` columns of `m` into row echelon form using only
+/// unimodular row operations (Euclid-style elimination), so it works for any modulus.
+/// Returns the pivot columns; pivot `k` is at `(k, pivots[k])`.
+fn echelon_mod(m: &mut [Vec<u64>], cols: usize, ops: &FastModOps) -> Vec<usize> {
+    let mut pivots = vec![];
+    let mut r = 0;
+    for c in 0..cols {
+        if r == m.len() {
+            break;
+        }
+        for i in r + 1..m.len() {
+            if m[i][c] != 0 {
+                let mul = egcd_matrix(m[r][c], m[i][c], ops);
+                mul2x2_row(m, r, i, mul, ops);
+            }
+        }
+        if m[r][c] != 0 {
+            pivots.push(c);
+            r += 1;
+        }
+    }
+    pivots
+}
+
+/// Normalizes each pivot to 1 and clears the entries above it (Gauss-Jordan elimination).
+/// Returns `None` if some pivot is not invertible.
+fn normalize_pivots_mod(m: &mut [Vec<u64>], pivots: &[usize], ops: &FastModOps) -> Option<()> {
+    for (r, &c) in pivots.iter().enumerate().rev() {
+        let inv = ops.premul(modinv(m[r][c], ops.modulo())?);
+        m[r].iter_mut().for_each(|x| *x = inv(*x));
+        for i in 0..r {
+            let f = m[i][c];
+            if f != 0 {
+                let (upper, lower) = m.split_at_mut(r);
+                for (x, &y) in upper[i].iter_mut().zip(lower[0].iter()) {
+                    *x = ops.fnmadd(f, y, *x);
+                }
+            }
+        }
+    }
+    Some(())
+}
+
+impl Matrix<u64> {
+    fn canonicalized_rows(&self, ops: &FastModOps) -> Vec<Vec<u64>> {
+        let mut m = self.to_rows();
+        m.iter_mut()
+            .for_each(|r| r.iter_mut().for_each(|x| *x = ops.canonicalize(*x)));
+        m
+    }
+    /// Computes `self * rhs` in modulo `modulo`.
+    ///
+    /// If `modulo` equals 0, it is treated as `2**64`.
+    /// Note that `modulo` does not need to be a prime.
+    pub fn mul_mod(&self, rhs: &Self, modulo: u64) -> Self {
+        assert_eq!(self.cols, rhs.rows);
+        let ops = FastModOps::new(modulo);
+        let n = rhs.cols;
+        let rhs: Vec<u64> = rhs.data.iter().map(|&x| ops.canonicalize(x)).collect();
+        let mut out = Matrix::new(self.rows, n);
+        for i in 0..self.rows {
+            for k in 0..self.cols {
+                let a = ops.premul(ops.canonicalize(self[(i, k)]));
+                let out_row = &mut out.data[i * n..(i + 1) * n];
+                for (o, &b) in out_row.iter_mut().zip(&rhs[k * n..(k + 1) * n]) {
+                    *o = ops.add(*o, a(b));
+                }
+            }
+        }
+        out
+    }
+    /// Computes `self ** exponent` in modulo `modulo` in `O(n**3 lg exponent)` time.
+    ///
+    /// If `modulo` equals 0, it is treated as `2**64`.
+    /// Note that `modulo` does not need to be a prime.
+    pub fn pow_mod(&self, mut exponent: u64, modulo: u64) -> Self {
+        assert!(self.is_square());
+        let mut base = self.clone();
+        let mut out = Self::identity(self.rows);
+        if modulo == 1 {
+            out.data.iter_mut().for_each(|x| *x = 0);
+        }
+        while exponent > 0 {
+            if exponent & 1 != 0 {
+                out = out.mul_mod(&base, modulo);
+            }
+            exponent >>= 1;
+            if exponent > 0 {
+                base = base.mul_mod(&base, modulo);
+            }
+        }
+        out
+    }
+    /// Computes the determinant in modulo `modulo` in `O(n**3 + n**2 lg modulo)` time.
+    ///
+    /// If `modulo` equals 0, it is treated as `2**64`.
+    /// Note that `modulo` does not need to be a prime; the elimination is done with
+    /// Euclid-style row reduction instead of division.
+    pub fn det_mod(&self, modulo: u64) -> u64 {
+        assert!(self.is_square());
+        if modulo == 1 {
+            return 0;
+        }
+        let ops = FastModOps::new(modulo);
+        let mut m = self.canonicalized_rows(&ops);
+        let n = self.rows;
+        let pivots = echelon_mod(&mut m, n, &ops);
+        if pivots.len() < n {
+            return 0;
+        }
+        (0..n).fold(ops.canonicalize(1), |acc, i| ops.mul(acc, m[i][i]))
+    }
+    /// Computes the rank in modulo `modulo`, which should be a prime.
+    ///
+    /// For a non-prime `modulo`, the result is the number of pivots of a row echelon form.
+    pub fn rank_mod(&self, modulo: u64) -> usize {
+        if modulo == 1 {
+            return 0;
+        }
+        let ops = FastModOps::new(modulo);
+        let mut m = self.canonicalized_rows(&ops);
+        echelon_mod(&mut m, self.cols, &ops).len()
+    }
+    /// Computes the inverse in modulo `modulo`.
+    ///
+    /// If the matrix is invertible (i.e., its determinant is invertible modulo `modulo`),
+    /// the result is wrapped in `Some`. Otherwise, the result is `None`.
+    ///
+    /// If `modulo` equals 0, it is treated as `2**64`.
+    /// Note that `modulo` does not need to be a prime.
+    pub fn inverse_mod(&self, modulo: u64) -> Option<Self> {
+        assert!(self.is_square());
+        let n = self.rows;
+        if modulo == 1 {
+            return Some(Matrix::new(n, n));
+        }
+        let ops = FastModOps::new(modulo);
+        let mut m = self.canonicalized_rows(&ops);
+        for (i, r) in m.iter_mut().enumerate() {
+            r.resize(2 * n, 0);
+            r[n + i] = 1;
+        }
+        let pivots = echelon_mod(&mut m, n, &ops);
+        if pivots.len() < n {
+            return None;
+        }
+        normalize_pivots_mod(&mut m, &pivots, &ops)?;
+        let data = m.into_iter().flat_map(|r| r.into_iter().skip(n)).collect();
+        Some(Matrix::from_vec(n, n, data))
+    }
+    /// Solves the linear system `self * x = b` in modulo `modulo`, which must be a prime.
+    /// The matrix need not be square.
+    ///
+    /// If the system is consistent, a solution is returned wrapped in `Some`
+    /// (free variables are set to zero). Otherwise, the result is `None`.
+    pub fn solve_mod(&self, b: &[u64], modulo: u64) -> Option<Vec<u64>> {
+        assert_eq!(self.rows, b.len());
+        let ops = FastModOps::new(modulo);
+        let mut m = self.canonicalized_rows(&ops);
+        for (r, &x) in m.iter_mut().zip(b) {
+            r.push(ops.canonicalize(x));
+        }
+        let pivots = echelon_mod(&mut m, self.cols, &ops);
+        if m[pivots.len()..].iter().any(|r| r[self.cols] != 0) {
+            return None;
+        }
+        normalize_pivots_mod(&mut m, &pivots, &ops)?;
+        let mut x = vec![0; self.cols];
+        for (r, &c) in pivots.iter().enumerate() {
+            x[c] = m[r][self.cols];
+        }
+        Some(x)
+    }
+    /// Computes the characteristic polynomial in modulo `modulo`. See [`charpoly_u64`].
+    pub fn charpoly_mod(&self, modulo: u64) -> Vec<u64> {
+        assert!(self.is_square());
+        charpoly_u64(&self.to_rows(), modulo)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::math::{ModInt998244353, modmul};
+
+    fn random_matrix(n: usize, m: usize, seed: u64, modulo: u64) -> Matrix<u64> {
+        use rand::{Rng, SeedableRng};
+        let mut rng = rand::rngs::SmallRng::seed_from_u64(seed);
+        let data = (0..n * m).map(|_| rng.random::<u64>() % modulo).collect();
+        Matrix::from_vec(n, m, data)
+    }
+
+    fn naive_det(m: &[Vec<u64>], modulo: u64) -> u64 {
+        let n = m.len();
+        if n == 0 {
+            return 1 % modulo;
+        }
+        let mut out = 0;
+        for j in 0..n {
+            let minor: Vec<Vec<u64>> = m[1..]
+                .iter()
+                .map(|r| [&r[..j], &r[j + 1..]].concat())
+                .collect();
+            let term = modmul(m[0][j], naive_det(&minor, modulo), modulo);
+            out = if j % 2 == 0 {
+                (out + term) % modulo
+            } else {
+                (out + modulo - term) % modulo
+            };
+        }
+        out
+    }
+
+    #[test]
+    fn check_matrix_generic() {
+        type Mint = ModInt998244353;
+        let a = Matrix::from_rows(&[[Mint::new(1), Mint::new(2)], [Mint::new(3), Mint::new(4)]]);
+        let b = &a * &a;
+        let vals: Vec<u64> = b.as_slice().iter().map(|x| x.val()).collect();
+        assert_eq!(vec![7, 10, 15, 22], vals);
+        assert_eq!(&(&b * &a) * &a, a.pow(4));
+        assert_eq!(Matrix::<Mint>::identity(2), a.pow(0));
+        let c = &a + &a;
+        assert_eq!(Mint::new(8), c[(1, 1)]);
+        assert_eq!(a, &c - &a);
+        let t = Matrix::from_rows(&[[1i64, 2, 3], [4, 5, 6]]).transpose();
+        assert_eq!((3, 2), (t.rows(), t.cols()));
+        assert_eq!(&[3, 6], t.row(2));
+    }
+
+    #[test]
+    fn check_matrix_det_mod() {
+        for modulo in [2, 12, 36, 998_244_353, 1_000_000_007] {
+            for n in 1..=6 {
+                let a = random_matrix(n, n, n as u64 * modulo, modulo);
+                assert_eq!(naive_det(&a.to_rows(), modulo), a.det_mod(modulo));
+            }
+        }
+        let a = Matrix::from_rows(&[[2u64, 3], [5, 7]]);
+        assert_eq!(u64::MAX, a.det_mod(0));
+        assert_eq!(0, Matrix::from_rows(&[[2u64, 4], [1, 2]]).det_mod(0));
+    }
+
+    #[test]
+    fn check_matrix_inverse_solve_mod() {
+        for modulo in [12, 998_244_353, 0] {
+            let a = Matrix::from_rows(&[[2u64, 3], [5, 7]]);
+            let inv = a.inverse_mod(modulo).unwrap();
+            assert_eq!(Matrix::identity(2), a.mul_mod(&inv, modulo));
+        }
+        assert_eq!(
+            None,
+            Matrix::from_rows(&[[2u64, 3], [4, 7]]).inverse_mod(12)
+        );
+        let modulo = 998_244_353;
+        for n in [1, 5, 20] {
+            let a = random_matrix(n, n, n as u64, modulo);
+            let inv = a.inverse_mod(modulo).unwrap();
+            assert_eq!(Matrix::identity(n), inv.mul_mod(&a, modulo));
+        }
+
+        /* rank-deficient rectangular system */
+        let a = Matrix::from_rows(&[[1u64, 2, 3], [2, 4, 6], [1, 0, 1]]);
+        assert_eq!(2, a.rank_mod(modulo));
+        let b = [6, 12, 2];
+        let x = a.solve_mod(&b, modulo).unwrap();
+        let xm = Matrix::from_vec(3, 1, x);
+        assert_eq!(b.to_vec(), a.mul_mod(&xm, modulo).into_vec());
+        assert_eq!(None, a.solve_mod(&[6, 13, 2], modulo));
+        let a = random_matrix(4, 7, 77, modulo);
+        assert_eq!(4, a.rank_mod(modulo));
+        let b = [1, 2, 3, 4];
+        let x = a.solve_mod(&b, modulo).unwrap();
+        assert_eq!(
+            b.to_vec(),
+            a.mul_mod(&Matrix::from_vec(7, 1, x), modulo).into_vec()
+        );
+    }
+
+    #[test]
+    fn check_matrix_pow_charpoly_mod() {
+        let fib = Matrix::from_rows(&[[1u64, 1], [1, 0]]);
+        assert_eq!(
+            [12200160415121876738u64, 7540113804746346429],
+            [fib.pow_mod(93, 0)[(0, 1)], fib.pow_mod(92, 0)[(0, 1)]]
+        );
+        assert_eq!(
+            vec![36, 9987, 1],
+            Matrix::from_rows(&[[5u64, 4], [1, 8]]).charpoly_mod(10000)
+        );
+        let a = Matrix::from_rows(&[[1u64, 2, 3]]);
+        let b = Matrix::from_rows(&[[1u64], [1], [1]]);
+        assert_eq!(vec![1], a.mul_mod(&b, 5).into_vec());
+        assert_eq!(vec![1, 2, 3, 1, 2, 3, 1, 2, 3], b.mul_mod(&a, 0).into_vec());
+    }
+}
@@ -63,7 +63,23 @@ macro_rules! impl_mont {
                 self.redc(self.r2 as $ty_large * a as $ty_large)
             }
 
-            #[allow(dead_code)]
+            /// Converts `a` out of Montgomery form.
+            pub fn from_mont(&self, a: M<$ty>) -> $ty {
+                self.redc(a.v as $ty_large).v
+            }
+
+            /// Returns `1` in Montgomery form.
+            pub fn one(&self) -> M<$ty> {
+                M { v: self.r1 }
+            }
+
+            /// Returns `base**exp` in Montgomery form; `base` must be in Montgomery form.
+            pub fn pow(&self, base: M<$ty>, exp: $ty) -> M<$ty> {
+                M {
+                    v: self.powmul(base, exp, self.r1),
+                }
+            }
+
             fn powmul(&self, base: M<$ty>, exp: $ty, v: $ty) -> $ty {
                 let mut ans = M { v };
                 let mut base = base;
@@ -1,3 +1,9 @@
+use super::{linear_nth, modinv, polymul_u64};
+use crate::platform::io::{Print, Writer};
+use alloc::vec::Vec;
+use core::fmt;
+use core::ops::*;
+
 /// Provides faster modular operations when modulo is not known at compile time.
 pub struct FastModOps {
     modulo: u64,
@@ -32,6 +38,14 @@ impl FastModOps {
             }
         }
     }
+    /// Returns the modulo. If it equals 0, it is treated as `2**64`.
+    pub fn modulo(&self) -> u64 {
+        self.modulo
+    }
+    /// Creates a [`DynModInt`] holding `x` mod `self.modulo`, bound to this object.
+    pub fn int(&self, x: u64) -> DynModInt<'_> {
+        DynModInt::new(x, self)
+    }
     /// Canonicalizes given integer `a` into the range `[0, self.modulo)`.
     pub fn canonicalize(&self, a: u64) -> u64 {
         if self.pow2 {
@@ -158,6 +172,249 @@ impl FastModOps {
     }
 }
 
+/// Modular integer whose modulo is determined at runtime.
+///
+/// Each value borrows the [`FastModOps`] object it belongs to, which serves as the context handle:
+/// the modulo is set once per scope by creating a `FastModOps`, and every value created from it
+/// shares the precomputed magic numbers. Mixing values bound to different moduli is a logic error.
+///
+/// Example:
+/// ```
+/// use basm_std::math::FastModOps;
+/// let ops = FastModOps::new(1_000_000_006);
+/// let x = ops.int(5);
+/// assert_eq!(25, (x * x).val());
+/// assert_eq!(1_000_000_001, (-x).val());
+/// assert!(ops.int(2).inv().is_none());
+/// ```
+#[derive(Clone, Copy)]
+pub struct DynModInt<'a> {
+    v: u64,
+    ops: &'a FastModOps,
+}
+
+impl<'a> DynModInt<'a> {
+    /// Creates a new `DynModInt` holding `x` mod `ops.modulo()`.
+    pub fn new(x: u64, ops: &'a FastModOps) -> Self {
+        Self {
+            v: ops.canonicalize(x),
+            ops,
+        }
+    }
+    /// Creates a new `DynModInt` holding `x` mod `ops.modulo()`, where `x` may be negative.
+    pub fn from_i64(x: i64, ops: &'a FastModOps) -> Self {
+        let out = Self::new(x.unsigned_abs(), ops);
+        if x < 0 { -out } else { out }
+    }
+    /// Returns the zero bound to `ops`.
+    pub fn zero(ops: &'a FastModOps) -> Self {
+        Self { v: 0, ops }
+    }
+    /// Returns the one bound to `ops`.
+    pub fn one(ops: &'a FastModOps) -> Self {
+        Self::new(1, ops)
+    }
+    /// Returns the canonical representative of the value in `[0, modulo)`.
+    pub fn val(self) -> u64 {
+        self.v
+    }
+    /// Returns the [`FastModOps`] object this value is bound to.
+    pub fn ops(self) -> &'a FastModOps {
+        self.ops
+    }
+    /// Returns the modulo. If it equals 0, it is treated as `2**64`.
+    pub fn modulo(self) -> u64 {
+        self.ops.modulo
+    }
+    /// Computes `self ** exponent` in `O(lg exponent)` time.
+    pub fn pow(self, mut exponent: u64) -> Self {
+        let ops = self.ops;
+        let (mut base, mut out) = (self.v, ops.canonicalize(1));
+        while exponent > 0 {
+            if exponent & 1 != 0 {
+                out = ops.mul(out, base);
+            }
+            base = ops.mul(base, base);
+            exponent >>= 1;
+        }
+        Self { v: out, ops }
+    }
+    /// Computes the multiplicative inverse using [`modinv`].
+    /// Returns `None` if `self` is not invertible. Note that the modulo does not need to be a prime.
+    pub fn inv(self) -> Option<Self> {
+        let v = modinv(self.v, self.ops.modulo)?;
+        Some(Self { v, ops: self.ops })
+    }
+}
+
+impl PartialEq for DynModInt<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        debug_assert_eq!(self.ops.modulo, other.ops.modulo);
+        self.v == other.v
+    }
+}
+
+impl Eq for DynModInt<'_> {}
+
+impl<'a> Add for DynModInt<'a> {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        debug_assert_eq!(self.ops.modulo, rhs.ops.modulo);
+        Self {
+            v: self.ops.add(self.v, rhs.v),
+            ops: self.ops,
+        }
+    }
+}
+
+impl<'a> Sub for DynModInt<'a> {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {
+        debug_assert_eq!(self.ops.modulo, rhs.ops.modulo);
+        Self {
+            v: self.ops.sub(self.v, rhs.v),
+            ops: self.ops,
+        }
+    }
+}
+
+impl<'a> Mul for DynModInt<'a> {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self {
+        debug_assert_eq!(self.ops.modulo, rhs.ops.modulo);
+        Self {
+            v: self.ops.mul(self.v, rhs.v),
+            ops: self.ops,
+        }
+    }
+}
+
+/// Panics if `rhs` is not invertible. Use [`DynModInt::inv`] to handle that case.
+impl<'a> Div for DynModInt<'a> {
+    type Output = Self;
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, rhs: Self) -> Self {
+        self * rhs.inv().expect("divisor is not invertible")
+    }
+}
+
+impl<'a> Neg for DynModInt<'a> {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self {
+            v: self.ops.neg(self.v),
+            ops: self.ops,
+        }
+    }
+}
+
+impl<'a> Neg for &DynModInt<'a> {
+    type Output = DynModInt<'a>;
+    fn neg(self) -> DynModInt<'a> {
+        -*self
+    }
+}
+
+macro_rules! impl_binary_ops {
+    ($($tr:ident $f:ident $tr_assign:ident $f_assign:ident),*) => { $(
+        impl<'a> $tr<&DynModInt<'a>> for DynModInt<'a> {
+            type Output = DynModInt<'a>;
+            fn $f(self, rhs: &DynModInt<'a>) -> DynModInt<'a> {
+                $tr::$f(self, *rhs)
+            }
+        }
+        impl<'a> $tr<DynModInt<'a>> for &DynModInt<'a> {
+            type Output = DynModInt<'a>;
+            fn $f(self, rhs: DynModInt<'a>) -> DynModInt<'a> {
+                $tr::$f(*self, rhs)
+            }
+        }
+        impl<'a> $tr<&DynModInt<'a>> for &DynModInt<'a> {
+            type Output = DynModInt<'a>;
+            fn $f(self, rhs: &DynModInt<'a>) -> DynModInt<'a> {
+                $tr::$f(*self, *rhs)
+            }
+        }
+        impl<'a> $tr_assign for DynModInt<'a> {
+            fn $f_assign(&mut self, rhs: DynModInt<'a>) {
+                *self = $tr::$f(*self, rhs);
+            }
+        }
+        impl<'a> $tr_assign<&DynModInt<'a>> for DynModInt<'a> {
+            fn $f_assign(&mut self, rhs: &DynModInt<'a>) {
+                *self = $tr::$f(*self, *rhs);
+            }
+        }
+    )* };
+}
+impl_binary_ops!(
+    Add add AddAssign add_assign,
+    Sub sub SubAssign sub_assign,
+    Mul mul MulAssign mul_assign,
+    Div div DivAssign div_assign
+);
+
+impl fmt::Display for DynModInt<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.v, f)
+    }
+}
+
+impl fmt::Debug for DynModInt<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.v, f)
+    }
+}
+
+/// Writes a single `DynModInt` to standard output. Note that `print()` doesn't add a newline at the end of the output. If a newline is needed, use `println()`.
+impl<const N: usize> Print<DynModInt<'_>> for Writer<N> {
+    fn print(&mut self, x: DynModInt<'_>) {
+        self.u64(x.v);
+    }
+    fn println(&mut self, x: DynModInt<'_>) {
+        self.u64(x.v);
+        self.byte(b'\n');
+    }
+}
+
+/// Writes a single `&DynModInt` to standard output. Note that `print()` doesn't add a newline at the end of the output. If a newline is needed, use `println()`.
+impl<const N: usize> Print<&DynModInt<'_>> for Writer<N> {
+    fn print(&mut self, x: &DynModInt<'_>) {
+        self.print(*x);
+    }
+    fn println(&mut self, x: &DynModInt<'_>) {
+        self.println(*x);
+    }
+}
+
+impl FastModOps {
+    /// Multiplies two polynomials given by coefficient lists of `DynModInt`s using [`polymul_u64`].
+    /// Every coefficient must be bound to `self`.
+    pub fn polymul<'a>(&'a self, x: &[DynModInt<'a>], y: &[DynModInt<'a>]) -> Vec<DynModInt<'a>> {
+        let x: Vec<u64> = x.iter().map(|a| a.v).collect();
+        let y: Vec<u64> = y.iter().map(|a| a.v).collect();
+        polymul_u64(&x, &y, self.modulo)
+            .into_iter()
+            .map(|v| DynModInt { v, ops: self })
+            .collect()
+    }
+    /// Computes the `n`-th term of a linear recurrence using [`linear_nth`].
+    /// The arguments follow the same convention as [`linear_nth`]. Every value must be bound to `self`.
+    pub fn linear_nth<'a>(
+        &'a self,
+        first_terms: &[DynModInt<'a>],
+        coeff: &[DynModInt<'a>],
+        n: u128,
+    ) -> DynModInt<'a> {
+        let first_terms: Vec<u64> = first_terms.iter().map(|a| a.v).collect();
+        let coeff: Vec<u64> = coeff.iter().map(|a| a.v).collect();
+        DynModInt {
+            v: linear_nth(&first_terms, &coeff, n, self.modulo),
+            ops: self,
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -199,4 +456,56 @@ mod test {
             }
         }
     }
+    #[test]
+    fn check_dynmodint() {
+        for modulo in [
+            0,
+            1,
+            2,
+            1 << 40,
+            1_000_000_006,
+            998_244_353,
+            (1u64 << 63) + 1,
+            u64::MAX,
+        ] {
+            let ops = FastModOps::new(modulo);
+            let samples = [0, 1, 2, 3, 12345, 1 << 50, u64::MAX - 1, u64::MAX];
+            for &a in samples.iter() {
+                let x = ops.int(a);
+                for &b in samples.iter() {
+                    let y = DynModInt::new(b, &ops);
+                    assert_eq!(modadd(a, b, modulo), (x + y).val());
+                    assert_eq!(modsub(a, b, modulo), (x - y).val());
+                    assert_eq!(modmul(a, b, modulo), (x * y).val());
+                    match modinv(b, modulo) {
+                        Some(inv) => assert_eq!(modmul(a, inv, modulo), (x / y).val()),
+                        None => assert!(y.inv().is_none()),
+                    }
+                    let mut z = x;
+                    z *= y;
+                    z -= &x;
+                    assert_eq!(modsub(modmul(a, b, modulo), a, modulo), z.val());
+                }
+                assert_eq!(modsub(0, a, modulo), (-x).val());
+                assert_eq!(modpow(a, 98765, modulo).unwrap(), x.pow(98765).val());
+            }
+            assert_eq!(modsub(0, 7, modulo), DynModInt::from_i64(-7, &ops).val());
+        }
+    }
+
+    #[test]
+    fn check_dynmodint_interop() {
+        let ops = FastModOps::new(1_000_000_006);
+        let x: Vec<DynModInt> = [1, 2, 3].into_iter().map(|v| ops.int(v)).collect();
+        let y: Vec<DynModInt> = [4, 5].into_iter().map(|v| ops.int(v)).collect();
+        let z: Vec<u64> = ops.polymul(&x, &y).into_iter().map(|v| v.val()).collect();
+        assert_eq!(vec![4, 13, 22, 15], z);
+        // Fibonacci numbers: F(90) = 2880067194370816120
+        let first_terms = [ops.int(0), ops.int(1)];
+        let coeff = [ops.int(1), ops.int(1)];
+        assert_eq!(
+            2880067194370816120 % 1_000_000_006,
+            ops.linear_nth(&first_terms, &coeff, 90).val()
+        );
+    }
 }
@@ -0,0 +1,316 @@
+use super::ntt::nttcore::Arith;
+use crate::platform::io::{Print, Readable, ReaderTrait, Writer};
+use core::fmt;
+use core::iter::{Product, Sum};
+use core::ops::*;
+
+/// Modular integer whose modulus `P` is known at compile time.
+///
+/// The value is kept in Montgomery form (`x * 2**64 mod P`), and every operation is
+/// carried out with the Montgomery routines of [`Arith<P>`], which are shared with the NTT.
+/// Hence `P` must be odd; using an even `P` results in a compile error.
+/// Inversion and division additionally assume that `P` is a prime.
+///
+/// Example:
+/// ```
+/// use basm_std::math::ModInt998244353 as Mint;
+/// let x = Mint::new(3);
+/// assert_eq!(332748118, x.inv().val());
+/// assert_eq!(1, (x * x.inv()).val());
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct ModInt<const P: u64> {
+    v: u64,
+}
+
+pub type ModInt998244353 = ModInt<998_244_353>;
+pub type ModInt1000000007 = ModInt<1_000_000_007>;
+
+impl<const P: u64> ModInt<P> {
+    /// The modulus `P`.
+    pub const MODULUS: u64 = P;
+    /// The additive identity.
+    pub const ZERO: Self = Self { v: 0 };
+    /// The multiplicative identity.
+    pub const ONE: Self = Self { v: Arith::<P>::R };
+
+    /// Creates a new `ModInt` holding `x mod P`.
+    pub const fn new(x: u64) -> Self {
+        // x * R2 < 2**64 * P holds for every x, so no prior reduction is needed.
+        Self {
+            v: Arith::<P>::mmulmod(x, Arith::<P>::R2),
+        }
+    }
+
+    /// Returns the canonical representative of the value in `[0, P)`.
+    pub const fn val(self) -> u64 {
+        Arith::<P>::mreduce(self.v as u128)
+    }
+
+    /// Returns `true` if the value equals zero.
+    pub const fn is_zero(self) -> bool {
+        self.v == 0
+    }
+
+    /// Computes `self ** exponent` in `O(lg exponent)` time.
+    pub const fn pow(self, exponent: u64) -> Self {
+        Self {
+            v: Arith::<P>::mpowmod(self.v, exponent),
+        }
+    }
+
+    /// Computes the multiplicative inverse by Fermat's little theorem.
+    ///
+    /// `P` must be a prime and `self` must be nonzero; otherwise the result is meaningless.
+    pub const fn inv(self) -> Self {
+        debug_assert!(self.v != 0);
+        self.pow(P - 2)
+    }
+}
+
+impl<const P: u64> Add for ModInt<P> {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        Self {
+            v: Arith::<P>::addmod(self.v, rhs.v),
+        }
+    }
+}
+
+impl<const P: u64> Sub for ModInt<P> {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {
+        Self {
+            v: Arith::<P>::submod(self.v, rhs.v),
+        }
+    }
+}
+
+impl<const P: u64> Mul for ModInt<P> {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self {
+        Self {
+            v: Arith::<P>::mmulmod(self.v, rhs.v),
+        }
+    }
+}
+
+impl<const P: u64> Div for ModInt<P> {
+    type Output = Self;
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, rhs: Self) -> Self {
+        self * rhs.inv()
+    }
+}
+
+impl<const P: u64> Neg for ModInt<P> {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self {
+            v: Arith::<P>::submod(0, self.v),
+        }
+    }
+}
+
+impl<const P: u64> Neg for &ModInt<P> {
+    type Output = ModInt<P>;
+    fn neg(self) -> ModInt<P> {
+        -*self
+    }
+}
+
+macro_rules! impl_binary_ops {
+    ($($tr:ident $f:ident $tr_assign:ident $f_assign:ident),*) => { $(
+        impl<const P: u64> $tr<&ModInt<P>> for ModInt<P> {
+            type Output = ModInt<P>;
+            fn $f(self, rhs: &ModInt<P>) -> ModInt<P> {
+                $tr::$f(self, *rhs)
+            }
+        }
+        impl<const P: u64> $tr<ModInt<P>> for &ModInt<P> {
+            type Output = ModInt<P>;
+            fn $f(self, rhs: ModInt<P>) -> ModInt<P> {
+                $tr::$f(*self, rhs)
+            }
+        }
+        impl<const P: u64> $tr<&ModInt<P>> for &ModInt<P> {
+            type Output = ModInt<P>;
+            fn $f(self, rhs: &ModInt<P>) -> ModInt<P> {
+                $tr::$f(*self, *rhs)
+            }
+        }
+        impl<const P: u64> $tr_assign for ModInt<P> {
+            fn $f_assign(&mut self, rhs: ModInt<P>) {
+                *self = $tr::$f(*self, rhs);
+            }
+        }
+        impl<const P: u64> $tr_assign<&ModInt<P>> for ModInt<P> {
+            fn $f_assign(&mut self, rhs: &ModInt<P>) {
+                *self = $tr::$f(*self, *rhs);
+            }
+        }
+    )* };
+}
+impl_binary_ops!(
+    Add add AddAssign add_assign,
+    Sub sub SubAssign sub_assign,
+    Mul mul MulAssign mul_assign,
+    Div div DivAssign div_assign
+);
+
+macro_rules! impl_from_unsigned {
+    ($($t:ty),*) => { $(
+        impl<const P: u64> From<$t> for ModInt<P> {
+            fn from(x: $t) -> Self {
+                if <$t>::BITS <= 64 {
+                    Self::new(x as u64)
+                } else {
+                    Self::new((x as u128 % P as u128) as u64)
+                }
+            }
+        }
+    )* };
+}
+impl_from_unsigned!(u8, u16, u32, u64, u128, usize);
+
+macro_rules! impl_from_signed {
+    ($($t:ty),*) => { $(
+        impl<const P: u64> From<$t> for ModInt<P> {
+            fn from(x: $t) -> Self {
+                let out = Self::from(x.unsigned_abs());
+                if x < 0 { -out } else { out }
+            }
+        }
+    )* };
+}
+impl_from_signed!(i8, i16, i32, i64, i128, isize);
+
+impl<const P: u64> Sum for ModInt<P> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::ZERO, |acc, x| acc + x)
+    }
+}
+
+impl<'a, const P: u64> Sum<&'a ModInt<P>> for ModInt<P> {
+    fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.fold(Self::ZERO, |acc, x| acc + x)
+    }
+}
+
+impl<const P: u64> Product for ModInt<P> {
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::ONE, |acc, x| acc * x)
+    }
+}
+
+impl<'a, const P: u64> Product<&'a ModInt<P>> for ModInt<P> {
+    fn product<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.fold(Self::ONE, |acc, x| acc * x)
+    }
+}
+
+impl<const P: u64> fmt::Display for ModInt<P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.val(), f)
+    }
+}
+
+impl<const P: u64> fmt::Debug for ModInt<P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.val(), f)
+    }
+}
+
+/// Reads an integer and reduces it modulo `P`. A leading minus sign is accepted.
+impl<const P: u64> Readable for ModInt<P> {
+    fn read(reader: &mut impl ReaderTrait) -> Self {
+        reader.skip_whitespace();
+        reader.try_refill(1);
+        if reader.remain().first() == Some(&b'-') {
+            reader.try_consume(1);
+            -Self::new(reader.u64())
+        } else {
+            Self::new(reader.u64())
+        }
+    }
+}
+
+/// Writes a single `ModInt<P>` to standard output. Note that `print()` doesn't add a newline at the end of the output. If a newline is needed, use `println()`.
+impl<const N: usize, const P: u64> Print<ModInt<P>> for Writer<N> {
+    fn print(&mut self, x: ModInt<P>) {
+        self.u64(x.val());
+    }
+    fn println(&mut self, x: ModInt<P>) {
+        self.u64(x.val());
+        self.byte(b'\n');
+    }
+}
+
+/// Writes a single `&ModInt<P>` to standard output. Note that `print()` doesn't add a newline at the end of the output. If a newline is needed, use `println()`.
+impl<const N: usize, const P: u64> Print<&ModInt<P>> for Writer<N> {
+    fn print(&mut self, x: &ModInt<P>) {
+        self.print(*x);
+    }
+    fn println(&mut self, x: &ModInt<P>) {
+        self.println(*x);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::math::{modinv, modmul, modpow};
+
+    #[test]
+    fn check_modint_arith() {
+        type Mint = ModInt998244353;
+        let p = Mint::MODULUS;
+        let samples = [0, 1, 2, 3, 12345, 998244352, 998244353, 1 << 40, u64::MAX];
+        for &a in samples.iter() {
+            for &b in samples.iter() {
+                let (x, y) = (Mint::new(a), Mint::new(b));
+                assert_eq!((a % p + b % p) % p, (x + y).val());
+                assert_eq!((a % p + p - b % p) % p, (x - y).val());
+                assert_eq!(modmul(a, b, p), (x * y).val());
+                if b % p != 0 {
+                    assert_eq!(modmul(a, modinv(b, p).unwrap(), p), (x / y).val());
+                }
+            }
+            assert_eq!((p - a % p) % p, (-Mint::new(a)).val());
+            assert_eq!(
+                modpow(a, 1234567, p).unwrap(),
+                Mint::new(a).pow(1234567).val()
+            );
+        }
+        let mut x = Mint::from(-1i64);
+        assert_eq!(p - 1, x.val());
+        x += Mint::ONE;
+        assert!(x.is_zero());
+        x -= Mint::from(5u32);
+        x *= Mint::from(3u8);
+        assert_eq!(p - 15, x.val());
+        x /= Mint::from(-3i32);
+        assert_eq!(5, x.val());
+    }
+
+    #[test]
+    fn check_modint_large_modulus() {
+        const P: u64 = 18_446_744_073_709_551_557; // the largest prime below 2**64
+        type Mint = ModInt<P>;
+        let x = Mint::new(P - 2);
+        assert_eq!(4, (x * x).val());
+        assert_eq!(1, (x * x.inv()).val());
+        assert_eq!(1, Mint::new(3).pow(P - 1).val());
+        // 2**128 - 1 = (2**64)**2 - 1 = 59**2 - 1 (mod P)
+        assert_eq!(3480, Mint::from(u128::MAX).val());
+    }
+
+    #[test]
+    fn check_modint_sum_product() {
+        type Mint = ModInt1000000007;
+        let v: alloc::vec::Vec<Mint> = (1..=20u64).map(Mint::new).collect();
+        assert_eq!(210, v.iter().sum::<Mint>().val());
+        assert_eq!(146326063, v.iter().product::<Mint>().val());
+        assert_eq!(146326063, v.into_iter().product::<Mint>().val());
+    }
+}
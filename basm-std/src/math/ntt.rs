@@ -1,12 +1,17 @@
 pub mod linear_recurrence;
-pub use linear_recurrence::linear_nth;
+pub use linear_recurrence::{LinearNthAlgorithm, linear_nth, linear_nth_with, rational_nth};
 pub mod multiply;
 pub mod nttcore;
 pub use multiply::multiply_u64;
 pub mod polyeval;
-pub use polyeval::polyeval_u64;
+pub use polyeval::{lagrange_interp_u64, polyeval_u64, polyinterp_u64};
 pub mod polymul;
 pub use polymul::{polymul_ex_u64, polymul_u64};
+mod polyfps;
+pub use polyfps::{
+    polyderiv_u64, polyexp_u64, polyinteg_u64, polylog_u64, polypow_u64, polysqrt_u64,
+    polytaylorshift_u64,
+};
 mod polyops;
 pub use polyops::{polyadd_u64, polydiv_u64, polyinv_u64, polymod_u64, polysub_u64};
 
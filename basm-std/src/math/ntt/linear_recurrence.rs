@@ -1,6 +1,15 @@
 use super::{polymod_u64, polymul_u64};
-use crate::math::{modadd, modmul, modsub};
-use alloc::vec;
+use crate::math::{modadd, modinv, modmul, modsub};
+use alloc::{vec, vec::Vec};
+
+/// Algorithm used by [`linear_nth_with`] to compute the `n`-th term of a linear recurrence.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LinearNthAlgorithm {
+    /// Kitamasa's algorithm: computes `x**n mod Q'(x)` by repeated squaring with the O(n lg n) NTT division.
+    Kitamasa,
+    /// Bostan-Mori algorithm: computes `[x**n] P(x)/Q(x)` by repeatedly halving `n` (see [`rational_nth`]).
+    BostanMori,
+}
 
 /// Logarithmic time linear recurrence solver.
 ///
@@ -14,40 +23,112 @@ use alloc::vec;
 /// If `modulo` equals 0, it is treated as `2**64`.
 /// Note that `modulo` does not need to be a prime.
 ///
-/// Current implementation uses the Kitamasa algorithm along with the O(n lg n) NTT division.
-/// This is subject to change (e.g., Bostan-Mori).
-pub fn linear_nth(first_terms: &[u64], coeff: &[u64], mut n: u128, modulo: u64) -> u64 {
+/// Current implementation uses the Bostan-Mori algorithm.
+/// Use [`linear_nth_with`] to choose the algorithm explicitly.
+pub fn linear_nth(first_terms: &[u64], coeff: &[u64], n: u128, modulo: u64) -> u64 {
+    linear_nth_with(
+        first_terms,
+        coeff,
+        n,
+        modulo,
+        LinearNthAlgorithm::BostanMori,
+    )
+}
+
+/// Logarithmic time linear recurrence solver with a selectable algorithm.
+///
+/// The arguments and the result are the same as [`linear_nth`], except that
+/// the algorithm is specified by `algorithm`.
+pub fn linear_nth_with(
+    first_terms: &[u64],
+    coeff: &[u64],
+    n: u128,
+    modulo: u64,
+    algorithm: LinearNthAlgorithm,
+) -> u64 {
     let m = first_terms.len();
     assert!(m == coeff.len());
     assert!(m > 0);
     if modulo == 1 {
-        0
-    } else {
-        let mut p_base = vec![]; // The modulo base polynomial of Kitamasa
-        for x in coeff.iter().rev() {
-            p_base.push(modsub(0, *x, modulo));
+        return 0;
+    }
+    match algorithm {
+        LinearNthAlgorithm::Kitamasa => linear_nth_kitamasa(first_terms, coeff, n, modulo),
+        LinearNthAlgorithm::BostanMori => {
+            /* Q(x) = 1 - coeff[0] x - ... - coeff[m-1] x**m, P(x) = A(x) Q(x) mod x**m */
+            let mut q = Vec::with_capacity(m + 1);
+            q.push(1);
+            q.extend(coeff.iter().map(|&c| modsub(0, c, modulo)));
+            let mut p = polymul_u64(first_terms, &q, modulo);
+            p.truncate(m);
+            rational_nth(&p, &q, n, modulo).unwrap()
         }
-        p_base.push(1);
-        let mut p_pow2 = vec![0, 1];
-        let mut p_out = vec![1];
-        while n > 0 {
-            if (n & 1) != 0 {
-                p_out =
-                    polymod_u64(&polymul_u64(&p_pow2, &p_out, modulo), &p_base, modulo).unwrap();
-            }
-            p_pow2 = polymod_u64(&polymul_u64(&p_pow2, &p_pow2, modulo), &p_base, modulo).unwrap();
-            n >>= 1;
+    }
+}
+
+/// Computes `[x**n] p(x) / q(x)`, the coefficient of `x**n` in the power series `p(x) / q(x)`,
+/// using the Bostan-Mori algorithm. `p[i]` and `q[i]` should be the coefficients of `x**i`.
+///
+/// If `q[0]` is invertible modulo `modulo`, the result is wrapped in `Some`.
+/// Otherwise, the result is `None`.
+///
+/// The time complexity is `O(M(d) lg n)` where `d = max(p.len(), q.len())`
+/// and `M(d)` is the time complexity of multiplying two polynomials of length `d`.
+///
+/// The result is computed in modulo `modulo`.
+/// If `modulo` equals 0, it is treated as `2**64`.
+/// Note that `modulo` does not need to be a prime.
+pub fn rational_nth(p: &[u64], q: &[u64], mut n: u128, modulo: u64) -> Option<u64> {
+    if modulo == 1 {
+        return (!q.is_empty()).then_some(0);
+    }
+    /* q[0] is squared at each step, so it stays invertible; the final one is inverted at the end */
+    modinv(*q.first()?, modulo)?;
+    let (mut p, mut q) = (p.to_vec(), q.to_vec());
+    while n > 0 && !p.is_empty() {
+        /* p(x)/q(x) = p(x)q(-x) / q(x)q(-x), where the denominator is even */
+        let q_neg: Vec<u64> = q
+            .iter()
+            .enumerate()
+            .map(|(i, &c)| if i % 2 == 0 { c } else { modsub(0, c, modulo) })
+            .collect();
+        let u = polymul_u64(&p, &q_neg, modulo);
+        let v = polymul_u64(&q, &q_neg, modulo);
+        p = u.into_iter().skip((n & 1) as usize).step_by(2).collect();
+        q = v.into_iter().step_by(2).collect();
+        n >>= 1;
+    }
+    if n > 0 || p.is_empty() {
+        return Some(0);
+    }
+    Some(modmul(p[0], modinv(q[0], modulo)?, modulo))
+}
+
+fn linear_nth_kitamasa(first_terms: &[u64], coeff: &[u64], mut n: u128, modulo: u64) -> u64 {
+    let m = first_terms.len();
+    let mut p_base = vec![]; // The modulo base polynomial of Kitamasa
+    for x in coeff.iter().rev() {
+        p_base.push(modsub(0, *x, modulo));
+    }
+    p_base.push(1);
+    let mut p_pow2 = vec![0, 1];
+    let mut p_out = vec![1];
+    while n > 0 {
+        if (n & 1) != 0 {
+            p_out = polymod_u64(&polymul_u64(&p_pow2, &p_out, modulo), &p_base, modulo).unwrap();
         }
-        let mut ans = 0u64;
-        for i in 0..m {
-            if i >= p_out.len() {
-                break;
-            }
-            let term = modmul(first_terms[i], p_out[i], modulo);
-            ans = modadd(ans, term, modulo);
+        p_pow2 = polymod_u64(&polymul_u64(&p_pow2, &p_pow2, modulo), &p_base, modulo).unwrap();
+        n >>= 1;
+    }
+    let mut ans = 0u64;
+    for i in 0..m {
+        if i >= p_out.len() {
+            break;
         }
-        ans
+        let term = modmul(first_terms[i], p_out[i], modulo);
+        ans = modadd(ans, term, modulo);
     }
+    ans
 }
 
 #[cfg(test)]
@@ -61,4 +142,100 @@ mod test {
         assert_eq!(34, linear_nth(&first_terms, &coeff, 8, 1_000_000_007));
         assert_eq!(34, linear_nth(&first_terms, &coeff, 8, 0));
     }
+
+    #[test]
+    fn check_linear_nth_algorithms() {
+        use rand::{Rng, SeedableRng};
+        let mut rng = rand::rngs::SmallRng::seed_from_u64(6);
+        for modulo in [0, 2, 12, 998_244_353, 1_000_000_007, u64::MAX] {
+            for m in [1usize, 2, 5, 40, 100] {
+                let reduce = |x: u64| if modulo == 0 { x } else { x % modulo };
+                let first_terms: Vec<u64> = (0..m).map(|_| reduce(rng.random())).collect();
+                let coeff: Vec<u64> = (0..m).map(|_| reduce(rng.random())).collect();
+                /* naive computation of the first few terms */
+                let mut a = first_terms.clone();
+                for k in m..m + 60 {
+                    let mut v = 0;
+                    for i in 0..m {
+                        v = modadd(v, modmul(coeff[i], a[k - 1 - i], modulo), modulo);
+                    }
+                    a.push(v);
+                }
+                for n in (0..a.len()).chain([1_000_000_000_000, usize::MAX]) {
+                    let n = n as u128;
+                    let kitamasa = linear_nth_with(
+                        &first_terms,
+                        &coeff,
+                        n,
+                        modulo,
+                        LinearNthAlgorithm::Kitamasa,
+                    );
+                    let bostan_mori = linear_nth_with(
+                        &first_terms,
+                        &coeff,
+                        n,
+                        modulo,
+                        LinearNthAlgorithm::BostanMori,
+                    );
+                    assert_eq!(kitamasa, bostan_mori);
+                    if (n as usize) < a.len() {
+                        assert_eq!(a[n as usize], bostan_mori);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn check_rational_nth() {
+        /* 1 / (1 - x - x**2) generates the Fibonacci numbers F(1), F(2), ... */
+        let q = [1, u64::MAX, u64::MAX];
+        assert_eq!(Some(1), rational_nth(&[1], &q, 0, 0));
+        assert_eq!(Some(2880067194370816120), rational_nth(&[1], &q, 89, 0));
+        /* x / (1 - x)**2 = sum n x**n */
+        let modulo = 1_000_000_007;
+        let q = [1, modulo - 2, 1];
+        assert_eq!(
+            Some(123456789),
+            rational_nth(&[0, 1], &q, 123456789, modulo)
+        );
+        assert_eq!(Some(0), rational_nth(&[], &q, 5, modulo));
+        assert_eq!(None, rational_nth(&[1], &[2, 1], 5, 0));
+        assert_eq!(None, rational_nth(&[1], &[], 5, modulo));
+        /* 1 / (2 + x) */
+        let expected = [499122177, 249561088, 873463809, 62390272];
+        for (n, &c) in expected.iter().enumerate() {
+            assert_eq!(Some(c), rational_nth(&[1], &[2, 1], n as u128, 998_244_353));
+        }
+    }
+
+    #[test]
+    fn check_rational_nth_random() {
+        use rand::{Rng, SeedableRng};
+        let mut rng = rand::rngs::SmallRng::seed_from_u64(6);
+        for modulo in [998_244_353, 1_000_000_007, 1_000_000, 0] {
+            for _ in 0..30 {
+                let p: Vec<u64> = (0..rng.random_range(0..5)).map(|_| rng.random()).collect();
+                let mut q: Vec<u64> = (0..rng.random_range(1..5)).map(|_| rng.random()).collect();
+                while modinv(q[0], modulo).is_none() {
+                    q[0] = rng.random();
+                }
+                /* naive power series division */
+                let q0_inv = modinv(q[0], modulo).unwrap();
+                let mut a: Vec<u64> = vec![];
+                for i in 0..40 {
+                    let mut c = p
+                        .get(i)
+                        .map_or(0, |&x| if modulo == 0 { x } else { x % modulo });
+                    for j in 1..q.len().min(i + 1) {
+                        c = modsub(c, modmul(q[j], a[i - j], modulo), modulo);
+                    }
+                    a.push(modmul(c, q0_inv, modulo));
+                }
+                for (n, &c) in a.iter().enumerate() {
+                    assert_eq!(Some(c), rational_nth(&p, &q, n as u128, modulo));
+                }
+            }
+        }
+    }
 }
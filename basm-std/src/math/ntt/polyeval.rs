@@ -1,4 +1,5 @@
-use super::{polymul_ex_u64, polyops::polyneginv_u64};
+use super::{polyadd_u64, polyderiv_u64, polymul_ex_u64, polymul_u64, polyops::polyneginv_u64};
+use crate::math::{modadd, modinv, modmul, modsub};
 use alloc::{vec, vec::Vec};
 use core::cmp::min;
 
@@ -19,30 +20,20 @@ fn deg_sum(n: usize) -> usize {
     }
 }
 
-/// Performs multipoint evaluation of the input polynomial `poly`
-/// at points specified by `query_points`. `poly[i]` should be the coefficient of `x**i`.
-///
-/// The time complexity is `O((n + q) lg^2 (n + q))`,
-/// where `n` is degree of the input polynomial and `q` is the number of query points.
+/// Builds the subproduct tree of `points` in a heap-style layout.
 ///
-/// The result is computed in modulo `modulo`.
-/// If `modulo` equals 0, it is treated as `2**64`.
-/// Note that `modulo` does not need to be a prime.
-pub fn polyeval_u64(poly: &[u64], query_points: &[u64], modulo: u64) -> Vec<u64> {
-    assert!(!poly.is_empty());
-
-    let (d, n) = (poly.len() - 1, query_points.len());
-    if modulo == 1 || n == 0 {
-        return vec![0; n];
-    }
-
+/// Node `i` (for `1 <= i < 2 * n`) has children `2 * i` and `2 * i + 1`, and the leaf for `points[i]` is node `n + i`.
+/// The polynomial of node `i` is stored in `tree[pos[i]..pos[i - 1]]`; each leaf holds `points[i] - x`,
+/// and each internal node holds the product of its children.
+fn subproduct_tree(points: &[u64], modulo: u64) -> (Vec<u64>, Vec<usize>) {
+    let n = points.len();
     let tree1_len = deg_sum(n) + 2 * n - 1;
     let mut tree1 = vec![modulo.wrapping_sub(1); tree1_len];
     let mut tree1_pos = vec![0; 2 * n];
     let mut pos = 0;
     for i in (0..n).rev() {
         tree1_pos[n + i] = pos;
-        tree1[pos] = query_points[i];
+        tree1[pos] = points[i];
         pos += 2;
     }
     for i in (1..n).rev() {
@@ -62,10 +53,34 @@ pub fn polyeval_u64(poly: &[u64], query_points: &[u64], modulo: u64) -> Vec<u64>
         pos += l;
     }
     tree1_pos[0] = pos;
+    (tree1, tree1_pos)
+}
+
+/// Performs multipoint evaluation of the input polynomial `poly`
+/// at points specified by `query_points`. `poly[i]` should be the coefficient of `x**i`.
+///
+/// The time complexity is `O((n + q) lg^2 (n + q))`,
+/// where `n` is degree of the input polynomial and `q` is the number of query points.
+///
+/// The result is computed in modulo `modulo`.
+/// If `modulo` equals 0, it is treated as `2**64`.
+/// Note that `modulo` does not need to be a prime.
+pub fn polyeval_u64(poly: &[u64], query_points: &[u64], modulo: u64) -> Vec<u64> {
+    assert!(!poly.is_empty());
+
+    let n = query_points.len();
+    if modulo == 1 || n == 0 {
+        return vec![0; n];
+    }
+    let (tree1, tree1_pos) = subproduct_tree(query_points, modulo);
+    polyeval_with_tree(poly, &tree1, &tree1_pos, modulo)
+}
 
-    let root = &mut tree1[tree1_pos[1]..];
+fn polyeval_with_tree(poly: &[u64], tree1: &[u64], tree1_pos: &[usize], modulo: u64) -> Vec<u64> {
+    let (d, n) = (poly.len() - 1, tree1_pos.len() / 2);
+    let mut root = tree1[tree1_pos[1]..tree1_pos[0]].to_vec();
     root.reverse();
-    let mut inv = polyneginv_u64(root, d + 1, modulo).unwrap();
+    let mut inv = polyneginv_u64(&root, d + 1, modulo).unwrap();
     inv.reverse();
 
     let (mut tree2_0, mut tree2_1) = (vec![0; n], vec![0; n]);
@@ -106,3 +121,151 @@ pub fn polyeval_u64(poly: &[u64], query_points: &[u64], modulo: u64) -> Vec<u64>
     out.extend_from_slice(&tree2_1[..n - d]);
     out
 }
+
+/// Computes the unique polynomial `p` of degree less than `n` such that `p(xs[i]) = ys[i]` for all `i`,
+/// where `n = xs.len() = ys.len()`. The `i`-th element of the result is the coefficient of `x**i`.
+///
+/// If the differences `xs[i] - xs[j]` (for `i != j`) are all invertible modulo `modulo`,
+/// the result is a Vec of length `n` wrapped in `Some`. Otherwise, the result is `None`.
+///
+/// The time complexity is `O(n lg^2 n)`. The subproduct tree is shared with [`polyeval_u64`].
+///
+/// The result is computed in modulo `modulo`.
+/// If `modulo` equals 0, it is treated as `2**64`.
+/// Note that `modulo` does not need to be a prime.
+pub fn polyinterp_u64(xs: &[u64], ys: &[u64], modulo: u64) -> Option<Vec<u64>> {
+    assert_eq!(xs.len(), ys.len());
+    let n = xs.len();
+    if modulo == 1 || n == 0 {
+        return Some(vec![0; n]);
+    }
+    let (tree1, tree1_pos) = subproduct_tree(xs, modulo);
+
+    /* With M(x) = prod (xs[j] - x), we have p(x) = sum w[i] * M(x) / (xs[i] - x)
+    where w[i] = ys[i] / prod_{j != i} (xs[j] - xs[i]) = -ys[i] / M'(xs[i]). */
+    let root = &tree1[tree1_pos[1]..tree1_pos[0]];
+    let denom = polyeval_with_tree(&polyderiv_u64(root, modulo), &tree1, &tree1_pos, modulo);
+    let mut acc = vec![Vec::new(); 2 * n];
+    for i in 0..n {
+        let w = modmul(ys[i], modinv(denom[i], modulo)?, modulo);
+        acc[n + i] = vec![modsub(0, w, modulo)];
+    }
+
+    /* Combine bottom-up: acc[i] = acc[2i] * M[2i+1] + acc[2i+1] * M[2i] */
+    for i in (1..n).rev() {
+        let (l, r) = (i << 1, (i << 1) | 1);
+        let (acc_l, acc_r) = (core::mem::take(&mut acc[l]), core::mem::take(&mut acc[r]));
+        let m_l = &tree1[tree1_pos[l]..tree1_pos[l - 1]];
+        let m_r = &tree1[tree1_pos[r]..tree1_pos[r - 1]];
+        acc[i] = polyadd_u64(
+            &polymul_u64(&acc_l, m_r, modulo),
+            &polymul_u64(&acc_r, m_l, modulo),
+            modulo,
+        );
+    }
+    let mut out = core::mem::take(&mut acc[1]);
+    out.resize(n, 0);
+    Some(out)
+}
+
+/// Computes `p(x)` in `O(n)` time, where `p` is the unique polynomial of degree less than `n`
+/// such that `p(i) = ys[i]` for `0 <= i < n = ys.len()`.
+///
+/// If `1, 2, ..., n - 1` are all invertible modulo `modulo`, the result is wrapped in `Some`.
+/// Otherwise, the result is `None`. If `ys` is empty, the result is `Some(0)`.
+///
+/// The result is computed in modulo `modulo`.
+/// If `modulo` equals 0, it is treated as `2**64`.
+/// Note that `modulo` does not need to be a prime.
+pub fn lagrange_interp_u64(ys: &[u64], x: u64, modulo: u64) -> Option<u64> {
+    let n = ys.len();
+    if n == 0 || modulo == 1 {
+        return Some(0);
+    }
+    if x < n as u64 {
+        return Some(if modulo == 0 {
+            ys[x as usize]
+        } else {
+            ys[x as usize] % modulo
+        });
+    }
+    let x = if modulo == 0 { x } else { x % modulo };
+    let reduce = |v: u64| if modulo == 0 { v } else { v % modulo };
+
+    /* ifact[i] = 1 / i! */
+    let mut fact = 1u64;
+    for i in 1..n {
+        fact = modmul(fact, reduce(i as u64), modulo);
+    }
+    let mut ifact = vec![0u64; n];
+    ifact[n - 1] = modinv(fact, modulo)?;
+    for i in (1..n).rev() {
+        ifact[i - 1] = modmul(ifact[i], reduce(i as u64), modulo);
+    }
+
+    /* suffix[i] = prod_{j >= i} (x - j) */
+    let mut suffix = vec![1u64; n + 1];
+    for i in (0..n).rev() {
+        suffix[i] = modmul(suffix[i + 1], modsub(x, reduce(i as u64), modulo), modulo);
+    }
+    let (mut prefix, mut out) = (1u64, 0u64);
+    for i in 0..n {
+        /* ys[i] * prod_{j != i} (x - j) / (i! * (n-1-i)! * (-1)**(n-1-i)) */
+        let mut term = modmul(reduce(ys[i]), modmul(prefix, suffix[i + 1], modulo), modulo);
+        term = modmul(term, modmul(ifact[i], ifact[n - 1 - i], modulo), modulo);
+        out = if (n - 1 - i).is_multiple_of(2) {
+            modadd(out, term, modulo)
+        } else {
+            modsub(out, term, modulo)
+        };
+        prefix = modmul(prefix, modsub(x, reduce(i as u64), modulo), modulo);
+    }
+    Some(out)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn check_polyinterp_u64() {
+        for modulo in [998_244_353, 1_000_000_007, 0] {
+            for n in [1usize, 2, 3, 7, 100] {
+                let poly: Vec<u64> = (0..n as u64).map(|i| i * i * 31 + 5).collect();
+                let xs: Vec<u64> = (0..n as u64).map(|i| 2 * i * i + 1).collect();
+                let ys = polyeval_u64(&poly, &xs, modulo);
+                let expected: Vec<u64> = if modulo == 0 {
+                    poly.clone()
+                } else {
+                    poly.iter().map(|&c| c % modulo).collect()
+                };
+                if modulo == 0 && n >= 2 {
+                    // xs[i] - xs[j] are even and hence not invertible
+                    assert_eq!(None, polyinterp_u64(&xs, &ys, modulo));
+                } else {
+                    assert_eq!(Some(expected), polyinterp_u64(&xs, &ys, modulo));
+                }
+            }
+        }
+        assert_eq!(None, polyinterp_u64(&[1, 1], &[2, 3], 998_244_353));
+    }
+
+    #[test]
+    fn check_lagrange_interp_u64() {
+        let modulo = 1_000_000_007;
+        let poly = [3, 1, 4, 1, 5, 9, 2, 6];
+        let xs: Vec<u64> = (0..poly.len() as u64).collect();
+        let ys = polyeval_u64(&poly, &xs, modulo);
+        for x in [0u64, 5, 8, 100, 1_000_000_010, u64::MAX] {
+            assert_eq!(
+                Some(polyeval_u64(&poly, &[x % modulo], modulo)[0]),
+                lagrange_interp_u64(&ys, x, modulo)
+            );
+        }
+        /* sum of squares 0**2 + 1**2 + ... + (x-1)**2 */
+        let ys = [0, 0, 1, 5];
+        assert_eq!(Some(285), lagrange_interp_u64(&ys, 10, modulo));
+        assert_eq!(None, lagrange_interp_u64(&ys, 10, 0));
+        assert_eq!(None, lagrange_interp_u64(&[0, 1, 2, 3], 10, 2));
+    }
+}
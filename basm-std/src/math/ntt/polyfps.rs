@@ -0,0 +1,386 @@
+use super::{polyinv_u64, polymul_u64};
+use crate::math::{modadd, modinv, modmul, modpow, modsub, sqrt_mod};
+use alloc::{vec, vec::Vec};
+use core::cmp::min;
+
+/// Computes `[0, 1/1, 1/2, ..., 1/n]` by batch inversion.
+/// Returns `None` if any of `1..=n` is not invertible modulo `modulo`.
+fn inverses_upto(n: usize, modulo: u64) -> Option<Vec<u64>> {
+    let mut prefix = vec![1u64; n + 1];
+    for i in 1..=n {
+        prefix[i] = modmul(prefix[i - 1], i as u64, modulo);
+    }
+    let mut acc = modinv(prefix[n], modulo)?;
+    let mut out = vec![0u64; n + 1];
+    for i in (1..=n).rev() {
+        out[i] = modmul(acc, prefix[i - 1], modulo);
+        acc = modmul(acc, i as u64, modulo);
+    }
+    Some(out)
+}
+
+/// Computes the derivative of the input polynomial `poly`.
+/// `poly[i]` should be the coefficient of `x**i`.
+///
+/// The resulting Vec has length `poly.len() - 1` (or 0 if `poly` is empty).
+///
+/// The result is computed in modulo `modulo`.
+/// If `modulo` equals 0, it is treated as `2**64`.
+/// Note that `modulo` does not need to be a prime.
+pub fn polyderiv_u64(poly: &[u64], modulo: u64) -> Vec<u64> {
+    (1..poly.len())
+        .map(|i| modmul(poly[i], i as u64, modulo))
+        .collect()
+}
+
+/// Computes the integral of the input polynomial `poly` with zero constant term.
+/// `poly[i]` should be the coefficient of `x**i`.
+///
+/// If `1, 2, ..., poly.len()` are all invertible modulo `modulo`,
+/// the result is a Vec of length `poly.len() + 1` wrapped in `Some`.
+/// Otherwise, the result is `None`.
+///
+/// The result is computed in modulo `modulo`.
+/// If `modulo` equals 0, it is treated as `2**64`.
+/// Note that `modulo` does not need to be a prime.
+pub fn polyinteg_u64(poly: &[u64], modulo: u64) -> Option<Vec<u64>> {
+    let inv = inverses_upto(poly.len(), modulo)?;
+    let mut out = Vec::with_capacity(poly.len() + 1);
+    out.push(0);
+    for i in 0..poly.len() {
+        out.push(modmul(poly[i], inv[i + 1], modulo));
+    }
+    Some(out)
+}
+
+/// Computes the logarithm of the input polynomial `poly`, modulo `x**n`.
+/// `poly[i]` should be the coefficient of `x**i`.
+///
+/// The constant term `poly[0]` must be 1, and `1, 2, ..., n - 1` must be invertible modulo `modulo`.
+/// If these conditions are met, the result is a Vec of length `n` wrapped in `Some`.
+/// Otherwise, the result is `None`.
+///
+/// The time complexity is `O(n lg n)`.
+///
+/// The result is computed in modulo `modulo`.
+/// If `modulo` equals 0, it is treated as `2**64`.
+/// Note that `modulo` does not need to be a prime.
+pub fn polylog_u64(poly: &[u64], n: usize, modulo: u64) -> Option<Vec<u64>> {
+    if modulo == 1 {
+        return Some(vec![0; n]);
+    }
+    let reduce = |c: u64| if modulo == 0 { c } else { c % modulo };
+    if poly.first().map(|&c| reduce(c)) != Some(1) {
+        return None;
+    }
+    if n <= 1 {
+        return Some(vec![0; n]);
+    }
+    let f = &poly[..min(poly.len(), n)];
+    let inv = polyinv_u64(f, n - 1, modulo)?;
+    let mut q = polymul_u64(&polyderiv_u64(f, modulo), &inv, modulo);
+    q.resize(n - 1, 0);
+    polyinteg_u64(&q, modulo)
+}
+
+/// Computes the exponential of the input polynomial `poly`, modulo `x**n`.
+/// `poly[i]` should be the coefficient of `x**i`.
+///
+/// The constant term `poly[0]` must be 0, and `1, 2, ..., n - 1` must be invertible modulo `modulo`.
+/// If these conditions are met, the result is a Vec of length `n` wrapped in `Some`.
+/// Otherwise, the result is `None`.
+///
+/// The time complexity is `O(n lg n)`.
+///
+/// The result is computed in modulo `modulo`.
+/// If `modulo` equals 0, it is treated as `2**64`.
+/// Note that `modulo` does not need to be a prime.
+pub fn polyexp_u64(poly: &[u64], n: usize, modulo: u64) -> Option<Vec<u64>> {
+    if n == 0 || modulo == 1 {
+        return Some(vec![0; n]);
+    }
+    let reduce = |c: u64| if modulo == 0 { c } else { c % modulo };
+    if poly.first().is_some_and(|&c| reduce(c) != 0) {
+        return None;
+    }
+    inverses_upto(n - 1, modulo)?;
+    /* Newton's iteration: g <- g * (1 - log(g) + f) */
+    let mut g = vec![1];
+    let mut len = 1;
+    while len < n {
+        len = min(2 * len, n);
+        let mut h = polylog_u64(&g, len, modulo)?;
+        for (i, x) in h.iter_mut().enumerate() {
+            *x = modsub(*poly.get(i).unwrap_or(&0), *x, modulo);
+        }
+        h[0] = modadd(h[0], 1, modulo);
+        g = polymul_u64(&g, &h, modulo);
+        g.truncate(len);
+    }
+    g.resize(n, 0);
+    Some(g)
+}
+
+/// Computes a square root of the input polynomial `poly`, modulo `x**n`.
+/// `poly[i]` should be the coefficient of `x**i`.
+///
+/// Leading zero coefficients are allowed: if the lowest nonzero term is `c * x**k`,
+/// then `k` must be even and `c` must be a quadratic residue.
+/// Among the square roots, the one whose lowest nonzero coefficient is
+/// the smaller of the two square roots of `c` is returned.
+///
+/// If a square root exists, the result is a Vec of length `n` wrapped in `Some`.
+/// Otherwise, the result is `None`.
+///
+/// The time complexity is `O(n lg n)`.
+///
+/// The result is computed in modulo `modulo`, which must be an odd prime.
+pub fn polysqrt_u64(poly: &[u64], n: usize, modulo: u64) -> Option<Vec<u64>> {
+    assert!(modulo % 2 == 1, "modulo must be an odd prime");
+    let Some(k) = poly.iter().take(n).position(|&c| c % modulo != 0) else {
+        return Some(vec![0; n]);
+    };
+    if k % 2 != 0 {
+        return None;
+    }
+    let (shift, n1) = (k / 2, n - k / 2);
+    let f = &poly[k..min(poly.len(), k + n1)];
+    let inv2 = modulo.div_ceil(2);
+    /* Newton's iteration: g <- (g + f / g) / 2 */
+    let mut g = vec![sqrt_mod(f[0], modulo)?];
+    let mut len = 1;
+    while len < n1 {
+        len = min(2 * len, n1);
+        let inv = polyinv_u64(&g, len, modulo)?;
+        let q = polymul_u64(&f[..min(f.len(), len)], &inv, modulo);
+        g.resize(len, 0);
+        for i in 0..len {
+            g[i] = modmul(modadd(g[i], q[i], modulo), inv2, modulo);
+        }
+    }
+    let mut out = vec![0; shift];
+    out.extend_from_slice(&g[..n1]);
+    Some(out)
+}
+
+/// Computes `poly ** exponent`, modulo `x**n`.
+/// `poly[i]` should be the coefficient of `x**i`.
+///
+/// Leading zero coefficients are allowed. If the lowest nonzero term is `c * x**k`,
+/// then `c` must be invertible modulo `modulo` unless the result is trivially zero.
+/// Also, `1, 2, ..., n - 1` must be invertible modulo `modulo`.
+/// If these conditions are met, the result is a Vec of length `n` wrapped in `Some`.
+/// Otherwise, the result is `None`.
+///
+/// The time complexity is `O(n lg n)`.
+///
+/// The result is computed in modulo `modulo`.
+/// If `modulo` equals 0, it is treated as `2**64`.
+/// Note that `modulo` does not need to be a prime.
+pub fn polypow_u64(poly: &[u64], exponent: u64, n: usize, modulo: u64) -> Option<Vec<u64>> {
+    let mut out = vec![0; n];
+    if n == 0 || modulo == 1 {
+        return Some(out);
+    }
+    if exponent == 0 {
+        out[0] = 1;
+        return Some(out);
+    }
+    let Some(k) = poly
+        .iter()
+        .take(n)
+        .position(|&c| if modulo == 0 { c != 0 } else { c % modulo != 0 })
+    else {
+        return Some(out);
+    };
+    if (k as u128) * (exponent as u128) >= n as u128 {
+        return Some(out);
+    }
+    let shift = k * exponent as usize;
+    let n1 = n - shift;
+    /* poly = c * x**k * (1 + h) */
+    let c = poly[k];
+    let c_inv = modinv(c, modulo)?;
+    let f: Vec<u64> = poly[k..min(poly.len(), k + n1)]
+        .iter()
+        .map(|&x| modmul(x, c_inv, modulo))
+        .collect();
+    let mut l = polylog_u64(&f, n1, modulo)?;
+    let e = if modulo == 0 {
+        exponent
+    } else {
+        exponent % modulo
+    };
+    l.iter_mut().for_each(|x| *x = modmul(*x, e, modulo));
+    let g = polyexp_u64(&l, n1, modulo)?;
+    let c_pow = modpow(c, exponent, modulo)?;
+    for i in 0..n1 {
+        out[shift + i] = modmul(g[i], c_pow, modulo);
+    }
+    Some(out)
+}
+
+/// Computes the Taylor shift `poly(x + c)` of the input polynomial `poly`.
+/// `poly[i]` should be the coefficient of `x**i`.
+///
+/// `1, 2, ..., poly.len() - 1` must be invertible modulo `modulo`.
+/// If this condition is met, the result is a Vec of length `poly.len()` wrapped in `Some`.
+/// Otherwise, the result is `None`.
+///
+/// The time complexity is `O(n lg n)` where `n = poly.len()`.
+///
+/// The result is computed in modulo `modulo`.
+/// If `modulo` equals 0, it is treated as `2**64`.
+/// Note that `modulo` does not need to be a prime.
+pub fn polytaylorshift_u64(poly: &[u64], c: u64, modulo: u64) -> Option<Vec<u64>> {
+    let n = poly.len();
+    if n == 0 || modulo == 1 {
+        return Some(vec![0; n]);
+    }
+    let inv = inverses_upto(n - 1, modulo)?;
+    let (mut fact, mut ifact) = (vec![1; n], vec![1; n]);
+    for i in 1..n {
+        fact[i] = modmul(fact[i - 1], i as u64, modulo);
+        ifact[i] = modmul(ifact[i - 1], inv[i], modulo);
+    }
+    /* out[i] * i! = sum_{j >= i} (poly[j] * j!) * (c**(j-i) / (j-i)!) */
+    let a: Vec<u64> = (0..n)
+        .map(|j| modmul(poly[n - 1 - j], fact[n - 1 - j], modulo))
+        .collect();
+    let mut b = Vec::with_capacity(n);
+    let mut cp = 1;
+    for &x in ifact.iter() {
+        b.push(modmul(cp, x, modulo));
+        cp = modmul(cp, c, modulo);
+    }
+    let ab = polymul_u64(&a, &b, modulo);
+    Some(
+        (0..n)
+            .map(|i| modmul(ab[n - 1 - i], ifact[i], modulo))
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn naive_polymul(x: &[u64], y: &[u64], n: usize, modulo: u64) -> Vec<u64> {
+        let mut out = vec![0; n];
+        for (i, &a) in x.iter().enumerate() {
+            for (j, &b) in y.iter().enumerate() {
+                if i + j < n {
+                    out[i + j] = modadd(out[i + j], modmul(a, b, modulo), modulo);
+                }
+            }
+        }
+        out
+    }
+
+    #[test]
+    fn check_polyderiv_polyinteg() {
+        assert_eq!(vec![2, 6, 12], polyderiv_u64(&[1, 2, 3, 4], 0));
+        assert_eq!(vec![0u64; 0], polyderiv_u64(&[5], 0));
+        assert_eq!(
+            Some(vec![0, 1, 2, 3, 4]),
+            polyinteg_u64(&[1, 4, 9, 16], 1_000_000_007)
+        );
+        assert_eq!(None, polyinteg_u64(&[1, 4, 9, 16], 0));
+        assert_eq!(Some(vec![0, 5]), polyinteg_u64(&[5], 0));
+    }
+
+    #[test]
+    fn check_polylog_polyexp() {
+        for modulo in [998_244_353, 1_000_000_007, 18_446_744_073_709_551_557] {
+            let f: Vec<u64> = (0..300u64)
+                .map(|i| if i == 0 { 0 } else { i * i + 7 })
+                .collect();
+            let g = polyexp_u64(&f, 300, modulo).unwrap();
+            assert_eq!(1, g[0]);
+            assert_eq!(f, polylog_u64(&g, 300, modulo).unwrap());
+            /* unreduced constant terms are accepted */
+            let mut g1 = g.clone();
+            g1[0] = modulo + 1;
+            assert_eq!(f, polylog_u64(&g1, 300, modulo).unwrap());
+            let mut f1 = f.clone();
+            f1[0] = modulo;
+            assert_eq!(g, polyexp_u64(&f1, 300, modulo).unwrap());
+            /* exp(x) = sum x**i / i! */
+            let e = polyexp_u64(&[0, 1], 6, modulo).unwrap();
+            let inv = inverses_upto(5, modulo).unwrap();
+            let mut expected = vec![1u64; 6];
+            for i in 1..6 {
+                expected[i] = modmul(expected[i - 1], inv[i], modulo);
+            }
+            assert_eq!(expected, e);
+        }
+        assert_eq!(None, polylog_u64(&[2, 1], 4, 998_244_353));
+        assert_eq!(None, polyexp_u64(&[1, 1], 4, 998_244_353));
+        assert_eq!(None, polyexp_u64(&[0, 1], 4, 0));
+    }
+
+    #[test]
+    fn check_polysqrt() {
+        let modulo = 998_244_353;
+        let f: Vec<u64> = (0..200u64).map(|i| (i * 31 + 4) % modulo).collect();
+        let g = polysqrt_u64(&f, 200, modulo).unwrap();
+        assert_eq!(f, naive_polymul(&g, &g, 200, modulo));
+        let h = [0, 0, 0, 0, 9, 1, 2];
+        let g = polysqrt_u64(&h, 7, modulo).unwrap();
+        assert_eq!(&[0, 0, 3], &g[..3]);
+        assert_eq!(h.to_vec(), naive_polymul(&g, &g, 7, modulo));
+        assert_eq!(None, polysqrt_u64(&[0, 1], 4, modulo));
+        assert_eq!(None, polysqrt_u64(&[3, 1], 4, modulo)); // 3 is a nonresidue
+        assert_eq!(Some(vec![0, 0, 0]), polysqrt_u64(&[0], 3, modulo));
+    }
+
+    #[test]
+    fn check_polypow() {
+        for modulo in [998_244_353, 1_000_000_007] {
+            let f = [0, 0, 3, 1, 4, 1, 5];
+            let mut expected = vec![1];
+            for e in 0..6u64 {
+                expected = naive_polymul(&expected, &f, 30, modulo);
+                assert_eq!(expected, polypow_u64(&f, e + 1, 30, modulo).unwrap());
+            }
+            assert_eq!(vec![1, 0, 0], polypow_u64(&f, 0, 3, modulo).unwrap());
+            assert_eq!(vec![0; 5], polypow_u64(&f, 3, 5, modulo).unwrap());
+            assert_eq!(vec![0; 5], polypow_u64(&f, u64::MAX, 5, modulo).unwrap());
+            /* unreduced zero coefficients are skipped */
+            assert_eq!(
+                vec![0, 0, 0, 1],
+                polypow_u64(&[modulo, 1], 3, 4, modulo).unwrap()
+            );
+        }
+        let f = [1, 1];
+        let g = polypow_u64(&f, 1_000_000_000_000, 4, 998_244_353).unwrap();
+        let m = 998_244_353;
+        let n = 1_000_000_000_000u64 % m;
+        let binom2 = modmul(modmul(n, n - 1, m), modinv(2, m).unwrap(), m);
+        let binom3 = modmul(modmul(binom2, n - 2, m), modinv(3, m).unwrap(), m);
+        assert_eq!(vec![1, n, binom2, binom3], g);
+    }
+
+    #[test]
+    fn check_polytaylorshift() {
+        assert_eq!(None, polytaylorshift_u64(&[0, 0, 0, 1], 2, 0));
+        for modulo in [998_244_353, 1_000_000_007] {
+            /* (x + 2)**3 = x**3 + 6x**2 + 12x + 8 */
+            assert_eq!(
+                Some(vec![8, 12, 6, 1]),
+                polytaylorshift_u64(&[0, 0, 0, 1], 2, modulo)
+            );
+            let f: Vec<u64> = (0..100u64).map(|i| i * i % 17).collect();
+            let c = 12345;
+            let g = polytaylorshift_u64(&f, c, modulo).unwrap();
+            for x in [0u64, 1, 2, 777] {
+                let eval = |p: &[u64], x: u64| {
+                    p.iter()
+                        .rev()
+                        .fold(0, |acc, &a| modadd(modmul(acc, x, modulo), a, modulo))
+                };
+                assert_eq!(eval(&f, modadd(x, c, modulo)), eval(&g, x));
+            }
+        }
+    }
+}
@@ -0,0 +1,366 @@
+use super::LinearSieve;
+use super::modint_dynamic::FastModOps;
+use alloc::{vec, vec::Vec};
+
+/// The distinct values of `n / i` for `1 <= i <= n`, in decreasing order.
+struct Quotients {
+    n: u64,
+    sq: u64,
+    vals: Vec<u64>,
+}
+
+impl Quotients {
+    fn new(n: u64) -> Self {
+        let sq = n.isqrt();
+        let mut vals = Vec::with_capacity(2 * sq as usize + 1);
+        let mut i = 1;
+        while i <= n {
+            let v = n / i;
+            vals.push(v);
+            i = n / v + 1;
+        }
+        Self { n, sq, vals }
+    }
+    /// Returns the index of `v`, which must be of the form `n / i`.
+    fn idx(&self, v: u64) -> usize {
+        if v <= self.sq {
+            self.vals.len() - v as usize
+        } else {
+            (self.n / v - 1) as usize
+        }
+    }
+    fn primes(&self) -> Vec<u64> {
+        LinearSieve::new()
+            .primes_upto(self.sq as usize)
+            .iter()
+            .map(|&p| p as u64)
+            .collect()
+    }
+}
+
+/// Lucy_Hedgehog's sieve: `g[i]` starts as the sum of `h(k)` over `2 <= k <= vals[i]` for a completely
+/// multiplicative `h`, and ends as the sum of `h(p)` over primes `p <= vals[i]`.
+///
+/// Each prime `p` removes the numbers whose smallest prime factor is `p` by
+/// `g(v) <- update(g(v), g(v / p), g(p - 1), p)`, which should compute `g(v) - h(p) * (g(v / p) - g(p - 1))`.
+fn lucy<T: Copy>(q: &Quotients, primes: &[u64], g: &mut [T], update: impl Fn(T, T, T, u64) -> T) {
+    for &p in primes {
+        let gp = g[q.idx(p - 1)];
+        for i in 0..q.vals.len() {
+            let v = q.vals[i];
+            if v < p * p {
+                break;
+            }
+            g[i] = update(g[i], g[q.idx(v / p)], gp, p);
+        }
+    }
+}
+
+/// Computes the number of primes less than or equal to `n` in `O(n**(3/4))` time
+/// and `O(sqrt(n))` space, by Lucy_Hedgehog's algorithm.
+///
+/// Example:
+/// ```
+/// use basm_std::math::prime_count;
+/// assert_eq!(25, prime_count(100));
+/// assert_eq!(5_761_455, prime_count(100_000_000));
+/// ```
+pub fn prime_count(n: u64) -> u64 {
+    if n < 2 {
+        return 0;
+    }
+    let q = Quotients::new(n);
+    let mut g: Vec<u64> = q.vals.iter().map(|&v| v - 1).collect();
+    lucy(&q, &q.primes(), &mut g, |gv, gvp, gp, _| gv - (gvp - gp));
+    g[0]
+}
+
+/// Computes the sum of primes less than or equal to `n` in `O(n**(3/4))` time
+/// and `O(sqrt(n))` space, by Lucy_Hedgehog's algorithm.
+///
+/// The result is exact as long as `n` is at most about `10**18`.
+///
+/// Example:
+/// ```
+/// use basm_std::math::prime_sum;
+/// assert_eq!(1060, prime_sum(100));
+/// assert_eq!(37_550_402_023, prime_sum(1_000_000));
+/// ```
+pub fn prime_sum(n: u64) -> u128 {
+    if n < 2 {
+        return 0;
+    }
+    let q = Quotients::new(n);
+    let mut g: Vec<u128> = q
+        .vals
+        .iter()
+        .map(|&v| v as u128 * (v as u128 + 1) / 2 - 1)
+        .collect();
+    lucy(&q, &q.primes(), &mut g, |gv, gvp, gp, p| {
+        gv - p as u128 * (gvp - gp)
+    });
+    g[0]
+}
+
+/// Computes `1**k + 2**k + ... + v**k` for `k <= 3`, in modulo `ops.modulo()`.
+fn power_sum(v: u64, k: usize, ops: &FastModOps) -> u64 {
+    let m = if ops.modulo() == 0 {
+        1 << 64
+    } else {
+        ops.modulo() as u128
+    };
+    let reduce = |x: u128| (x % m) as u64;
+    let v = v as u128;
+    let t = v * (v + 1) / 2;
+    match k {
+        0 => reduce(v),
+        1 => reduce(t),
+        2 => {
+            let w = 2 * v + 1;
+            if t.is_multiple_of(3) {
+                ops.mul(reduce(t / 3), reduce(w))
+            } else {
+                ops.mul(reduce(t), reduce(w / 3))
+            }
+        }
+        3 => ops.mul(reduce(t), reduce(t)),
+        _ => panic!("power sums are supported up to the third power"),
+    }
+}
+
+/// Computes `f(1) + f(2) + ... + f(n)` for a multiplicative function `f`, by the Min_25 sieve
+/// in roughly `O(n**(3/4) / lg n)` time and `O(sqrt(n))` space.
+///
+/// `f` is described by two parts:
+/// - On primes, `f(p) = prime_poly[0] + prime_poly[1] * p + prime_poly[2] * p**2 + ...`.
+///   At most four coefficients (i.e., up to `p**3`) are supported.
+/// - On prime powers, `f(p**e) = f_pe(p, e)`. This must agree with `prime_poly` for `e = 1`.
+///
+/// The small primes (up to `sqrt(n)`) are obtained from [`LinearSieve`].
+///
+/// The result is computed in modulo `modulo`.
+/// If `modulo` equals 0, it is treated as `2**64`.
+/// Note that `modulo` does not need to be a prime.
+///
+/// Example:
+/// ```
+/// use basm_std::math::multiplicative_sum;
+/// // the number of divisors: f(p) = 2, f(p**e) = e + 1
+/// assert_eq!(27, multiplicative_sum(10, &[2], |_, e| e as u64 + 1, 0));
+/// ```
+pub fn multiplicative_sum(
+    n: u64,
+    prime_poly: &[u64],
+    f_pe: impl Fn(u64, u32) -> u64,
+    modulo: u64,
+) -> u64 {
+    assert!(prime_poly.len() <= 4);
+    if n == 0 || modulo == 1 {
+        return 0;
+    }
+    let ops = FastModOps::new(modulo);
+    let q = Quotients::new(n);
+    let primes = q.primes();
+
+    /* G(v) = sum of f(p) over primes p <= v */
+    let mut big_g = vec![0; q.vals.len()];
+    for (k, &c) in prime_poly.iter().enumerate() {
+        let c = ops.canonicalize(c);
+        if c == 0 {
+            continue;
+        }
+        let one = ops.canonicalize(1);
+        let mut g: Vec<u64> = q
+            .vals
+            .iter()
+            .map(|&v| ops.sub(power_sum(v, k, &ops), one))
+            .collect();
+        lucy(&q, &primes, &mut g, |gv, gvp, gp, p| {
+            let pk = (0..k).fold(one, |acc, _| ops.mul(acc, ops.canonicalize(p)));
+            ops.fnmadd(pk, ops.sub(gvp, gp), gv)
+        });
+        for (x, y) in big_g.iter_mut().zip(g) {
+            *x = ops.fmadd(c, y, *x);
+        }
+    }
+    let mut prefix = vec![0; primes.len() + 1];
+    for (i, &p) in primes.iter().enumerate() {
+        prefix[i + 1] = ops.add(prefix[i], ops.canonicalize(f_pe(p, 1)));
+    }
+
+    struct Ctx<'a, F: Fn(u64, u32) -> u64> {
+        q: Quotients,
+        primes: Vec<u64>,
+        big_g: Vec<u64>,
+        prefix: Vec<u64>,
+        f_pe: F,
+        ops: &'a FastModOps,
+    }
+    /* sum of f(i) over 2 <= i <= x whose smallest prime factor is at least primes[j] */
+    fn rec<F: Fn(u64, u32) -> u64>(ctx: &Ctx<F>, x: u64, j: usize) -> u64 {
+        let ops = ctx.ops;
+        if x < 2 || (j < ctx.primes.len() && ctx.primes[j] > x) {
+            return 0;
+        }
+        let mut out = ops.sub(ctx.big_g[ctx.q.idx(x)], ctx.prefix[j]);
+        for k in j..ctx.primes.len() {
+            let p = ctx.primes[k];
+            if p * p > x {
+                break;
+            }
+            let (mut pe, mut e) = (p, 1);
+            let mut fe = ops.canonicalize((ctx.f_pe)(p, 1));
+            while pe * p <= x {
+                let fe1 = ops.canonicalize((ctx.f_pe)(p, e + 1));
+                let y = x / pe;
+                if ctx.primes.get(k + 1).is_none_or(|&q| q <= y) {
+                    out = ops.fmadd(fe, rec(ctx, y, k + 1), out);
+                }
+                out = ops.add(out, fe1);
+                (pe, e, fe) = (pe * p, e + 1, fe1);
+            }
+        }
+        out
+    }
+    let ctx = Ctx {
+        q,
+        primes,
+        big_g,
+        prefix,
+        f_pe,
+        ops: &ops,
+    };
+    ops.add(rec(&ctx, n, 0), ops.canonicalize(1))
+}
+
+/// Computes `phi(1) + phi(2) + ... + phi(n)`, where `phi` is Euler's totient function.
+/// See [`multiplicative_sum`] for the time complexity.
+///
+/// The result is computed in modulo `modulo`.
+/// If `modulo` equals 0, it is treated as `2**64`.
+/// Note that `modulo` does not need to be a prime.
+///
+/// Example:
+/// ```
+/// use basm_std::math::totient_sum;
+/// assert_eq!(32, totient_sum(10, 0));
+/// assert_eq!(303_963_551_173_008_414, totient_sum(1_000_000_000, 0));
+/// ```
+pub fn totient_sum(n: u64, modulo: u64) -> u64 {
+    multiplicative_sum(
+        n,
+        &[modulo.wrapping_sub(1), 1],
+        |p, e| p.pow(e - 1) * (p - 1),
+        modulo,
+    )
+}
+
+/// Computes the Mertens function `mu(1) + mu(2) + ... + mu(n)`, where `mu` is the Mobius function.
+/// See [`multiplicative_sum`] for the time complexity.
+///
+/// Example:
+/// ```
+/// use basm_std::math::mertens;
+/// assert_eq!(-1, mertens(10));
+/// assert_eq!(212, mertens(1_000_000));
+/// ```
+pub fn mertens(n: u64) -> i64 {
+    multiplicative_sum(n, &[u64::MAX], |_, e| if e == 1 { u64::MAX } else { 0 }, 0) as i64
+}
+
+/// Computes `sigma_k(1) + sigma_k(2) + ... + sigma_k(n)`, where `sigma_k(x)` is the sum of the `k`-th powers
+/// of the positive divisors of `x`. In particular, `k = 0` counts the divisors. `k` must be at most 3.
+/// See [`multiplicative_sum`] for the time complexity.
+///
+/// The result is computed in modulo `modulo`.
+/// If `modulo` equals 0, it is treated as `2**64`.
+/// Note that `modulo` does not need to be a prime.
+///
+/// Example:
+/// ```
+/// use basm_std::math::divisor_sum;
+/// assert_eq!(27, divisor_sum(10, 0, 0));
+/// assert_eq!(87, divisor_sum(10, 1, 0));
+/// ```
+pub fn divisor_sum(n: u64, k: u32, modulo: u64) -> u64 {
+    assert!(k <= 3);
+    let mut prime_poly = vec![0; k as usize + 1];
+    prime_poly[0] += 1;
+    prime_poly[k as usize] += 1;
+    let ops = FastModOps::new(modulo);
+    multiplicative_sum(
+        n,
+        &prime_poly,
+        |p, e| sigma_prime_power(p, e, k, &ops),
+        modulo,
+    )
+}
+
+/* sigma_k(p**e) = 1 + p**k + ... + p**(ek), reducing after each factor since p**k may not fit in u64 */
+fn sigma_prime_power(p: u64, e: u32, k: u32, ops: &FastModOps) -> u64 {
+    let one = ops.canonicalize(1);
+    let pk = (0..k).fold(one, |acc, _| ops.mul(acc, ops.canonicalize(p)));
+    (0..e).fold(one, |acc, _| ops.fmadd(acc, pk, one))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn brute_sums(n: usize) -> Vec<[u64; 7]> {
+        /* prefix sums of (pi, sum of primes, phi, mu, sigma_0, sigma_2, sigma_3) */
+        let mut ls = LinearSieve::new();
+        let mut out = vec![[0u64; 7]; n + 1];
+        for x in 1..=n {
+            let mut row = out[x - 1];
+            if ls.is_prime(x) {
+                row[0] += 1;
+                row[1] += x as u64;
+            }
+            row[2] += ls.phi(x) as u64;
+            row[3] = row[3].wrapping_add(ls.mu(x) as i64 as u64);
+            let divs = ls.divisors(x);
+            row[4] += divs.len() as u64;
+            row[5] += divs.iter().map(|&d| (d * d) as u64).sum::<u64>();
+            row[6] += divs.iter().map(|&d| (d as u64).pow(3)).sum::<u64>();
+            out[x] = row;
+        }
+        out
+    }
+
+    #[test]
+    fn check_prime_count_small() {
+        let sums = brute_sums(2000);
+        for n in 0..=2000u64 {
+            let row = sums[n as usize];
+            assert_eq!(row[0], prime_count(n), "{n}");
+            assert_eq!(row[1] as u128, prime_sum(n), "{n}");
+            assert_eq!(row[2], totient_sum(n, 0), "{n}");
+            assert_eq!(row[3] as i64, mertens(n), "{n}");
+            assert_eq!(row[4], divisor_sum(n, 0, 0), "{n}");
+            assert_eq!(row[5] % 1_000_007, divisor_sum(n, 2, 1_000_007), "{n}");
+            assert_eq!(row[6] % 998_244_353, divisor_sum(n, 3, 998_244_353), "{n}");
+        }
+    }
+
+    #[test]
+    fn check_prime_count_large() {
+        assert_eq!(455_052_511, prime_count(10_000_000_000));
+        assert_eq!(3_203_324_994_356, prime_sum(10_000_000));
+        assert_eq!(1037, mertens(10_000_000));
+    }
+
+    #[test]
+    fn check_sigma_prime_power_large() {
+        /* p**3 overflows u64 once p > 2642245 */
+        let (p, modulo) = (4_194_319u64, 1_000_000_007u64);
+        let ops = FastModOps::new(modulo);
+        let p3 = (p as u128).pow(3) % modulo as u128;
+        let (mut expected, mut term) = (1, 1);
+        for e in 0..4 {
+            assert_eq!(expected as u64, sigma_prime_power(p, e, 3, &ops));
+            term = term * p3 % modulo as u128;
+            expected = (expected + term) % modulo as u128;
+        }
+    }
+}
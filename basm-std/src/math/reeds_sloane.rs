@@ -1,4 +1,4 @@
-use crate::math::{factorize, modadd, modinv, modmul, modsub};
+use crate::math::{factorize, linear_nth, modadd, modinv, modmul, modsub};
 use alloc::{vec, vec::Vec};
 use core::cmp::max;
 
@@ -159,6 +159,10 @@ fn reeds_sloane_prime_power(first_terms: &[u64], p: u64, e: usize) -> Vec<u64> {
 /// Finds a minimal length linear recurrence for `first_terms`
 /// under modulo `modulo`, via the Reeds-Sloane algorithm.
 ///
+/// The output `coeff` follows the convention of [`linear_nth`], so that
+/// `linear_nth(&first_terms[..coeff.len()], &coeff, n, modulo)` extends the sequence.
+/// See also [`linear_guess_nth`] which does exactly this.
+///
 /// Note that `modulo` of `0` is interpreted as `2**64`.
 pub fn reeds_sloane(first_terms: &[u64], modulo: u64) -> Vec<u64> {
     if first_terms.len() <= 1 {
@@ -232,6 +236,28 @@ pub fn linear_fit(first_terms: &[u64], modulo: u64) -> Vec<u64> {
     reeds_sloane(first_terms, modulo)
 }
 
+/// Guesses the `n`-th term of a sequence from its first terms.
+///
+/// Finds a minimal length linear recurrence for `first_terms` via [`reeds_sloane`],
+/// and then computes the `n`-th term of the recurrence via [`linear_nth`].
+/// For the guess to be correct, `first_terms` should contain at least twice as many terms
+/// as the order of the recurrence.
+///
+/// Note that `modulo` of `0` is interpreted as `2**64`.
+pub fn linear_guess_nth(first_terms: &[u64], n: u128, modulo: u64) -> u64 {
+    let coeff = reeds_sloane(first_terms, modulo);
+    if coeff.is_empty() {
+        // The sequence is too short to extrapolate; we only return the known terms.
+        let x = if n < first_terms.len() as u128 {
+            first_terms[n as usize]
+        } else {
+            0
+        };
+        return if modulo == 0 { x } else { x % modulo };
+    }
+    linear_nth(&first_terms[..coeff.len()], &coeff, n, modulo)
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -283,4 +309,23 @@ mod test {
         let coeff = linear_fit(&first_terms, modulo);
         assert!(coeff == vec![0, 0, 0, 0, 0]);
     }
+
+    #[test]
+    fn check_linear_guess_nth() {
+        // sum of squares of Fibonacci numbers: F(n) * F(n+1)
+        let mut first_terms = vec![0u64];
+        let (mut a, mut b) = (0u64, 1u64);
+        for _ in 0..10 {
+            (a, b) = (b, a + b);
+            first_terms.push(first_terms.last().unwrap() + a * a);
+        }
+        // F(80) * F(81) mod 1e9+7
+        let (f80, f81) = (23416728348467685u64, 37889062373143906u64);
+        let modulo = 1_000_000_007;
+        let expected = modmul(f80 % modulo, f81 % modulo, modulo);
+        assert_eq!(expected, linear_guess_nth(&first_terms, 80, modulo));
+        assert_eq!(f80.wrapping_mul(f81), linear_guess_nth(&first_terms, 80, 0));
+        assert_eq!(7, linear_guess_nth(&[7], 0, modulo));
+        assert_eq!(0, linear_guess_nth(&[5], 1u128 << 64, 0));
+    }
 }
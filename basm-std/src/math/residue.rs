@@ -0,0 +1,331 @@
+use super::miller_rabin::{M, OddMont};
+use super::{factorize_dedup, gcd, modinv, modmul};
+use alloc::vec::Vec;
+use core::cmp::min;
+
+/// Finds the smallest `x` in `[0, bound]` such that `base**x == target` by the baby-step giant-step algorithm,
+/// where `base` must be invertible. Values are in an arbitrary representation multiplied by `mul`, with `one` as the identity.
+fn bsgs(
+    base: u64,
+    target: u64,
+    one: u64,
+    bound: u64,
+    mul: impl Fn(u64, u64) -> u64,
+) -> Option<u64> {
+    if target == one {
+        return Some(0);
+    }
+    let n = bound.isqrt() + 1;
+    /* baby steps: target * base**j for 0 <= j < n */
+    let mut baby = Vec::with_capacity(n as usize);
+    let mut cur = target;
+    for j in 0..n {
+        baby.push((cur, j));
+        cur = mul(cur, base);
+    }
+    baby.sort_unstable();
+    /* giant steps: base**(i*n) == target * base**j yields x = i*n - j, which is increasing in i */
+    let giant = (0..n).fold(one, |acc, _| mul(acc, base));
+    let mut cur = one;
+    for i in 1..=n {
+        cur = mul(cur, giant);
+        let pos = baby.partition_point(|&(v, _)| v <= cur);
+        if pos > 0 && baby[pos - 1].0 == cur {
+            let x = i * n - baby[pos - 1].1;
+            return if x <= bound { Some(x) } else { None };
+        }
+    }
+    None
+}
+
+/// Computes the smallest nonnegative integer `x` such that `a**x === b (mod m)`,
+/// by the baby-step giant-step algorithm in `O(sqrt(m) lg m)` time.
+/// `a` and `m` need not be coprime.
+///
+/// If such `x` exists, the result is wrapped in `Some`. Otherwise, the result is `None`.
+/// Note that `0**0` is treated as 1.
+///
+/// This function will panic if `m` is zero.
+///
+/// Example:
+/// ```
+/// use basm_std::math::discrete_log;
+/// assert_eq!(Some(0), discrete_log(3, 1, 7));
+/// assert_eq!(Some(3), discrete_log(3, 6, 7));
+/// assert_eq!(Some(3), discrete_log(2, 8, 24));
+/// assert_eq!(None, discrete_log(2, 3, 24));
+/// ```
+pub fn discrete_log(a: u64, b: u64, m: u64) -> Option<u64> {
+    assert!(m != 0);
+    let (a, mut b, mut m) = (a % m, b % m, m);
+    /* Peel off the common factors of a and m: a**x === b (mod m) with x >= 1 becomes
+    (a/g) * a**(x-1) === b/g (mod m/g), where g = gcd(a, m). */
+    let (mut cur, mut k) = (1 % m, 0);
+    loop {
+        if b == cur {
+            return Some(k);
+        }
+        let g = gcd(a, m);
+        if g == 1 {
+            break;
+        }
+        if b % g != 0 {
+            return None;
+        }
+        (b, m, k) = (b / g, m / g, k + 1);
+        cur = modmul(cur, a / g, m);
+    }
+    /* Now cur * a**y === b (mod m) with a, cur coprime to m */
+    let target = modmul(b, modinv(cur, m)?, m);
+    let y = if m % 2 == 1 {
+        let mont = OddMont::<u64>::new(m);
+        let mul = |x, y| mont.mul(M { v: x }, M { v: y }).v;
+        bsgs(
+            mont.to_mont(a % m).v,
+            mont.to_mont(target).v,
+            mont.one().v,
+            m,
+            mul,
+        )
+    } else {
+        bsgs(a % m, target, 1, m, |x, y| modmul(x, y, m))
+    };
+    y.map(|y| k + y)
+}
+
+/// Returns the smallest primitive root modulo a prime `p`.
+///
+/// The time complexity is dominated by factorizing `p - 1`. The result is meaningless if `p` is not a prime.
+///
+/// Example:
+/// ```
+/// use basm_std::math::primitive_root;
+/// assert_eq!(3, primitive_root(998_244_353));
+/// assert_eq!(5, primitive_root(1_000_000_007));
+/// ```
+pub fn primitive_root(p: u64) -> u64 {
+    debug_assert!(super::is_prime_u64(p));
+    if p == 2 {
+        return 1;
+    }
+    let mont = OddMont::<u64>::new(p);
+    let factors = factorize_dedup(p - 1);
+    (2..p)
+        .find(|&g| {
+            let g = mont.to_mont(g);
+            factors
+                .iter()
+                .all(|&(q, _)| mont.pow(g, (p - 1) / q).v != mont.one().v)
+        })
+        .unwrap()
+}
+
+/// Computes a square root of `a` modulo a prime `p` by the Tonelli-Shanks algorithm in `O(lg^2 p)` time.
+///
+/// If `a` is a quadratic residue, the smaller of its two square roots is returned wrapped in `Some`.
+/// Otherwise, the result is `None`. The result is meaningless if `p` is not a prime.
+///
+/// Example:
+/// ```
+/// use basm_std::math::sqrt_mod;
+/// assert_eq!(Some(2), sqrt_mod(4, 7));
+/// assert_eq!(Some(3), sqrt_mod(2, 7));
+/// assert_eq!(None, sqrt_mod(5, 998_244_353));
+/// assert_eq!(None, sqrt_mod(3, 7));
+/// ```
+pub fn sqrt_mod(a: u64, p: u64) -> Option<u64> {
+    let a = a % p;
+    if a < 2 || p == 2 {
+        return Some(a);
+    }
+    let mont = OddMont::<u64>::new(p);
+    let (one, minus_one) = (mont.one(), mont.to_mont(p - 1));
+    let am = mont.to_mont(a);
+    if mont.pow(am, (p - 1) / 2).v != one.v {
+        return None;
+    }
+    let s = (p - 1).trailing_zeros();
+    let q = (p - 1) >> s;
+    let z = (2..p)
+        .map(|z| mont.to_mont(z))
+        .find(|&z| mont.pow(z, (p - 1) / 2).v == minus_one.v)
+        .unwrap();
+    let (mut m, mut c) = (s, mont.pow(z, q));
+    let (mut t, mut r) = (mont.pow(am, q), mont.pow(am, q.div_ceil(2)));
+    while t.v != one.v {
+        let mut i = 0;
+        let mut t2 = t;
+        while t2.v != one.v {
+            t2 = mont.mul(t2, t2);
+            i += 1;
+        }
+        let b = mont.pow(c, 1u64 << (m - i - 1));
+        (m, c) = (i, mont.mul(b, b));
+        (t, r) = (mont.mul(t, c), mont.mul(r, b));
+    }
+    let r = mont.from_mont(r);
+    Some(min(r, p - r))
+}
+
+/// Computes `x` such that `x**(q**e) == a`, where `q` is a prime and `a` is a `q**e`-th power modulo the prime `p`.
+///
+/// With `p - 1 = q**t * s` and `gcd(q, s) = 1`, `a**alpha` for `q**e * alpha === 1 (mod s)` is correct up to
+/// an error in the Sylow `q`-subgroup, which is removed by the Pohlig-Hellman algorithm in `O(t sqrt(q) lg q)` time.
+fn prime_power_root(mont: &OddMont<u64>, p: u64, a: M<u64>, q: u64, e: u32) -> M<u64> {
+    let (mut s, mut t) = (p - 1, 0);
+    while s % q == 0 {
+        (s, t) = (s / q, t + 1);
+    }
+    let qe = q.pow(e);
+    let alpha = if s == 1 {
+        0
+    } else {
+        modinv(qe % s, s).unwrap()
+    };
+    let x = mont.pow(a, alpha);
+    if t == e {
+        return x;
+    }
+    let inv = |v: M<u64>| mont.pow(v, p - 2);
+    let one = mont.one();
+    /* find y such that h**y = target, where h = c**(q**e) generates the subgroup of order q**(t-e) */
+    let target = mont.mul(a, inv(mont.pow(x, qe)));
+    let c = (2..p)
+        .map(|n| mont.pow(mont.to_mont(n), s))
+        .find(|&c| mont.pow(c, q.pow(t - 1)).v != one.v)
+        .unwrap();
+    let h = mont.pow(c, qe);
+    let m = t - e;
+    let gamma = mont.pow(h, q.pow(m - 1));
+    let mul = |x, y| mont.mul(M { v: x }, M { v: y }).v;
+    let (mut y, mut qk) = (0, 1);
+    for k in 0..m {
+        let d = mont.pow(mont.mul(inv(mont.pow(h, y)), target), q.pow(m - 1 - k));
+        let dk = bsgs(gamma.v, d.v, one.v, q - 1, mul).unwrap();
+        (y, qk) = (y + dk * qk, qk * q);
+    }
+    mont.mul(x, mont.pow(c, y))
+}
+
+/// Computes `x` such that `x**k === a (mod p)`, where `p` is a prime.
+///
+/// If such `x` exists, one of them is returned wrapped in `Some`. Otherwise, the result is `None`.
+/// Note that `0**0` is treated as 1.
+///
+/// The time complexity is `O(sqrt(q) lg^2 p)` plus the time for factorizing `gcd(k, p - 1)`,
+/// where `q` is the largest prime factor of `gcd(k, p - 1)` (Adleman-Manders-Miller algorithm).
+/// The result is meaningless if `p` is not a prime.
+///
+/// Example:
+/// ```
+/// use basm_std::math::{kth_root_mod, modpow};
+/// let p = 998_244_353;
+/// let x = kth_root_mod(5, 3, p).unwrap();
+/// assert_eq!(5, modpow(x, 3, p).unwrap());
+/// assert_eq!(None, kth_root_mod(3, 2, p));
+/// ```
+pub fn kth_root_mod(a: u64, k: u64, p: u64) -> Option<u64> {
+    let a = a % p;
+    if k == 0 {
+        return (a == 1).then_some(1);
+    }
+    if a < 2 || p == 2 {
+        return Some(a);
+    }
+    let mont = OddMont::<u64>::new(p);
+    let g = gcd(k, p - 1);
+    let mut x = mont.to_mont(a);
+    if mont.pow(x, (p - 1) / g).v != mont.one().v {
+        return None;
+    }
+    /* x**g == a**(1/(k/g)) implies x**k == a, since a lies in the subgroup of order (p-1)/g */
+    let order = (p - 1) / g;
+    let e = if order == 1 {
+        0
+    } else {
+        modinv((k / g) % order, order).unwrap()
+    };
+    x = mont.pow(x, e);
+    for (q, e) in factorize_dedup(g) {
+        x = prime_power_root(&mont, p, x, q, e);
+    }
+    Some(mont.from_mont(x))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::math::{is_prime_u64, modpow};
+
+    #[test]
+    fn check_discrete_log() {
+        for m in 1..60u64 {
+            for a in 0..m {
+                let mut first = alloc::vec![None; m as usize];
+                let mut cur = 1 % m;
+                for x in 0..2 * m {
+                    first[cur as usize].get_or_insert(x);
+                    cur = cur * a % m;
+                }
+                for b in 0..m {
+                    assert_eq!(first[b as usize], discrete_log(a, b, m), "{a} {b} {m}");
+                }
+            }
+        }
+        let p = 1_000_000_007;
+        assert_eq!(
+            Some(123_456_789),
+            discrete_log(5, modpow(5, 123_456_789, p).unwrap(), p)
+        );
+        assert_eq!(Some(0), discrete_log(u64::MAX, 1, 1 << 40));
+    }
+
+    #[test]
+    fn check_primitive_root() {
+        for p in (2..2000u64).filter(|&p| is_prime_u64(p)) {
+            let g = primitive_root(p);
+            let mut cur = 1;
+            for i in 1..p - 1 {
+                cur = cur * g % p;
+                assert!(cur != 1, "{g} {p} {i}");
+            }
+        }
+        assert_eq!(3, primitive_root(167_772_161));
+    }
+
+    #[test]
+    fn check_sqrt_mod() {
+        for p in [2u64, 3, 5, 13, 17, 97, 257, 998_244_353, 1_000_000_007] {
+            for a in 0..min(p, 300) {
+                match sqrt_mod(a, p) {
+                    Some(r) => assert_eq!(a, modmul(r, r, p)),
+                    None => assert_eq!(Some(p - 1), modpow(a, (p - 1) / 2, p)),
+                }
+            }
+        }
+        let p = 18_446_744_073_709_551_557; // the largest prime below 2**64
+        let r = sqrt_mod(p - 4, p).unwrap();
+        assert_eq!(p - 4, modmul(r, r, p));
+    }
+
+    #[test]
+    fn check_kth_root_mod() {
+        for p in [2u64, 3, 7, 13, 17, 97, 101, 1009] {
+            for k in 0..30 {
+                let powers: Vec<u64> = (0..p).map(|x| modpow(x, k, p).unwrap()).collect();
+                for a in 0..p {
+                    match kth_root_mod(a, k, p) {
+                        Some(x) => assert_eq!(a, powers[x as usize], "{a} {k} {p}"),
+                        None => assert!(!powers.contains(&a), "{a} {k} {p}"),
+                    }
+                }
+            }
+        }
+        let p = 998_244_353; // 2**23 * 7 * 17 + 1
+        for k in [2u64, 1 << 20, 7 << 23, 119, 1_000_000_000_000] {
+            let a = modpow(12345, k, p).unwrap();
+            let x = kth_root_mod(a, k, p).unwrap();
+            assert_eq!(a, modpow(x, k, p).unwrap());
+        }
+    }
+}
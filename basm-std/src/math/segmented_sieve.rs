@@ -0,0 +1,247 @@
+use alloc::{vec, vec::Vec};
+
+/// Number of 64-bit words in a segment (32 KiB, which fits in the L1 cache).
+const SEGMENT_WORDS: usize = 1 << 12;
+const SEGMENT_BITS: u64 = (SEGMENT_WORDS * 64) as u64;
+/// Primes whose multiples are pre-sieved by copying a periodic pattern (wheel) instead of crossing them off one by one.
+const WHEEL_PRIMES: [u64; 4] = [3, 5, 7, 11];
+/// Period of the wheel in words. Since 64 is coprime to `3 * 5 * 7 * 11`, the pattern repeats every `1155` words.
+const WHEEL_WORDS: usize = 3 * 5 * 7 * 11;
+
+/// A segmented sieve of Eratosthenes which enumerates the primes in `[lo, hi)`.
+///
+/// Only odd numbers are stored, one bit each, and the range is processed in cache-sized segments.
+/// Multiples of 3, 5, 7 and 11 are removed by copying a precomputed wheel pattern.
+/// Hence the memory usage is `O(sqrt(hi))` regardless of the length of the range
+/// (well within typical limits even for `hi = 10**10`), and the time complexity is `O(hi lg lg hi)`.
+/// Counting the primes below `10**9` takes about a second.
+///
+/// The primes are produced in ascending order through the [`Iterator`] interface.
+/// [`Iterator::count`] is specialized to count the primes in each segment by popcount,
+/// without visiting them one by one.
+///
+/// Example:
+/// ```
+/// use basm_std::math::SegmentedSieve;
+/// let primes: Vec<u64> = SegmentedSieve::new(90, 110).collect();
+/// assert_eq!(vec![97, 101, 103, 107, 109], primes);
+/// assert_eq!(78_498, SegmentedSieve::new(0, 1_000_000).count());
+/// ```
+pub struct SegmentedSieve {
+    hi: u64,
+    /// Odd primes up to `sqrt(hi)`, and the next odd multiple of each to be crossed off.
+    base_primes: Vec<u64>,
+    next_multiple: Vec<u64>,
+    /// Bit `k` of the wheel is set if and only if `2 * k + 1` is coprime to `WHEEL_PRIMES`.
+    wheel: Vec<u64>,
+    /// Bit `i` of the segment represents `seg_lo + 2 * i`.
+    seg_lo: u64,
+    seg: Vec<u64>,
+    word_idx: usize,
+    word: u64,
+    emit_two: bool,
+}
+
+impl SegmentedSieve {
+    /// Creates a sieve which enumerates the primes in `[lo, hi)`.
+    pub fn new(lo: u64, hi: u64) -> Self {
+        let hi = hi.max(lo);
+        let emit_two = lo <= 2 && 2 < hi;
+        /* the first odd number in range, but not 1 */
+        let odd_lo = (lo.max(3)) | 1;
+        let mut base_primes = Self::small_odd_primes(hi.saturating_sub(1).isqrt());
+        base_primes.retain(|p| !WHEEL_PRIMES.contains(p));
+        let mut wheel = vec![!0u64; WHEEL_WORDS];
+        for p in WHEEL_PRIMES {
+            /* 2 * k + 1 is a multiple of p if and only if k === (p - 1) / 2 (mod p) */
+            for k in ((p as usize - 1) / 2..WHEEL_WORDS * 64).step_by(p as usize) {
+                wheel[k / 64] &= !(1u64 << (k % 64));
+            }
+        }
+        let next_multiple = base_primes
+            .iter()
+            .map(|&p| {
+                let m = (p * p).max(odd_lo.div_ceil(p) * p);
+                if m % 2 == 0 { m + p } else { m }
+            })
+            .collect();
+        let mut out = Self {
+            hi,
+            base_primes,
+            next_multiple,
+            wheel,
+            seg_lo: odd_lo,
+            seg: vec![0; SEGMENT_WORDS],
+            word_idx: 0,
+            word: 0,
+            emit_two,
+        };
+        out.fill_segment();
+        out
+    }
+
+    /// Returns the odd primes less than or equal to `n` by a plain sieve of Eratosthenes.
+    fn small_odd_primes(n: u64) -> Vec<u64> {
+        let n = n as usize;
+        let mut composite = vec![false; n + 1];
+        let mut out = vec![];
+        for i in (3..=n).step_by(2) {
+            if !composite[i] {
+                out.push(i as u64);
+                for j in (i * i..=n).step_by(2 * i) {
+                    composite[j] = true;
+                }
+            }
+        }
+        out
+    }
+
+    /// Sieves the segment starting at `self.seg_lo`.
+    fn fill_segment(&mut self) {
+        self.word_idx = 0;
+        if self.seg_lo >= self.hi {
+            self.seg.fill(0);
+            self.word = 0;
+            return;
+        }
+        let nbits = ((self.hi - self.seg_lo).div_ceil(2)).min(SEGMENT_BITS);
+        let k0 = (self.seg_lo / 2) as usize % (WHEEL_WORDS * 64);
+        let (q, r) = (k0 / 64, k0 % 64);
+        for (w, x) in self.seg.iter_mut().enumerate() {
+            let lo = self.wheel[(q + w) % WHEEL_WORDS];
+            *x = if r == 0 {
+                lo
+            } else {
+                (lo >> r) | (self.wheel[(q + w + 1) % WHEEL_WORDS] << (64 - r))
+            };
+        }
+        for p in WHEEL_PRIMES {
+            if self.seg_lo <= p && p < self.hi {
+                let j = (p - self.seg_lo) / 2;
+                self.seg[(j / 64) as usize] |= 1u64 << (j % 64);
+            }
+        }
+        let full_words = (nbits / 64) as usize;
+        if full_words < SEGMENT_WORDS {
+            self.seg[full_words] &= (1u64 << (nbits % 64)) - 1;
+            self.seg[full_words + 1..].fill(0);
+        }
+        let seg_hi = self.seg_lo + 2 * nbits;
+        for (&p, next) in self.base_primes.iter().zip(self.next_multiple.iter_mut()) {
+            if p * p >= seg_hi {
+                break;
+            }
+            let mut j = (*next - self.seg_lo) / 2;
+            while j < nbits {
+                self.seg[(j / 64) as usize] &= !(1u64 << (j % 64));
+                j += p;
+            }
+            *next = self.seg_lo + 2 * j;
+        }
+        self.word = self.seg[0];
+    }
+
+    fn advance_segment(&mut self) {
+        self.seg_lo = self.seg_lo.saturating_add(2 * SEGMENT_BITS);
+        self.fill_segment();
+    }
+}
+
+impl Iterator for SegmentedSieve {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        if self.emit_two {
+            self.emit_two = false;
+            return Some(2);
+        }
+        loop {
+            if self.word != 0 {
+                let t = self.word.trailing_zeros() as u64;
+                self.word &= self.word - 1;
+                return Some(self.seg_lo + 2 * (self.word_idx as u64 * 64 + t));
+            }
+            if self.seg_lo >= self.hi {
+                return None;
+            }
+            self.word_idx += 1;
+            if self.word_idx == SEGMENT_WORDS {
+                self.advance_segment();
+            } else {
+                self.word = self.seg[self.word_idx];
+            }
+        }
+    }
+
+    fn count(mut self) -> usize {
+        let mut out = self.emit_two as usize + self.word.count_ones() as usize;
+        out += self.seg[self.word_idx + 1..]
+            .iter()
+            .map(|w| w.count_ones() as usize)
+            .sum::<usize>();
+        while self.seg_lo < self.hi {
+            self.advance_segment();
+            out += self
+                .seg
+                .iter()
+                .map(|w| w.count_ones() as usize)
+                .sum::<usize>();
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::math::{LinearSieve, is_prime_u64};
+
+    #[test]
+    fn check_segmented_sieve() {
+        let mut ls = LinearSieve::new();
+        let n = 4 * SEGMENT_BITS as usize + 12345;
+        let expected: Vec<u64> = ls.primes_upto(n).iter().map(|&p| p as u64).collect();
+        for (lo, hi) in [
+            (0, 0),
+            (0, 2),
+            (0, 3),
+            (2, 3),
+            (3, 3),
+            (1, 10),
+            (4, 5),
+            (0, n as u64 + 1),
+        ] {
+            let primes: Vec<u64> = SegmentedSieve::new(lo, hi).collect();
+            let range: Vec<u64> = expected
+                .iter()
+                .copied()
+                .filter(|&p| lo <= p && p < hi)
+                .collect();
+            assert_eq!(range, primes, "{lo} {hi}");
+            assert_eq!(range.len(), SegmentedSieve::new(lo, hi).count());
+        }
+        for (lo, hi) in [(100_000, 400_000), (262_143, 1_000_003), (500_000, 500_100)] {
+            let range: Vec<u64> = expected
+                .iter()
+                .copied()
+                .filter(|&p| lo <= p && p < hi)
+                .collect();
+            assert_eq!(range, SegmentedSieve::new(lo, hi).collect::<Vec<u64>>());
+            /* count after partially consuming the iterator */
+            let mut it = SegmentedSieve::new(lo, hi);
+            it.next();
+            assert_eq!(range.len() - 1, it.count());
+        }
+    }
+
+    #[test]
+    fn check_segmented_sieve_large() {
+        assert_eq!(664_579, SegmentedSieve::new(0, 10_000_000).count());
+        let lo = 1_000_000_000_000;
+        let expected: Vec<u64> = (lo..lo + 1000).filter(|&x| is_prime_u64(x)).collect();
+        assert_eq!(
+            expected,
+            SegmentedSieve::new(lo, lo + 1000).collect::<Vec<u64>>()
+        );
+    }
+}
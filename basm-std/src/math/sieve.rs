@@ -52,6 +52,13 @@ impl LinearSieve {
         self.primes[n - 1]
     }
 
+    /// Returns all primes less than or equal to `x`, in ascending order.
+    pub fn primes_upto(&mut self, x: usize) -> &[usize] {
+        self.ensure_upto(x);
+        let len = self.primes.partition_point(|&p| p <= x);
+        &self.primes[..len]
+    }
+
     /// Mobius function.
     pub fn mu(&mut self, x: usize) -> i8 {
         assert!(x >= 1);
@@ -239,6 +246,15 @@ mod test {
         assert!(ls.is_prime(19));
     }
 
+    #[test]
+    fn check_primes_upto() {
+        let mut ls = LinearSieve::new();
+        assert_eq!(&[] as &[usize], ls.primes_upto(1));
+        assert_eq!(&[2, 3, 5, 7, 11, 13, 17, 19], ls.primes_upto(19));
+        assert_eq!(1229, ls.primes_upto(10000).len());
+        assert_eq!(&[2, 3, 5, 7], ls.primes_upto(10));
+    }
+
     #[test]
     fn check_mu() {
         let mut ls = LinearSieve::new();